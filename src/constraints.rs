@@ -0,0 +1,71 @@
+use crate::solver::{Solver, literal::Literal};
+
+/// Up to this many literals, `add_at_most_one` uses the pairwise encoding
+const PAIRWISE_AT_MOST_ONE_LIMIT: usize = 5;
+
+/// Adds a clause on the top level; returns `false` if the instance is UNSAT
+fn add_clause(solver: &mut Solver, literals: &[Literal]) -> bool {
+    let lits: Vec<i32> = literals.iter().map(Literal::to_dimacs).collect();
+    solver.add_clause_ints(&lits)
+}
+
+/// Constrains at most `k` of `lits` to be true using the sequential counter
+/// encoding, which introduces `(lits.len() - 1) * k` auxiliary variables
+/// after the existing ones and those in `lits`; returns `false` if the instance is found to be UNSAT
+///
+/// The auxiliary variable of the `i`-th literal and count `j` is true if at
+/// least `j + 1` of the first `i + 1` literals are true.
+pub fn add_at_most_k(solver: &mut Solver, lits: &[Literal], k: usize) -> bool {
+    let n = lits.len();
+    if k >= n {
+        return true;
+    }
+    if k == 0 {
+        return lits.iter().all(|&lit| add_clause(solver, &[!lit]));
+    }
+
+    // Counter literal of literal `i < n - 1` and count `j < k`; counters come
+    // after all variables, including those in `lits` not created yet
+    let base = lits
+        .iter()
+        .map(|lit| lit.var() + 1)
+        .fold(solver.num_variables(), usize::max);
+    let counter = |i: usize, j: usize| Literal::from_var_with_polarity(base + i * k + j, true);
+
+    // The first literal sets the first count
+    let mut ok = add_clause(solver, &[!lits[0], counter(0, 0)]);
+    for j in 1..k {
+        ok = ok && add_clause(solver, &[!counter(0, j)]);
+    }
+
+    // Each further literal increments the count, which must not exceed `k`
+    for (i, &lit) in lits.iter().enumerate().take(n - 1).skip(1) {
+        ok = ok
+            && add_clause(solver, &[!lit, counter(i, 0)])
+            && add_clause(solver, &[!counter(i - 1, 0), counter(i, 0)]);
+        for j in 1..k {
+            ok = ok
+                && add_clause(solver, &[!lit, !counter(i - 1, j - 1), counter(i, j)])
+                && add_clause(solver, &[!counter(i - 1, j), counter(i, j)]);
+        }
+        ok = ok && add_clause(solver, &[!lit, !counter(i - 1, k - 1)]);
+    }
+    ok && add_clause(solver, &[!lits[n - 1], !counter(n - 2, k - 1)])
+}
+
+/// Constrains at most one of `lits` to be true; uses the pairwise encoding
+/// for few literals and the sequential counter (ladder) encoding otherwise;
+/// returns `false` if the instance is found to be UNSAT
+pub fn add_at_most_one(solver: &mut Solver, lits: &[Literal]) -> bool {
+    if lits.len() > PAIRWISE_AT_MOST_ONE_LIMIT {
+        return add_at_most_k(solver, lits, 1);
+    }
+    for (i, &first) in lits.iter().enumerate() {
+        for &second in &lits[i + 1..] {
+            if !add_clause(solver, &[!first, !second]) {
+                return false;
+            }
+        }
+    }
+    true
+}
@@ -0,0 +1,67 @@
+use crate::{
+    parsing::ClauseReceiver,
+    solver::{Solver, VerbosityLevel, literal::Literal},
+};
+
+/// Collects the clauses of a problem instance in code, as an alternative to
+/// parsing a CNF file
+///
+/// Literals are DIMACS-style signed integers, where `n` and `-n` are the
+/// positive and negative literals of the `n`-th variable.
+#[derive(Clone, Debug, Default)]
+pub struct FormulaBuilder {
+    /// Clauses in the order they were added
+    clauses: Vec<Vec<i32>>,
+    /// Largest variable occurring in any clause
+    num_variables: usize,
+}
+
+impl FormulaBuilder {
+    /// Create an empty formula
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Number of variables, i.e., the largest variable in any clause
+    #[must_use]
+    pub const fn num_variables(&self) -> usize {
+        self.num_variables
+    }
+
+    /// Number of clauses
+    #[must_use]
+    pub const fn num_clauses(&self) -> usize {
+        self.clauses.len()
+    }
+
+    /// Adds a clause; an empty clause makes the formula UNSAT
+    ///
+    /// # Panics
+    ///
+    /// If a literal is `0`, which DIMACS reserves as the clause terminator
+    pub fn add_clause(&mut self, lits: &[i32]) -> &mut Self {
+        assert!(!lits.contains(&0), "0 is not a valid literal");
+        for lit in lits {
+            self.num_variables = self.num_variables.max(lit.unsigned_abs() as usize);
+        }
+        self.clauses.push(lits.to_vec());
+        self
+    }
+
+    /// Creates a solver loaded with the formula; as with a parsed CNF file,
+    /// clauses after one that makes the formula trivially UNSAT are skipped
+    /// and `Solver::solve` reports UNSAT
+    #[must_use]
+    pub fn build(self, logging_level: VerbosityLevel) -> Solver {
+        let mut solver = Solver::new(logging_level);
+        solver.create_variables(self.num_variables);
+        for clause in &self.clauses {
+            let literals: Vec<Literal> = clause.iter().copied().map(Literal::from_dimacs).collect();
+            if !solver.add_clause(&literals) {
+                break;
+            }
+        }
+        solver
+    }
+}
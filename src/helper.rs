@@ -1,3 +1,5 @@
+use std::time::Instant;
+
 /// Truncate `f64` to `usize`
 #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
 pub fn f64_to_usize_trunc(x: f64) -> usize {
@@ -10,3 +12,21 @@ pub fn f64_to_usize_trunc(x: f64) -> usize {
 pub fn usize_to_f64(x: usize) -> f64 {
     x as f64
 }
+
+/// Duration between `start` and `end` in seconds
+#[allow(clippy::cast_precision_loss)]
+pub fn duration_in_seconds(start: Instant, end: Instant) -> f64 {
+    (end - start).as_micros() as f64 / 1e6
+}
+
+/// Reports a line of progress or statistics output to `out`, and also to the
+/// `log` crate at the given level if the `log` feature is enabled; write
+/// errors are ignored since the output is informational only
+macro_rules! report {
+    ($out:expr, $level:ident, $($arg:tt)*) => {{
+        #[cfg(feature = "log")]
+        log::log!(log::Level::$level, "{}", format!($($arg)*).trim_matches('\n'));
+        let _ = writeln!($out, $($arg)*);
+    }};
+}
+pub(crate) use report;
@@ -1,128 +1,556 @@
-use std::time::Instant;
+use std::{
+    cell::RefCell,
+    fs,
+    io::{self, Write},
+    rc::Rc,
+    time::Instant,
+};
 
 use crate::{
-    parsing::parse_cnf,
-    solver::{Solver, SolverExitCode, VerbosityLevel},
+    helper::{duration_in_seconds, report, usize_to_f64},
+    maxsat::MaxSatInstance,
+    parsing::{ClauseList, ClauseReceiver, ClauseStats, parse_assumptions, parse_cnf, parse_wcnf},
+    proof::{ClauseId, ProofStep, ProofWriter, check_drat, verify_model},
+    solver::{
+        RestartWindow, SolveSummary, Solver, SolverExitCode, SolverOptions, VerbosityLevel,
+        literal::{DimacsLiteralError, Literal},
+        variable::VariableValue,
+    },
 };
 
+pub mod constraints;
+pub mod formula;
 mod helper;
+pub mod maxsat;
 pub mod parsing;
+pub mod portfolio;
+pub mod proof;
 pub mod solver;
 
-/// Duration between `start` and `end` in seconds
-#[allow(clippy::cast_precision_loss)]
-fn duration_in_seconds(start: Instant, end: Instant) -> f64 {
-    (end - start).as_micros() as f64 / 1e6
-}
-
 /// Print stats on the loaded CNF instance
-fn print_stats(solver: &Solver, start_time: Instant, parse_end_time: Instant) {
-    print!(
+fn print_stats(
+    out: &mut impl Write,
+    solver: &Solver,
+    start_time: Instant,
+    parse_end_time: Instant,
+) {
+    let histogram = solver.clause_length_histogram();
+    report!(
+        out,
+        Info,
         "
 ============================[ Problem Statistics ]=============================
 |                                                                             |
 |  Number of variables:  {:>12}                                         |
 |  Number of clauses:    {:>12}                                         |
+|    binary:             {:>12}                                         |
+|    ternary:            {:>12}                                         |
+|    longer:             {:>12}                                         |
+|  Random seed:          {:>12}                                         |
 |  Parse time:           {:>12.6}                                         |
 |                                                                             |",
         solver.num_variables(),
         solver.num_clauses(),
+        histogram.get(2).copied().unwrap_or(0),
+        histogram.get(3).copied().unwrap_or(0),
+        histogram.iter().skip(4).sum::<usize>(),
+        solver.options().random_seed,
         duration_in_seconds(start_time, parse_end_time)
     );
 }
 
 /// Print search statistics header
-fn print_search_stats_banner() {
-    print!(
-        "
-============================[ Search Statistics ]==============================
+fn print_search_stats_banner(out: &mut impl Write) {
+    report!(
+        out,
+        Info,
+        "============================[ Search Statistics ]==============================
 | Conflicts |          ORIGINAL         |          LEARNED         | Progress |
 |           |    Vars  Clauses Literals |    Limit  Clauses Lit/Cl |          |
-===============================================================================
-"
+==============================================================================="
     );
 }
 
 /// Print stats after finished with solving
-#[allow(clippy::cast_precision_loss)]
-fn print_post_solve_stats(solver: &Solver, start_time: Instant, end_time: Instant) {
+fn print_post_solve_stats(
+    out: &mut impl Write,
+    solver: &Solver,
+    start_time: Instant,
+    end_time: Instant,
+) {
     let total_time = duration_in_seconds(start_time, end_time);
-    let conflicts_per_s = solver.statistics().num_total_conflicts as f64 / total_time;
-    let propagations_per_s = solver.statistics().num_propagations as f64 / total_time;
-    print!(
+    let summary = SolveSummary::new(*solver.statistics(), total_time);
+    report!(
+        out,
+        Info,
         "============================[      Summary      ]==============================
 |                                                                             |
 |  #Restarts:            {:>12}                                         |
 |  #Conflicts:           {:>12} ({:>12.3}/sec)                      |
 |  #Decisions:           {:>12}                                         |
 |  #Propagations:        {:>12} ({:>12.3}/sec)                      |
+|  #Top-level units:     {:>12}                                         |
+|  Max decision level:   {:>12}                                         |
+|  Max trail length:     {:>12}                                         |
+|  Deleted learned lits: {:>12}                                         |
+|  Memory used:          {:>12.3} MB                                      |
 |  Total time:           {:>12.6}                                         |
 |                                                                             |
-===============================================================================
-",
-        solver.statistics().num_restarts,
-        solver.statistics().num_total_conflicts,
-        conflicts_per_s,
-        solver.statistics().num_decisions,
-        solver.statistics().num_propagations,
-        propagations_per_s,
-        total_time
+===============================================================================",
+        summary.statistics.num_restarts,
+        summary.statistics.num_total_conflicts,
+        summary.conflicts_per_second,
+        summary.statistics.num_decisions,
+        summary.statistics.num_propagations,
+        summary.propagations_per_second,
+        summary.statistics.num_top_level_units,
+        summary.statistics.max_decision_level,
+        summary.statistics.max_trail_length,
+        summary.statistics.num_learned_literals_deleted,
+        usize_to_f64(solver.memory_usage_bytes()) / (1024.0 * 1024.0),
+        summary.total_time
+    );
+}
+
+/// Print minimum, average, and maximum work per restart window
+fn print_restart_windows(out: &mut impl Write, solver: &Solver) {
+    let windows = solver.restart_windows();
+    let spread = |work: &dyn Fn(&RestartWindow) -> usize| {
+        let min = windows.iter().map(work).min().unwrap_or(0);
+        let max = windows.iter().map(work).max().unwrap_or(0);
+        let total: usize = windows.iter().map(work).sum();
+        (
+            min,
+            usize_to_f64(total) / usize_to_f64(windows.len().max(1)),
+            max,
+        )
+    };
+    let (min_conflicts, avg_conflicts, max_conflicts) = spread(&|w| w.num_conflicts);
+    let (min_propagations, avg_propagations, max_propagations) = spread(&|w| w.num_propagations);
+    report!(
+        out,
+        Info,
+        "============================[  Restart Windows  ]==============================
+|                                                                             |
+|  #Windows:             {:>12}                                         |
+|  Conflicts/window:    min {:>10}  avg {:>12.1}  max {:>10}      |
+|  Propagations/window: min {:>10}  avg {:>12.1}  max {:>10}      |
+|                                                                             |",
+        windows.len(),
+        min_conflicts,
+        avg_conflicts,
+        max_conflicts,
+        min_propagations,
+        avg_propagations,
+        max_propagations
     );
 }
 
-/// Print result
-fn print_result(solver: &Solver, exit_code: SolverExitCode) {
-    println!();
+/// Append the literals of all variables in `model` to `result`
+fn push_model(result: &mut String, model: &[VariableValue]) {
+    for (var, val) in model.iter().enumerate() {
+        debug_assert!(val.is_true() || val.is_false());
+        if val.is_true() {
+            result.push_str(&format!(" {}", var + 1));
+        } else {
+            result.push_str(&format!(" -{}", var + 1));
+        }
+    }
+}
+
+/// Append the literals of all variables in `model`, terminated by `0`, to
+/// `result` on `v` lines, each started by a line break; a line takes further
+/// literals while it is at most `max_line_length` long
+fn push_v_lines(result: &mut String, model: &[VariableValue], max_line_length: usize) {
+    let literals = model
+        .iter()
+        .enumerate()
+        .map(|(var, value)| Literal::from_var_with_polarity(var, value.is_true()).to_dimacs());
+    let mut line = String::from("v");
+    for value in literals.chain([0]) {
+        let token = format!(" {value}");
+        if line.len() > 1 && line.len() + token.len() > max_line_length {
+            result.push('\n');
+            result.push_str(&line);
+            line = String::from("v");
+        }
+        line.push_str(&token);
+    }
+    result.push('\n');
+    result.push_str(&line);
+}
+
+/// Format of the model printed after `SAT`
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub enum ModelFormat {
+    /// DIMACS literals on the result line, as in `SAT 1 -2 3`
+    #[default]
+    DimacsInline,
+    /// One DIMACS literal per `v` line after the result line, terminated by `v 0`
+    DimacsVLines,
+    /// One `0` or `1` per variable in the order of the variables on the line
+    /// after the result line
+    BitVector,
+}
+
+/// Append `model` in `format` to the result line in `result`
+fn push_model_in_format(result: &mut String, model: &[VariableValue], format: ModelFormat) {
+    match format {
+        ModelFormat::DimacsInline => push_model(result, model),
+        ModelFormat::DimacsVLines => push_v_lines(result, model, 0),
+        ModelFormat::BitVector => {
+            result.push('\n');
+            result.extend(
+                model
+                    .iter()
+                    .map(|value| if value.is_true() { '1' } else { '0' }),
+            );
+        }
+    }
+}
+
+/// Writes the result, `SAT` with the model in `format`, `UNSAT`, or
+/// `UNKNOWN`, after an empty line
+///
+/// # Errors
+///
+/// If writing to `out` fails
+pub fn write_result(
+    out: &mut impl Write,
+    solver: &Solver,
+    exit_code: SolverExitCode,
+    format: ModelFormat,
+) -> io::Result<()> {
+    let mut result = String::from("\n");
     match exit_code {
         // Unknown
         SolverExitCode::Unknown => {
-            println!("UNKNOWN");
+            result.push_str("UNKNOWN");
         }
         // SAT
         SolverExitCode::Sat => {
-            print!("SAT");
-            for var in 0..solver.model().len() {
-                let val = solver.model()[var];
-                debug_assert!(val.is_true() || val.is_false());
-                if val.is_true() {
-                    print!(" {}", var + 1);
-                } else {
-                    print!(" -{}", var + 1);
-                }
-            }
-            println!();
+            result.push_str("SAT");
+            push_model_in_format(&mut result, solver.model(), format);
         }
         // UNSAT
         SolverExitCode::Unsat => {
-            println!("UNSAT");
+            result.push_str("UNSAT");
         }
     }
+    writeln!(out, "{result}")
+}
+
+/// Print result; write errors are ignored as for the other output
+fn print_result(
+    out: &mut impl Write,
+    solver: &Solver,
+    exit_code: SolverExitCode,
+    format: ModelFormat,
+) {
+    let _ = write_result(out, solver, exit_code, format);
+}
+
+/// Longest `v` line of the competition output, as required by the format
+const COMPETITION_LINE_LENGTH: usize = 78;
+
+/// Print result in the SAT competition format: an `s` line with the status
+/// and, if SAT, the model on `v` lines terminated by `0`
+fn print_competition_result(out: &mut impl Write, solver: &Solver, exit_code: SolverExitCode) {
+    let status = match exit_code {
+        SolverExitCode::Unknown => "UNKNOWN",
+        SolverExitCode::Sat => "SATISFIABLE",
+        SolverExitCode::Unsat => "UNSATISFIABLE",
+    };
+    let mut result = format!("s {status}");
+    if exit_code == SolverExitCode::Sat {
+        push_v_lines(&mut result, solver.model(), COMPETITION_LINE_LENGTH);
+    }
+    let _ = writeln!(out, "{result}");
 }
 
-/// Solves a CNF instance in a `.cnf`, `.cnf.xz`, or `.cnf.gz` file
+/// Solves a CNF instance in a plain, gzip-, xz-, zstd-, or bzip2-compressed
+/// file
 #[must_use]
 pub fn solve_cnf_instance(filename: &str, logging_level: VerbosityLevel) -> SolverExitCode {
+    solve_cnf_instance_to(filename, logging_level, &mut io::stdout())
+}
+
+/// Solves a CNF instance like `solve_cnf_instance`, but with a solver using
+/// `options` and the model printed in `model_format`
+#[must_use]
+pub fn solve_cnf_instance_with_options(
+    filename: &str,
+    logging_level: VerbosityLevel,
+    options: SolverOptions,
+    model_format: ModelFormat,
+) -> SolverExitCode {
+    solve_cnf_instance_with(
+        filename,
+        logging_level,
+        options,
+        model_format,
+        &mut io::stdout(),
+        |solver, out| solver.solve_with_output(out),
+    )
+}
+
+/// Parses a plain or compressed CNF file and prints its variable, clause,
+/// and literal counts without solving; clauses are not retained, so this
+/// suits inspecting huge files
+pub fn print_cnf_statistics(filename: &str) {
+    let start_time = Instant::now();
+    let mut stats = ClauseStats::default();
+    parse_cnf(filename, &mut stats);
+    let _ = writeln!(
+        io::stdout(),
+        "============================[ Problem Statistics ]=============================
+|                                                                             |
+|  Number of variables:  {:>12}                                         |
+|  Max variable:         {:>12}                                         |
+|  Number of clauses:    {:>12}                                         |
+|  Number of literals:   {:>12}                                         |
+|  Parse time:           {:>12.6}                                         |
+|                                                                             |
+===============================================================================",
+        stats.num_variables,
+        stats.max_variable,
+        stats.num_clauses,
+        stats.num_literals,
+        duration_in_seconds(start_time, Instant::now())
+    );
+}
+
+/// Parses and preprocesses a plain or compressed CNF file and prints the
+/// simplified instance in DIMACS CNF format without solving it; returns
+/// `Unsat` if preprocessing finds the instance to be UNSAT and `Unknown`
+/// otherwise
+#[must_use]
+pub fn simplify_cnf_instance(filename: &str) -> SolverExitCode {
+    let mut solver = Solver::new(VerbosityLevel::OnlyResult);
+    parse_cnf(filename, &mut solver);
+    let exit_code = if solver.preprocess() {
+        SolverExitCode::Unknown
+    } else {
+        SolverExitCode::Unsat
+    };
+    let _ = solver.write_simplified_cnf(&mut io::BufWriter::new(io::stdout().lock()));
+    exit_code
+}
+
+/// Solves a plain or compressed CNF file and prints only the result in the
+/// SAT competition format, without banners or statistics, using a solver with
+/// `options`
+#[must_use]
+pub fn solve_cnf_instance_competition(filename: &str, options: SolverOptions) -> SolverExitCode {
+    let mut solver = Solver::with_options(VerbosityLevel::OnlyResult, options);
+    parse_cnf(filename, &mut solver);
+    let exit_code = solver.solve_with_output(&mut io::sink());
+    print_competition_result(&mut io::stdout().lock(), &solver, exit_code);
+    exit_code
+}
+
+/// Reads a model from a file in the solver's output format `SAT 1 -2 ...` or
+/// the competition format with `v` lines; words and `0` are skipped, and all
+/// variables must be below `num_variables`
+fn read_model(filename: &str, num_variables: usize) -> Result<Vec<VariableValue>, String> {
+    let content = fs::read_to_string(filename)
+        .map_err(|_| format!("Failed to read model file {filename:?}"))?;
+    let mut model = vec![VariableValue::Unset; num_variables];
+    for value in content
+        .split_whitespace()
+        .filter_map(|token| token.parse::<i32>().ok())
+    {
+        let literal = match Literal::try_from_dimacs(value) {
+            Ok(literal) if literal.var() < num_variables => literal,
+            Err(DimacsLiteralError::Terminator) => continue,
+            _ => {
+                return Err(format!(
+                    "Literal {value} in model file {filename:?} exceeds the {num_variables} variables of the instance"
+                ));
+            }
+        };
+        model[literal.var()] = VariableValue::from_bool(literal.polarity());
+    }
+    Ok(model)
+}
+
+/// Proof writer collecting the DRAT steps of a solver in memory for the caller
+struct ProofRecorder(Rc<RefCell<Vec<ProofStep>>>);
+
+impl ProofWriter for ProofRecorder {
+    fn add(&mut self, _id: ClauseId, literals: &[Literal], _hints: &[ClauseId]) -> io::Result<()> {
+        self.0.borrow_mut().push(ProofStep::Add(literals.to_vec()));
+        Ok(())
+    }
+
+    fn delete(&mut self, _id: ClauseId, literals: &[Literal]) -> io::Result<()> {
+        self.0
+            .borrow_mut()
+            .push(ProofStep::Delete(literals.to_vec()));
+        Ok(())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+/// Solves a plain or compressed CNF file and checks the result against
+/// `expected`, which is `SAT`, `UNSAT`, or the name of a file with a model;
+/// a model must satisfy the instance, which is then expected to be SAT; every
+/// model found by the solver is checked against all parsed clauses, and every
+/// UNSAT result by checking the DRAT proof of the solver with `check_drat`
+///
+/// The proof checker propagates without watched literals, so verifying UNSAT
+/// results of large instances is slow.
+///
+/// # Errors
+///
+/// Describes the mismatch between the expected and the actual result
+pub fn verify_cnf_instance(filename: &str, expected: &str) -> Result<(), String> {
+    let mut instance = ClauseList::default();
+    parse_cnf(filename, &mut instance);
+    let expected_exit_code = match expected {
+        "UNSAT" => SolverExitCode::Unsat,
+        "SAT" => SolverExitCode::Sat,
+        model_file => {
+            let model = read_model(model_file, instance.num_variables)?;
+            if !verify_model(&instance.clauses, &model) {
+                return Err(format!(
+                    "Model in {model_file:?} does not satisfy all clauses"
+                ));
+            }
+            SolverExitCode::Sat
+        }
+    };
+
+    // Solve the parsed clauses while recording a proof
+    let proof = Rc::new(RefCell::new(Vec::new()));
+    let mut solver = Solver::new(VerbosityLevel::OnlyResult);
+    solver.set_proof_writer(ProofRecorder(Rc::clone(&proof)), instance.clauses.len());
+    solver.create_variables(instance.num_variables);
+    for clause in &instance.clauses {
+        solver.add_clause(clause);
+    }
+    let exit_code = solver.solve_with_output(&mut io::sink());
+    if exit_code != expected_exit_code {
+        return Err(format!(
+            "Expected {expected_exit_code:?}, but the solver returned {exit_code:?}"
+        ));
+    }
+    match exit_code {
+        SolverExitCode::Sat if !verify_model(&instance.clauses, solver.model()) => Err(
+            String::from("Model found by the solver does not satisfy all clauses"),
+        ),
+        SolverExitCode::Unsat if !check_drat(&instance.clauses, &proof.borrow()) => Err(
+            String::from("Proof found by the solver does not refute the clauses"),
+        ),
+        _ => Ok(()),
+    }
+}
+
+/// Solves a weighted partial MaxSAT instance in a plain or compressed WCNF
+/// file; prints `OPTIMUM` with the minimum cost and an optimal model, or
+/// `UNSAT` if the hard clauses are unsatisfiable
+#[must_use]
+pub fn solve_wcnf_instance(filename: &str) -> SolverExitCode {
+    let mut instance = MaxSatInstance::new();
+    parse_wcnf(filename, &mut instance);
+    let (exit_code, result) = match instance.solve() {
+        Some(solution) => {
+            let mut result = format!("\nOPTIMUM {}", solution.cost);
+            push_model(&mut result, &solution.model);
+            (SolverExitCode::Sat, result)
+        }
+        None => (SolverExitCode::Unsat, String::from("\nUNSAT")),
+    };
+    let _ = writeln!(io::stdout(), "{result}");
+    exit_code
+}
+
+/// Solves a CNF instance like `solve_cnf_instance`, but writes all output to
+/// `out`; with the `log` feature, statistics and progress also go to the
+/// `log` crate
+#[must_use]
+pub fn solve_cnf_instance_to(
+    filename: &str,
+    logging_level: VerbosityLevel,
+    out: &mut impl Write,
+) -> SolverExitCode {
+    solve_cnf_instance_with(
+        filename,
+        logging_level,
+        SolverOptions::default(),
+        ModelFormat::default(),
+        out,
+        |solver, out| solver.solve_with_output(out),
+    )
+}
+
+/// Solves a CNF instance like `solve_cnf_instance`, but under the assumptions
+/// in the plain or compressed file `assumptions_filename` and with `options`
+/// and `model_format` as in `solve_cnf_instance_with_options`; UNSAT means
+/// that the instance has no model satisfying all assumptions
+#[must_use]
+pub fn solve_cnf_instance_with_assumptions(
+    filename: &str,
+    assumptions_filename: &str,
+    logging_level: VerbosityLevel,
+    options: SolverOptions,
+    model_format: ModelFormat,
+) -> SolverExitCode {
+    solve_cnf_instance_with(
+        filename,
+        logging_level,
+        options,
+        model_format,
+        &mut io::stdout(),
+        |solver, out| {
+            for assumption in parse_assumptions(assumptions_filename, solver.num_variables()) {
+                solver.push_assumption(assumption);
+            }
+            solver.solve_with_output(out)
+        },
+    )
+}
+
+/// Parses a CNF instance into a solver with `options`, solves it with
+/// `solve`, and reports the result and statistics to `out`, with the model in
+/// `model_format`
+fn solve_cnf_instance_with<W: Write>(
+    filename: &str,
+    logging_level: VerbosityLevel,
+    options: SolverOptions,
+    model_format: ModelFormat,
+    out: &mut W,
+    solve: impl FnOnce(&mut Solver, &mut W) -> SolverExitCode,
+) -> SolverExitCode {
     // Create solver and parse clauses
     let start_time = Instant::now();
-    let mut solver = Solver::new(logging_level);
+    let mut solver = Solver::with_options(logging_level, options);
     parse_cnf(filename, &mut solver);
-    if logging_level == VerbosityLevel::All {
+    if logging_level >= VerbosityLevel::Summary {
         let parse_end_time = Instant::now();
-        print_stats(&solver, start_time, parse_end_time);
-        print_search_stats_banner();
+        print_stats(out, &solver, start_time, parse_end_time);
+    }
+    if logging_level == VerbosityLevel::All {
+        print_search_stats_banner(out);
     }
 
     // Solve
-    let exit_code = solver.solve();
+    let exit_code = solve(&mut solver, out);
 
     // End time recording; print elapsed time
     if logging_level == VerbosityLevel::All {
+        print_restart_windows(out, &solver);
+    }
+    if logging_level >= VerbosityLevel::Summary {
         let end_time = Instant::now();
-        print_post_solve_stats(&solver, start_time, end_time);
+        print_post_solve_stats(out, &solver, start_time, end_time);
     }
 
     // Print model
-    print_result(&solver, exit_code);
+    print_result(out, &solver, exit_code, model_format);
 
     // Return unknown (0), sat (10), or unsat (20)
     exit_code
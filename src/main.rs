@@ -3,19 +3,142 @@ use std::{
     process::{ExitCode, exit},
 };
 
-use nanosat_rs::{solve_cnf_instance, solver::VerbosityLevel};
+use nanosat_rs::{
+    ModelFormat, print_cnf_statistics, simplify_cnf_instance, solve_cnf_instance_competition,
+    solve_cnf_instance_with_assumptions, solve_cnf_instance_with_options, solve_wcnf_instance,
+    solver::{SolverOptions, VerbosityLevel},
+    verify_cnf_instance,
+};
+
+/// What to do with the input file
+enum Mode {
+    /// Solve a CNF file and print the result with statistics
+    Solve,
+    /// Solve a CNF file under the assumptions in the given file
+    Assume(String),
+    /// Solve a CNF file and print only the result in the competition format
+    Competition,
+    /// Solve a WCNF file
+    MaxSat,
+    /// Only count the clauses of a CNF file
+    StatsOnly,
+    /// Print the preprocessed CNF file
+    SimplifyOnly,
+    /// Check the result on a CNF file against the expectation after it
+    Verify,
+}
+
+/// Prints the usage and exits with an error
+fn exit_with_usage() -> ! {
+    eprintln!(
+        "Expects `nanosat [options] [-q | -v]... file.cnf`, \
+         `nanosat [options] (--competition | --assume file.assumptions) file.cnf`, \
+         `nanosat (--stats-only | --simplify-only) file.cnf`, \
+         `nanosat --verify file.cnf (SAT | UNSAT | model)`, or \
+         `nanosat --maxsat file.wcnf`, where the options are `--random-seed n` (or `--seed n`) \
+         and `--model-format (inline | lines | bits)`; the file may be gzip-, xz-, zstd-, or \
+         bzip2-compressed."
+    );
+    exit(1);
+}
 
 /// Main
 fn main() -> ExitCode {
-    // Check CLI args
-    let args: Vec<String> = env::args().collect();
-    if args.len() != 2 {
-        eprintln!("Expects `nanosat file.cnf`, `nanosat file.cnf.gz`, or `nanosat file.cnf.xz`.");
-        exit(1);
+    // Check CLI args in any order; each `-q` lowers the verbosity from search
+    // progress to the summary and then to only the result, and each `-v`
+    // raises it again, `--competition` prints only the result in the SAT
+    // competition format, `--assume` solves under the assumptions in a file,
+    // `--maxsat` solves a WCNF file, `--stats-only` only counts the clauses,
+    // `--simplify-only` prints the preprocessed instance, `--verify` checks the
+    // result against `SAT`, `UNSAT`, or a model file after the file, `--random-seed n`
+    // (or `--seed n`) changes the random seed, and `--model-format` with `inline`, `lines`, or
+    // `bits` selects how the model is printed
+    let args: Vec<String> = env::args().skip(1).collect();
+    let mut args = args.into_iter();
+    let mut options = SolverOptions::default();
+    let mut model_format = ModelFormat::default();
+    let mut logging_level = VerbosityLevel::All;
+    let mut mode = Mode::Solve;
+    let mut positional = Vec::new();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "-q" => {
+                logging_level = match logging_level {
+                    VerbosityLevel::All => VerbosityLevel::Summary,
+                    _ => VerbosityLevel::OnlyResult,
+                };
+            }
+            "-v" => {
+                logging_level = match logging_level {
+                    VerbosityLevel::OnlyResult => VerbosityLevel::Summary,
+                    _ => VerbosityLevel::All,
+                };
+            }
+            "--competition" => mode = Mode::Competition,
+            "--maxsat" => mode = Mode::MaxSat,
+            "--stats-only" => mode = Mode::StatsOnly,
+            "--simplify-only" => mode = Mode::SimplifyOnly,
+            "--verify" => mode = Mode::Verify,
+            "--assume" => mode = Mode::Assume(args.next().unwrap_or_else(|| exit_with_usage())),
+            "--random-seed" | "--seed" => {
+                let seed = args.next().unwrap_or_else(|| exit_with_usage());
+                let Ok(seed) = seed.parse() else {
+                    eprintln!("Could not parse seed \"{seed}\"; expects a non-negative integer.");
+                    exit(1);
+                };
+                options.random_seed = seed;
+            }
+            "--model-format" => {
+                let format = args.next().unwrap_or_else(|| exit_with_usage());
+                model_format = match format.as_str() {
+                    "inline" => ModelFormat::DimacsInline,
+                    "lines" => ModelFormat::DimacsVLines,
+                    "bits" => ModelFormat::BitVector,
+                    _ => {
+                        eprintln!(
+                            "Unknown model format \"{format}\"; expects `inline`, `lines`, or \
+                             `bits`."
+                        );
+                        exit(1);
+                    }
+                };
+            }
+            _ => positional.push(arg),
+        }
     }
-    let filename = &args[1];
 
-    // Run solver
-    let exit_code = solve_cnf_instance(filename, VerbosityLevel::All);
+    // Run the selected mode
+    let exit_code = match (mode, positional.as_slice()) {
+        (Mode::Verify, [filename, expected]) => {
+            return match verify_cnf_instance(filename, expected) {
+                Ok(()) => {
+                    println!("VERIFIED");
+                    ExitCode::SUCCESS
+                }
+                Err(err_msg) => {
+                    eprintln!("{err_msg}.");
+                    ExitCode::FAILURE
+                }
+            };
+        }
+        (Mode::StatsOnly, [filename]) => {
+            print_cnf_statistics(filename);
+            return ExitCode::SUCCESS;
+        }
+        (Mode::Solve, [filename]) => {
+            solve_cnf_instance_with_options(filename, logging_level, options, model_format)
+        }
+        (Mode::Assume(assumptions_filename), [filename]) => solve_cnf_instance_with_assumptions(
+            filename,
+            &assumptions_filename,
+            logging_level,
+            options,
+            model_format,
+        ),
+        (Mode::Competition, [filename]) => solve_cnf_instance_competition(filename, options),
+        (Mode::MaxSat, [filename]) => solve_wcnf_instance(filename),
+        (Mode::SimplifyOnly, [filename]) => simplify_cnf_instance(filename),
+        _ => exit_with_usage(),
+    };
     ExitCode::from(exit_code as u8)
 }
@@ -0,0 +1,231 @@
+use std::collections::HashMap;
+
+use crate::{
+    constraints::add_at_most_one,
+    parsing::{ClauseReceiver, WeightedClauseReceiver},
+    solver::{
+        Solver, SolverExitCode, SolverOptions, VerbosityLevel, literal::Literal,
+        variable::VariableValue,
+    },
+};
+
+/// Weighted partial MaxSAT instance: all hard clauses must be satisfied while
+/// the total weight of the violated soft clauses is minimized
+#[derive(Clone, Debug, Default)]
+pub struct MaxSatInstance {
+    /// Number of variables
+    num_variables: usize,
+    /// Clauses that must be satisfied
+    hard_clauses: Vec<Vec<Literal>>,
+    /// Clauses with the cost of violating them
+    soft_clauses: Vec<(u64, Vec<Literal>)>,
+}
+
+/// Optimal assignment of a MaxSAT instance
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct MaxSatSolution {
+    /// Total weight of the soft clauses violated by `model`
+    pub cost: u64,
+    /// Assignment of all variables of the instance
+    pub model: Vec<VariableValue>,
+}
+
+/// Soft clause as seen by the solver; `selector` disables the clause when true
+struct SoftCopy {
+    /// Weight of the clause
+    weight: u64,
+    /// Literals of the clause including its relaxation literals
+    literals: Vec<Literal>,
+    /// Assumed false while the copy is active
+    selector: Literal,
+}
+
+impl MaxSatInstance {
+    /// Create an empty instance
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Number of variables
+    #[must_use]
+    pub const fn num_variables(&self) -> usize {
+        self.num_variables
+    }
+
+    /// Hard clauses in the order they were added
+    #[must_use]
+    pub fn hard_clauses(&self) -> &[Vec<Literal>] {
+        &self.hard_clauses
+    }
+
+    /// Soft clauses with their weights in the order they were added
+    #[must_use]
+    pub fn soft_clauses(&self) -> &[(u64, Vec<Literal>)] {
+        &self.soft_clauses
+    }
+
+    /// Adds a clause that must be satisfied
+    pub fn add_hard_clause(&mut self, literals: &[Literal]) -> &mut Self {
+        self.grow_variables(literals);
+        self.hard_clauses.push(literals.to_vec());
+        self
+    }
+
+    /// Adds a clause that costs `weight` if violated
+    pub fn add_soft_clause(&mut self, weight: u64, literals: &[Literal]) -> &mut Self {
+        self.grow_variables(literals);
+        self.soft_clauses.push((weight, literals.to_vec()));
+        self
+    }
+
+    /// Total weight of the soft clauses violated by `model`
+    #[must_use]
+    pub fn cost(&self, model: &[VariableValue]) -> u64 {
+        self.soft_clauses
+            .iter()
+            .filter(|(_, literals)| !literals.iter().any(|literal| literal.is_true(model)))
+            .map(|(weight, _)| weight)
+            .sum()
+    }
+
+    /// Minimizes the weight of the violated soft clauses with the core-guided
+    /// WPM1 algorithm; returns `None` if the hard clauses are UNSAT
+    ///
+    /// Each soft clause gets a selector variable that is assumed false. For
+    /// every UNSAT core, the cost grows by the smallest weight in the core and
+    /// the involved soft clauses are relaxed by fresh variables, at most one
+    /// of which may be true; clauses heavier than that weight keep an unrelaxed
+    /// copy with the remaining weight.
+    ///
+    /// # Panics
+    ///
+    /// If the variables and selectors exceed `SolverOptions::max_variables`
+    #[must_use]
+    pub fn solve(&self) -> Option<MaxSatSolution> {
+        let options = SolverOptions {
+            variable_elimination: false,
+            ..SolverOptions::default()
+        };
+        let mut solver = Solver::with_options(VerbosityLevel::OnlyResult, options);
+        assert!(
+            solver.create_variables(self.num_variables),
+            "instance exceeds the maximum number of variables"
+        );
+        for clause in &self.hard_clauses {
+            if !solver.add_clause(clause) {
+                return None;
+            }
+        }
+
+        // Active soft clause copies by the variable of their selector
+        let mut copies: HashMap<usize, SoftCopy> = HashMap::new();
+        for (weight, literals) in &self.soft_clauses {
+            if *weight > 0 {
+                Self::add_soft_copy(&mut solver, &mut copies, *weight, literals.clone());
+            }
+        }
+
+        let mut cost = 0;
+        loop {
+            let mut assumptions: Vec<Literal> =
+                copies.values().map(|copy| !copy.selector).collect();
+            assumptions.sort();
+            if solver.solve_with_assumptions(&assumptions) == SolverExitCode::Sat {
+                let model = solver.model()[..self.num_variables].to_vec();
+                debug_assert_eq!(self.cost(&model), cost);
+                return Some(MaxSatSolution { cost, model });
+            }
+            let core: Vec<usize> = solver
+                .failed_assumptions()
+                .iter()
+                .map(|assumption| assumption.var())
+                .collect();
+            if core.is_empty() {
+                return None;
+            }
+
+            // Relax the soft clauses in the core
+            let min_weight = core
+                .iter()
+                .map(|var| copies[var].weight)
+                .min()
+                .expect("core is not empty");
+            let mut relaxation_literals = Vec::with_capacity(core.len());
+            for var in core {
+                let relaxation_literal = Self::new_literal(&mut solver);
+                relaxation_literals.push(relaxation_literal);
+                let copy = copies.get_mut(&var).expect("core consists of selectors");
+                let mut literals = copy.literals.clone();
+                literals.push(relaxation_literal);
+                if copy.weight > min_weight {
+                    copy.weight -= min_weight;
+                } else if let Some(copy) = copies.remove(&var) {
+                    // Disable the copy for good
+                    solver.add_clause_ints(&[copy.selector.to_dimacs()]);
+                }
+                Self::add_soft_copy(&mut solver, &mut copies, min_weight, literals);
+            }
+            add_at_most_one(&mut solver, &relaxation_literals);
+            cost += min_weight;
+        }
+    }
+
+    /// Raises the number of variables to cover `literals`
+    fn grow_variables(&mut self, literals: &[Literal]) {
+        for literal in literals {
+            self.num_variables = self.num_variables.max(literal.var() + 1);
+        }
+    }
+
+    /// Positive literal of a new variable
+    fn new_literal(solver: &mut Solver) -> Literal {
+        let var = solver.num_variables();
+        assert!(
+            solver.create_variables(var + 1),
+            "selector exceeds the maximum number of variables"
+        );
+        Literal::from_var_with_polarity(var, true)
+    }
+
+    /// Adds a soft clause copy with a new selector
+    fn add_soft_copy(
+        solver: &mut Solver,
+        copies: &mut HashMap<usize, SoftCopy>,
+        weight: u64,
+        literals: Vec<Literal>,
+    ) {
+        let selector = Self::new_literal(solver);
+        let mut lits: Vec<i32> = literals.iter().map(Literal::to_dimacs).collect();
+        lits.push(selector.to_dimacs());
+        solver.add_clause_ints(&lits);
+        copies.insert(
+            selector.var(),
+            SoftCopy {
+                weight,
+                literals,
+                selector,
+            },
+        );
+    }
+}
+
+/// Hard and soft clauses of a WCNF file
+impl ClauseReceiver for MaxSatInstance {
+    fn create_variables(&mut self, num_variables: usize) -> bool {
+        self.num_variables = self.num_variables.max(num_variables);
+        true
+    }
+
+    fn add_clause(&mut self, literals: &[Literal]) -> bool {
+        self.add_hard_clause(literals);
+        true
+    }
+}
+
+impl WeightedClauseReceiver for MaxSatInstance {
+    fn add_weighted_clause(&mut self, weight: u64, literals: &[Literal]) -> bool {
+        self.add_soft_clause(weight, literals);
+        true
+    }
+}
@@ -1,20 +1,131 @@
 use std::{
     fs::File,
-    io::{BufRead, BufReader},
-    path::Path,
+    io::{BufRead, BufReader, Read, Seek},
+    num::{IntErrorKind, ParseIntError},
     process::{Command, Stdio, exit},
 };
 
-use crate::solver::literal::Literal;
+use bzip2::read::MultiBzDecoder;
+
+use crate::solver::literal::{DimacsLiteralError, Literal};
 
 /// Trait describing that a struct can process clauses
 pub trait ClauseReceiver {
-    /// Creates `num_variables` variables
-    fn create_variables(&mut self, num_variables: usize);
+    /// Creates `num_variables` variables; returns `false` if the receiver
+    /// cannot hold that many
+    fn create_variables(&mut self, num_variables: usize) -> bool;
     /// Add clauses
     fn add_clause(&mut self, literals: &[Literal]) -> bool;
 }
 
+/// Trait describing that a struct can process the clauses of WCNF files;
+/// hard clauses are passed to `ClauseReceiver::add_clause`
+pub trait WeightedClauseReceiver: ClauseReceiver {
+    /// Add soft clauses violating which costs `weight`
+    fn add_weighted_clause(&mut self, weight: u64, literals: &[Literal]) -> bool;
+}
+
+/// Receiver that only counts clauses and literals without retaining them
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub struct ClauseStats {
+    /// Number of variables declared by the header
+    pub num_variables: usize,
+    /// Number of clauses
+    pub num_clauses: usize,
+    /// Number of literals in clauses
+    pub num_literals: usize,
+    /// Largest variable occurring in a clause, counted from 1 as in DIMACS;
+    /// 0 if there are no literals
+    pub max_variable: usize,
+}
+
+impl ClauseReceiver for ClauseStats {
+    fn create_variables(&mut self, num_variables: usize) -> bool {
+        self.num_variables = num_variables;
+        true
+    }
+
+    fn add_clause(&mut self, literals: &[Literal]) -> bool {
+        self.num_clauses += 1;
+        self.num_literals += literals.len();
+        if let Some(max_var) = literals.iter().map(Literal::var).max() {
+            self.max_variable = self.max_variable.max(max_var + 1);
+        }
+        true
+    }
+}
+
+/// Receiver that retains all clauses, such as to check models against them
+#[derive(Clone, PartialEq, Eq, Debug, Default)]
+pub struct ClauseList {
+    /// Number of variables declared by the header or occurring in a clause
+    pub num_variables: usize,
+    /// Clauses in the order they were parsed
+    pub clauses: Vec<Vec<Literal>>,
+}
+
+impl ClauseReceiver for ClauseList {
+    fn create_variables(&mut self, num_variables: usize) -> bool {
+        self.num_variables = self.num_variables.max(num_variables);
+        true
+    }
+
+    fn add_clause(&mut self, literals: &[Literal]) -> bool {
+        self.clauses.push(literals.to_vec());
+        true
+    }
+}
+
+/// Receiver forwarding every call to two receivers, such as to feed a solver
+/// and collect statistics in one pass; both receivers are always called and
+/// the results are combined with `&`
+#[derive(Debug)]
+pub struct TeeReceiver<'a, A, B> {
+    /// Receiver called first
+    pub first: &'a mut A,
+    /// Receiver called second
+    pub second: &'a mut B,
+}
+
+impl<'a, A, B> TeeReceiver<'a, A, B> {
+    /// Creates a receiver forwarding to `first` and then `second`
+    pub const fn new(first: &'a mut A, second: &'a mut B) -> Self {
+        Self { first, second }
+    }
+}
+
+impl<A: ClauseReceiver, B: ClauseReceiver> ClauseReceiver for TeeReceiver<'_, A, B> {
+    fn create_variables(&mut self, num_variables: usize) -> bool {
+        self.first.create_variables(num_variables) & self.second.create_variables(num_variables)
+    }
+
+    fn add_clause(&mut self, literals: &[Literal]) -> bool {
+        self.first.add_clause(literals) & self.second.add_clause(literals)
+    }
+}
+
+impl<A: WeightedClauseReceiver, B: WeightedClauseReceiver> WeightedClauseReceiver
+    for TeeReceiver<'_, A, B>
+{
+    fn add_weighted_clause(&mut self, weight: u64, literals: &[Literal]) -> bool {
+        self.first.add_weighted_clause(weight, literals)
+            & self.second.add_weighted_clause(weight, literals)
+    }
+}
+
+/// Adds a soft clause; `None` for plain CNF files
+type AddWeightedClause<R> = Option<fn(&mut R, u64, &[Literal]) -> bool>;
+
+/// How to treat variable and clause counts deviating from the header
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub enum ParseMode {
+    /// Abort if the counts do not match the header
+    #[default]
+    Strict,
+    /// Accept the parsed counts; variables beyond the header are created on demand
+    Lenient,
+}
+
 /// Parsing state
 struct ParseState {
     /// Number of variabels in header
@@ -27,6 +138,14 @@ struct ParseState {
     pub curr_num_clauses: usize,
     /// Whether already processed the header `p cnf ...`
     pub processed_header: bool,
+    /// Whether the receiver still accepts clauses; `false` once UNSAT
+    pub still_satisfiable: bool,
+    /// Literals of the clause not yet terminated by `0`
+    pub curr_clause: Vec<Literal>,
+    /// Clauses at least this heavy are hard in WCNF files
+    pub top_weight: u64,
+    /// Weight of the current clause in WCNF files, once parsed
+    pub curr_weight: Option<u64>,
 }
 
 impl ParseState {
@@ -37,38 +156,115 @@ impl ParseState {
             num_clauses_header: 0,
             curr_num_clauses: 0,
             processed_header: false,
+            still_satisfiable: true,
+            curr_clause: Vec::new(),
+            top_weight: u64::MAX,
+            curr_weight: None,
+        }
+    }
+
+    /// Passes the current clause to the receiver; soft clauses of WCNF files
+    /// go to `add_weighted`
+    fn finish_clause<R: ClauseReceiver>(
+        &mut self,
+        solver: &mut R,
+        add_weighted: AddWeightedClause<R>,
+    ) {
+        // Keep counting clauses after the instance is known to be UNSAT
+        self.curr_num_clauses += 1;
+        if self.still_satisfiable {
+            self.still_satisfiable = match (add_weighted, self.curr_weight) {
+                (Some(add_weighted), Some(weight)) if weight < self.top_weight => {
+                    add_weighted(solver, weight, &self.curr_clause)
+                }
+                _ => solver.add_clause(&self.curr_clause),
+            };
         }
+        self.curr_clause.clear();
+        self.curr_weight = None;
     }
 }
 
-/// Open plain text file
-fn open_plain_file(filename: &str) -> Box<dyn BufRead> {
-    if let Ok(file) = File::open(filename) {
-        Box::new(BufReader::new(file))
-    } else {
-        eprintln!("Failed to open file \"{filename}\" using plain text mode.");
-        exit(1);
+/// Compression formats recognized by their magic bytes; a new format needs a
+/// variant, its magic bytes, and its decompressor
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+enum Compression {
+    /// Plain text
+    None,
+    /// gzip
+    Gzip,
+    /// xz
+    Xz,
+    /// zstd
+    Zstd,
+    /// bzip2
+    Bzip2,
+}
+
+impl Compression {
+    /// Compressed formats with the magic bytes their files start with
+    const MAGIC_BYTES: [(Self, &'static [u8]); 4] = [
+        (Self::Gzip, &[0x1f, 0x8b]),
+        (Self::Xz, &[0xfd, 0x37, 0x7a, 0x58, 0x5a]),
+        (Self::Zstd, &[0x28, 0xb5, 0x2f, 0xfd]),
+        (Self::Bzip2, b"BZh"),
+    ];
+
+    /// Maximum number of magic bytes
+    const MAGIC_LEN: usize = 5;
+
+    /// Detects the compression format from the first bytes of a file
+    fn from_magic_bytes(bytes: &[u8]) -> Self {
+        Self::MAGIC_BYTES
+            .iter()
+            .find(|(_, magic)| bytes.starts_with(magic))
+            .map_or(Self::None, |&(compression, _)| compression)
+    }
+
+    /// Program that decompresses the format to stdout with `-dc`; `None` for
+    /// plain text and bzip2, which is decompressed in-process
+    const fn decompressor(self) -> Option<&'static str> {
+        match self {
+            Self::None | Self::Bzip2 => None,
+            Self::Gzip => Some("gzip"),
+            Self::Xz => Some("xz"),
+            Self::Zstd => Some("zstd"),
+        }
     }
 }
 
-/// Open xz-compressed file
-fn open_xz_file(filename: &str) -> Box<dyn BufRead> {
-    if let Ok(child) = Command::new("xz")
-        .args(["-dc", filename])
-        .stdout(Stdio::piped())
-        .spawn()
-    {
-        let reader = BufReader::new(child.stdout.unwrap());
-        Box::new(reader)
-    } else {
-        eprintln!("Failed to open file \"{filename}\" using \"xz\".");
+/// Open a plain text or compressed file; the compression format is detected
+/// by the magic bytes at the start of the file rather than its extension
+fn open_file(filename: &str) -> Box<dyn BufRead> {
+    let Ok(mut file) = File::open(filename) else {
+        eprintln!("Failed to open file \"{filename}\" using plain text mode.");
+        exit(1);
+    };
+
+    // Peek the magic bytes and rewind
+    let mut magic_bytes = Vec::with_capacity(Compression::MAGIC_LEN);
+    let peeked = (&mut file)
+        .take(Compression::MAGIC_LEN as u64)
+        .read_to_end(&mut magic_bytes)
+        .and_then(|_| file.rewind());
+    if peeked.is_err() {
+        eprintln!("Failed to open file \"{filename}\" using plain text mode.");
         exit(1);
     }
+
+    let compression = Compression::from_magic_bytes(&magic_bytes);
+    match compression.decompressor() {
+        Some(program) => open_compressed_file(filename, program),
+        None if compression == Compression::Bzip2 => {
+            Box::new(BufReader::new(MultiBzDecoder::new(file)))
+        }
+        None => Box::new(BufReader::new(file)),
+    }
 }
 
-/// Open gzip-compressed file
-fn open_gzip_file(filename: &str) -> Box<dyn BufRead> {
-    if let Ok(child) = Command::new("gzip")
+/// Open compressed file by decompressing it with `program -dc`
+fn open_compressed_file(filename: &str, program: &str) -> Box<dyn BufRead> {
+    if let Ok(child) = Command::new(program)
         .args(["-dc", filename])
         .stdout(Stdio::piped())
         .spawn()
@@ -76,7 +272,7 @@ fn open_gzip_file(filename: &str) -> Box<dyn BufRead> {
         let reader = BufReader::new(child.stdout.unwrap());
         Box::new(reader)
     } else {
-        eprintln!("Failed to open file \"{filename}\" using \"gzip\".");
+        eprintln!("Failed to open file \"{filename}\" using \"{program}\".");
         exit(1);
     }
 }
@@ -87,15 +283,127 @@ fn unexpected_token(err_msg: &str, filename: &str, line_no: usize) -> ! {
     exit(1);
 }
 
-/// Parse `.cnf`, `.cnf.xz`, or `.cnf.gz`
+/// Largest number of variables; literals are parsed as `i32`
+const MAX_VARIABLES: usize = i32::MAX as usize;
+
+/// Parses a count of the header; reports negative and overflowing counts
+/// separately from malformed ones
+fn parse_header_count(token: &str, what: &str, filename: &str, line_no: usize) -> usize {
+    let err_msg = match token.parse::<usize>() {
+        Ok(count) => return count,
+        Err(err) if *err.kind() == IntErrorKind::PosOverflow => {
+            format!("Number of {what} in header exceeds {}", usize::MAX)
+        }
+        Err(_)
+            if token
+                .strip_prefix('-')
+                .is_some_and(|digits| digits.parse::<usize>().is_ok()) =>
+        {
+            format!("Number of {what} in header is negative")
+        }
+        Err(_) => format!("Could not parse number of {what} in header"),
+    };
+    unexpected_token(&err_msg, filename, line_no)
+}
+
+/// Parses a weight; reports overflows separately from malformed weights
+fn parse_weight(token: &str, what: &str, filename: &str, line_no: usize) -> u64 {
+    token.parse().unwrap_or_else(|err: ParseIntError| {
+        let err_msg = if *err.kind() == IntErrorKind::PosOverflow {
+            format!("Could not parse {what}; it exceeds {}", u64::MAX)
+        } else {
+            format!("Could not parse {what}")
+        };
+        unexpected_token(&err_msg, filename, line_no)
+    })
+}
+
+/// Parses a DIMACS literal; returns `None` for the clause terminator `0`
+fn parse_literal(token: &str, filename: &str, line_no: usize) -> Option<Literal> {
+    let num = token
+        .parse::<i32>()
+        .unwrap_or_else(|_| unexpected_token("Could not parse literal", filename, line_no));
+    match Literal::try_from_dimacs(num) {
+        Ok(literal) => Some(literal),
+        Err(DimacsLiteralError::Terminator) => None,
+        Err(DimacsLiteralError::OutOfRange) => {
+            unexpected_token("Could not parse literal", filename, line_no)
+        }
+    }
+}
+
+/// Parse a plain or compressed file of assumptions, given as DIMACS literals
+/// separated by any whitespace, such as one per line; comment lines start
+/// with `c` and `0` tokens are skipped; all variables must be below
+/// `num_variables`
+pub fn parse_assumptions(filename: &str, num_variables: usize) -> Vec<Literal> {
+    let file = open_file(filename);
+    let mut assumptions = Vec::new();
+    for (line_idx, line_res) in file.lines().enumerate() {
+        let line_no = line_idx + 1;
+        let line = line_res
+            .unwrap_or_else(|_| unexpected_token("Could not parse line", filename, line_no));
+        if line.trim_start().starts_with('c') {
+            continue;
+        }
+        for token in line.split_whitespace() {
+            let Some(literal) = parse_literal(token, filename, line_no) else {
+                continue;
+            };
+            if literal.var() >= num_variables {
+                unexpected_token(
+                    "Assumption exceeds the number of variables",
+                    filename,
+                    line_no,
+                );
+            }
+            assumptions.push(literal);
+        }
+    }
+    assumptions
+}
+
+/// Parse a plain, gzip-, xz-, zstd-, or bzip2-compressed CNF file; the header counts must be correct
 pub fn parse_cnf(filename: &str, solver: &mut impl ClauseReceiver) {
+    parse_cnf_with_mode(filename, solver, ParseMode::Strict);
+}
+
+/// Parse a plain or compressed CNF file with the given treatment of the header counts
+pub fn parse_cnf_with_mode(filename: &str, solver: &mut impl ClauseReceiver, mode: ParseMode) {
+    parse_dimacs(filename, solver, mode, None);
+}
+
+/// Parse a plain or compressed WCNF file with the header `p wcnf <vars>
+/// <clauses> [<top>]`, where every clause starts with its weight; clauses at
+/// least as heavy as `top` are hard and all clauses are soft without `top`;
+/// the header counts must be correct
+pub fn parse_wcnf<R: WeightedClauseReceiver>(filename: &str, receiver: &mut R) {
+    parse_dimacs(
+        filename,
+        receiver,
+        ParseMode::Strict,
+        Some(R::add_weighted_clause),
+    );
+}
+
+/// Name of the parsed format for error messages
+const fn format_name<R>(add_weighted: AddWeightedClause<R>) -> &'static str {
+    if add_weighted.is_some() {
+        "WCNF"
+    } else {
+        "CNF"
+    }
+}
+
+/// Parse a CNF file or, with `add_weighted`, a WCNF file
+fn parse_dimacs<R: ClauseReceiver>(
+    filename: &str,
+    solver: &mut R,
+    mode: ParseMode,
+    add_weighted: AddWeightedClause<R>,
+) {
     // Open file
-    let path = Path::new(filename);
-    let file = match path.extension() {
-        Some(x) if x.eq_ignore_ascii_case("xz") => open_xz_file(filename),
-        Some(x) if x.eq_ignore_ascii_case("gz") => open_gzip_file(filename),
-        _ => open_plain_file(filename),
-    };
+    let file = open_file(filename);
 
     // Parse file
     let mut curr_state = ParseState::new();
@@ -103,70 +411,119 @@ pub fn parse_cnf(filename: &str, solver: &mut impl ClauseReceiver) {
         let line_no = line_idx + 1;
         let line = line_res
             .unwrap_or_else(|_| unexpected_token("Could not parse line", filename, line_no));
-        match line {
+        // Lines of files with Windows line endings keep their `\r`
+        match line.trim() {
             // Comment
             l if l.starts_with('c') => {}
-            // Header
-            l if l.starts_with("p cnf ") && !curr_state.processed_header => {
+            // Header; tokens may be separated by any whitespace
+            l if l.starts_with('p') && !curr_state.processed_header => {
                 curr_state.processed_header = true;
                 let parts: Vec<&str> = l.split_whitespace().collect();
-                if parts.len() != 4 {
-                    unexpected_token("CNF header invalid", filename, line_no);
+                let valid_header = match add_weighted {
+                    None => parts.len() == 4 && parts[1] == "cnf",
+                    Some(_) => (parts.len() == 4 || parts.len() == 5) && parts[1] == "wcnf",
+                };
+                if !valid_header || parts[0] != "p" {
+                    unexpected_token(
+                        &format!("{} header invalid", format_name(add_weighted)),
+                        filename,
+                        line_no,
+                    );
+                }
+                if let Some(top) = parts.get(4) {
+                    curr_state.top_weight = parse_weight(top, "top weight", filename, line_no);
                 }
-                curr_state.num_variables_header = parts[2].parse().unwrap_or_else(|_| {
+                curr_state.num_variables_header =
+                    parse_header_count(parts[2], "variables", filename, line_no);
+                curr_state.num_clauses_header =
+                    parse_header_count(parts[3], "clauses", filename, line_no);
+
+                // Reject counts the solver cannot allocate before creating variables
+                if curr_state.num_variables_header > MAX_VARIABLES {
                     unexpected_token(
-                        "Could not parse number of variables in header",
+                        &format!("Number of variables in header exceeds {MAX_VARIABLES}"),
                         filename,
                         line_no,
-                    )
-                });
-                curr_state.num_clauses_header = parts[3].parse().unwrap_or_else(|_| {
+                    );
+                }
+                if curr_state.num_variables_header == 0 && curr_state.num_clauses_header > 0 {
                     unexpected_token(
-                        "Could not parse number of clauses in header",
+                        "Header declares clauses but no variables",
                         filename,
                         line_no,
-                    )
-                });
-                solver.create_variables(curr_state.num_variables_header);
+                    );
+                }
+                if !solver.create_variables(curr_state.num_variables_header) {
+                    unexpected_token(
+                        "Number of variables in header exceeds the solver's limit",
+                        filename,
+                        line_no,
+                    );
+                }
             }
+            // Second header
+            l if l.starts_with('p') => unexpected_token(
+                &format!("Duplicate {} header", format_name(add_weighted)),
+                filename,
+                line_no,
+            ),
             // Header missing
             _ if !curr_state.processed_header => {
                 unexpected_token("CNF header missing", filename, line_no)
             }
-            // Parse clause
+            // Parse literals; clauses may span several lines, end with `0`, and be
+            // followed by a comment
             l => {
-                let literals: Vec<Literal> = l
-                    .split_whitespace()
-                    .map(|s| {
-                        s.parse::<i32>().unwrap_or_else(|_| {
-                            unexpected_token("Could not parse literal", filename, line_no)
-                        })
-                    })
-                    .filter(|&num| num != 0)
-                    .map(|num| {
-                        let var = usize::try_from(num.unsigned_abs() - 1).unwrap_or_else(|_| {
-                            unexpected_token("Could not parse literal", filename, line_no)
-                        });
-                        if var + 1 > curr_state.curr_num_variables {
-                            curr_state.curr_num_variables = var + 1;
-                        }
-                        Literal::from_var_with_polarity(var, num > 0)
-                    })
-                    .collect();
-                if !literals.is_empty() {
-                    curr_state.curr_num_clauses += 1;
-                    let still_satisfiable = solver.add_clause(&literals);
-                    if !still_satisfiable {
+                for token in l.split_whitespace() {
+                    // Trailing comment; no literal or weight starts with `c`
+                    if token.starts_with('c') {
                         break;
                     }
+                    // Every WCNF clause starts with its weight
+                    if add_weighted.is_some() && curr_state.curr_weight.is_none() {
+                        curr_state.curr_weight =
+                            Some(parse_weight(token, "clause weight", filename, line_no));
+                        continue;
+                    }
+                    let Some(literal) = parse_literal(token, filename, line_no) else {
+                        curr_state.finish_clause(solver, add_weighted);
+                        continue;
+                    };
+                    let var = literal.var();
+                    if mode == ParseMode::Strict && var >= curr_state.num_variables_header {
+                        unexpected_token(
+                            "Variable exceeds the number of variables in the header",
+                            filename,
+                            line_no,
+                        );
+                    }
+                    if var + 1 > curr_state.curr_num_variables {
+                        curr_state.curr_num_variables = var + 1;
+                        if mode == ParseMode::Lenient
+                            && var + 1 > curr_state.num_variables_header
+                            && !solver.create_variables(var + 1)
+                        {
+                            unexpected_token(
+                                "Number of variables exceeds the solver's limit",
+                                filename,
+                                line_no,
+                            );
+                        }
+                    }
+                    curr_state.curr_clause.push(literal);
                 }
             }
         }
     }
 
+    // Accept a missing `0` after the last clause
+    if !curr_state.curr_clause.is_empty() {
+        curr_state.finish_clause(solver, add_weighted);
+    }
+
     // Check number of variables and clauses
-    if curr_state.curr_num_variables != curr_state.num_variables_header {
-        unexpected_token("Number of variables in cnf incorrect", filename, 0);
+    if mode == ParseMode::Lenient {
+        return;
     }
     if curr_state.curr_num_clauses != curr_state.num_clauses_header {
         unexpected_token("Number of clauses in cnf incorrect", filename, 0);
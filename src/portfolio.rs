@@ -0,0 +1,128 @@
+use std::{
+    sync::{
+        Arc, Mutex,
+        atomic::{AtomicBool, Ordering},
+    },
+    thread,
+};
+
+use crate::{
+    parsing::ClauseReceiver,
+    solver::{
+        ClauseExchange, RestartStrategy, Solver, SolverExitCode, SolverOptions, VerbosityLevel,
+        literal::Literal,
+    },
+};
+
+/// Options of the `idx`-th portfolio solver; the first one uses the default
+/// options and the others vary the seed, restart strategy, and phases
+fn portfolio_options(idx: usize) -> SolverOptions {
+    let defaults = SolverOptions::default();
+    if idx == 0 {
+        return defaults;
+    }
+    SolverOptions {
+        random_seed: defaults.random_seed + idx as u64,
+        restart_strategy: if idx % 2 == 1 {
+            RestartStrategy::Geometric
+        } else {
+            RestartStrategy::Luby
+        },
+        rephasing: idx % 4 >= 2,
+        random_phase_prob: if idx.is_multiple_of(3) { 0.01 } else { 0.0 },
+        ..defaults
+    }
+}
+
+/// Solves the CNF instance `clauses` by racing `num_threads` diversely
+/// configured solvers; the first solver to finish interrupts the others;
+/// returns its result together with its model if SAT
+///
+/// Every configuration is deterministic, so the result only depends on which
+/// solver finishes first; SAT or UNSAT is the same for all of them, but the
+/// model may differ between runs.
+///
+/// # Panics
+///
+/// If `num_threads` is `0` or the clauses exceed `SolverOptions::max_variables`
+#[must_use]
+pub fn solve_portfolio(
+    clauses: &[Vec<Literal>],
+    num_threads: usize,
+) -> (SolverExitCode, Option<Vec<bool>>) {
+    race(clauses, num_threads, None)
+}
+
+/// Solves the CNF instance `clauses` like `solve_portfolio`, but the solvers
+/// share their learned clauses with a literal block distance of at most
+/// `max_shared_lbd`
+///
+/// # Panics
+///
+/// If `num_threads` is `0` or the clauses exceed `SolverOptions::max_variables`
+#[must_use]
+pub fn solve_portfolio_with_sharing(
+    clauses: &[Vec<Literal>],
+    num_threads: usize,
+    max_shared_lbd: usize,
+) -> (SolverExitCode, Option<Vec<bool>>) {
+    race(clauses, num_threads, Some(max_shared_lbd))
+}
+
+/// Races the portfolio solvers; with `max_shared_lbd`, they exchange short
+/// learned clauses
+fn race(
+    clauses: &[Vec<Literal>],
+    num_threads: usize,
+    max_shared_lbd: Option<usize>,
+) -> (SolverExitCode, Option<Vec<bool>>) {
+    assert!(num_threads > 0, "portfolio needs at least one thread");
+    let num_variables = clauses
+        .iter()
+        .flatten()
+        .map(|literal| literal.var() + 1)
+        .max()
+        .unwrap_or(0);
+    let interrupt = Arc::new(AtomicBool::new(false));
+    let exchange = Arc::new(ClauseExchange::new());
+    let winner = Mutex::new((SolverExitCode::Unknown, None));
+
+    thread::scope(|scope| {
+        for idx in 0..num_threads {
+            let interrupt = Arc::clone(&interrupt);
+            let exchange = Arc::clone(&exchange);
+            let winner = &winner;
+            scope.spawn(move || {
+                let options = portfolio_options(idx);
+                let mut solver = Solver::with_options(VerbosityLevel::OnlyResult, options);
+                assert!(
+                    solver.create_variables(num_variables),
+                    "instance exceeds the maximum number of variables"
+                );
+                let mut status = SolverExitCode::Unknown;
+                for clause in clauses {
+                    if !solver.add_clause(clause) {
+                        status = SolverExitCode::Unsat;
+                        break;
+                    }
+                }
+                solver.set_interrupt(Arc::clone(&interrupt));
+                if let Some(max_lbd) = max_shared_lbd {
+                    solver.set_clause_exchange(exchange, idx, max_lbd);
+                }
+                if status == SolverExitCode::Unknown {
+                    status = solver.solve();
+                }
+
+                // Only the first finished solver reports its result
+                if status != SolverExitCode::Unknown && !interrupt.swap(true, Ordering::Relaxed) {
+                    let model = (status == SolverExitCode::Sat)
+                        .then(|| solver.model().iter().map(|value| value.is_true()).collect());
+                    *winner.lock().expect("no solver thread panicked") = (status, model);
+                }
+            });
+        }
+    });
+
+    winner.into_inner().expect("no solver thread panicked")
+}
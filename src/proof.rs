@@ -0,0 +1,323 @@
+use std::{
+    collections::HashMap,
+    io::{self, Write},
+};
+
+use crate::solver::{literal::Literal, variable::VariableValue};
+
+/// Identifier of a clause in a proof; the original clauses are numbered from
+/// `1` in the order of their addition, and derived clauses follow them
+pub type ClauseId = u64;
+
+/// Step of a DRAT proof of unsatisfiability
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum ProofStep {
+    /// Clause implied by the current clauses; the proof ends with the empty clause
+    Add(Vec<Literal>),
+    /// Clause removed from the current clauses
+    Delete(Vec<Literal>),
+}
+
+/// Step of an LRAT proof of unsatisfiability
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum LratStep {
+    /// Clause `id` implied by unit propagation over the clauses `hints` in
+    /// order; the proof ends with the empty clause
+    Add {
+        id: ClauseId,
+        literals: Vec<Literal>,
+        hints: Vec<ClauseId>,
+    },
+    /// Clauses removed from the current clauses
+    Delete(Vec<ClauseId>),
+}
+
+/// Receiver of the clauses a solver derives and deletes while proving an
+/// instance UNSAT; the solver assigns the clause identifiers, so that every
+/// proof format numbers the clauses alike
+pub trait ProofWriter {
+    /// Records clause `id`, which unit propagation over the clauses `hints`
+    /// in order implies
+    ///
+    /// # Errors
+    ///
+    /// If writing the step fails
+    fn add(&mut self, id: ClauseId, literals: &[Literal], hints: &[ClauseId]) -> io::Result<()>;
+
+    /// Records the removal of clause `id` with the given literals
+    ///
+    /// # Errors
+    ///
+    /// If writing the step fails
+    fn delete(&mut self, id: ClauseId, literals: &[Literal]) -> io::Result<()>;
+
+    /// Flushes the steps written so far
+    ///
+    /// # Errors
+    ///
+    /// If flushing the output fails
+    fn flush(&mut self) -> io::Result<()>;
+}
+
+/// Writes proofs in the textual DRAT format, which omits clause identifiers
+/// and hints
+#[derive(Debug)]
+pub struct DratWriter<W: Write> {
+    /// Destination of the proof
+    out: W,
+}
+
+impl<W: Write> DratWriter<W> {
+    /// Creates a writer emitting the proof to `out`
+    pub const fn new(out: W) -> Self {
+        Self { out }
+    }
+}
+
+impl<W: Write> ProofWriter for DratWriter<W> {
+    fn add(&mut self, _id: ClauseId, literals: &[Literal], _hints: &[ClauseId]) -> io::Result<()> {
+        for literal in literals {
+            write!(self.out, "{literal} ")?;
+        }
+        writeln!(self.out, "0")
+    }
+
+    fn delete(&mut self, _id: ClauseId, literals: &[Literal]) -> io::Result<()> {
+        write!(self.out, "d ")?;
+        for literal in literals {
+            write!(self.out, "{literal} ")?;
+        }
+        writeln!(self.out, "0")
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.out.flush()
+    }
+}
+
+/// Writes proofs in the textual LRAT format, in which every added clause lists
+/// the clauses that imply it, so that checkers need not search for them
+#[derive(Debug)]
+pub struct LratWriter<W: Write> {
+    /// Destination of the proof
+    out: W,
+    /// Identifier of the most recently added clause, which deletion lines carry
+    last_id: ClauseId,
+}
+
+impl<W: Write> LratWriter<W> {
+    /// Creates a writer emitting the proof to `out`
+    pub const fn new(out: W) -> Self {
+        Self { out, last_id: 0 }
+    }
+}
+
+impl<W: Write> ProofWriter for LratWriter<W> {
+    fn add(&mut self, id: ClauseId, literals: &[Literal], hints: &[ClauseId]) -> io::Result<()> {
+        self.last_id = id;
+        write!(self.out, "{id} ")?;
+        for literal in literals {
+            write!(self.out, "{literal} ")?;
+        }
+        write!(self.out, "0 ")?;
+        for hint in hints {
+            write!(self.out, "{hint} ")?;
+        }
+        writeln!(self.out, "0")
+    }
+
+    fn delete(&mut self, id: ClauseId, _literals: &[Literal]) -> io::Result<()> {
+        writeln!(self.out, "{} d {id} 0", self.last_id)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.out.flush()
+    }
+}
+
+/// Checks that `model` satisfies every clause of `clauses`; variables beyond
+/// the model count as unset
+#[must_use]
+pub fn verify_model(clauses: &[Vec<Literal>], model: &[VariableValue]) -> bool {
+    clauses.iter().all(|clause| {
+        clause
+            .iter()
+            .any(|literal| literal.var() < model.len() && literal.is_true(model))
+    })
+}
+
+/// Sorted literals without duplicates, to compare clauses up to literal order
+fn normalized(clause: &[Literal]) -> Vec<Literal> {
+    let mut literals = clause.to_vec();
+    literals.sort_unstable();
+    literals.dedup();
+    literals
+}
+
+/// Checks whether assigning the negation of all literals of `clause` and
+/// unit propagation over `clauses` lead to a conflict (reverse unit propagation)
+fn is_rup(clauses: &[Vec<Literal>], clause: &[Literal], num_variables: usize) -> bool {
+    let mut values = vec![VariableValue::Unset; num_variables];
+    for literal in clause {
+        // Tautologies are implied by any clauses
+        if literal.is_false(&values) {
+            return true;
+        }
+        values[literal.var()] = VariableValue::from_bool(!literal.polarity());
+    }
+
+    // Propagate until a fixpoint or a conflict
+    loop {
+        let mut changed = false;
+        for current in clauses {
+            if current.iter().any(|literal| literal.is_true(&values)) {
+                continue;
+            }
+            // Clauses may repeat literals
+            let mut unset = current.iter().filter(|literal| literal.is_unset(&values));
+            let first = unset.next();
+            match (first, unset.find(|&literal| Some(literal) != first)) {
+                (None, _) => return true,
+                (Some(literal), None) => {
+                    values[literal.var()] = VariableValue::from_bool(literal.polarity());
+                    changed = true;
+                }
+                _ => {}
+            }
+        }
+        if !changed {
+            return false;
+        }
+    }
+}
+
+/// Checks a DRAT proof that `clauses` are unsatisfiable; every added clause
+/// must be implied by the current clauses through reverse unit propagation,
+/// and the proof must add the empty clause; deletions of clauses not present
+/// are ignored
+///
+/// Clauses are propagated naively without watched literals, which suffices
+/// for validating the proofs of small instances.
+#[must_use]
+pub fn check_drat(clauses: &[Vec<Literal>], proof: &[ProofStep]) -> bool {
+    let num_variables = clauses
+        .iter()
+        .chain(proof.iter().map(|step| match step {
+            ProofStep::Add(clause) | ProofStep::Delete(clause) => clause,
+        }))
+        .flatten()
+        .map(|literal| literal.var() + 1)
+        .max()
+        .unwrap_or(0);
+
+    let mut current: Vec<Vec<Literal>> = clauses.to_vec();
+    for step in proof {
+        match step {
+            ProofStep::Add(clause) => {
+                if !is_rup(&current, clause, num_variables) {
+                    return false;
+                }
+                if clause.is_empty() {
+                    return true;
+                }
+                current.push(clause.clone());
+            }
+            ProofStep::Delete(clause) => {
+                let clause = normalized(clause);
+                if let Some(idx) = current.iter().position(|other| normalized(other) == clause) {
+                    current.swap_remove(idx);
+                }
+            }
+        }
+    }
+    false
+}
+
+/// Checks an LRAT proof that `clauses` are unsatisfiable, where clause `i` of
+/// `clauses` has the identifier `i + 1`; every added clause must have an
+/// unused identifier, and assigning the negation of its literals must make
+/// each of its hints unit in turn until the last one is falsified; the proof
+/// must add the empty clause
+#[must_use]
+pub fn check_lrat(clauses: &[Vec<Literal>], proof: &[LratStep]) -> bool {
+    let mut current: HashMap<ClauseId, &[Literal]> = (1..)
+        .zip(clauses)
+        .map(|(id, clause)| (id, clause.as_slice()))
+        .collect();
+    let num_variables = clauses
+        .iter()
+        .chain(proof.iter().filter_map(|step| match step {
+            LratStep::Add { literals, .. } => Some(literals),
+            LratStep::Delete(_) => None,
+        }))
+        .flatten()
+        .map(|literal| literal.var() + 1)
+        .max()
+        .unwrap_or(0);
+
+    for step in proof {
+        match step {
+            LratStep::Add {
+                id,
+                literals,
+                hints,
+            } => {
+                if current.contains_key(id)
+                    || !is_implied_by_hints(&current, literals, hints, num_variables)
+                {
+                    return false;
+                }
+                if literals.is_empty() {
+                    return true;
+                }
+                current.insert(*id, literals);
+            }
+            LratStep::Delete(ids) => {
+                for id in ids {
+                    current.remove(id);
+                }
+            }
+        }
+    }
+    false
+}
+
+/// Checks whether assigning the negation of all literals of `clause` makes
+/// the clauses `hints` unit one after another until the last one is falsified
+fn is_implied_by_hints(
+    current: &HashMap<ClauseId, &[Literal]>,
+    clause: &[Literal],
+    hints: &[ClauseId],
+    num_variables: usize,
+) -> bool {
+    let mut values = vec![VariableValue::Unset; num_variables];
+    for literal in clause {
+        // Tautologies are implied by any clauses
+        if literal.is_false(&values) {
+            return true;
+        }
+        values[literal.var()] = VariableValue::from_bool(!literal.polarity());
+    }
+
+    for hint in hints {
+        let Some(hint_clause) = current.get(hint) else {
+            return false;
+        };
+        if hint_clause.iter().any(|literal| literal.is_true(&values)) {
+            return false;
+        }
+        // Clauses may repeat literals
+        let mut unset = hint_clause
+            .iter()
+            .filter(|literal| literal.is_unset(&values));
+        let first = unset.next();
+        match (first, unset.find(|&literal| Some(literal) != first)) {
+            (None, _) => return true,
+            (Some(literal), None) => {
+                values[literal.var()] = VariableValue::from_bool(literal.polarity());
+            }
+            _ => return false,
+        }
+    }
+    false
+}
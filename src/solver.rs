@@ -1,23 +1,48 @@
+use std::{
+    collections::HashMap,
+    hash::{DefaultHasher, Hash, Hasher},
+    io::{self, Write},
+    sync::{
+        Arc,
+        atomic::{AtomicBool, Ordering},
+    },
+    time::Instant,
+};
+
 use crate::{
-    helper::{f64_to_usize_trunc, usize_to_f64},
+    helper::{duration_in_seconds, f64_to_usize_trunc, report, usize_to_f64},
     parsing::ClauseReceiver,
-    solver::clause::{ClauseRef, Clauses, VariableMetadata, Watch},
+    proof::ClauseId,
+    solver::clause::{ClauseRef, Clauses, RelocationMap, VariableMetadata, Watch},
     solver::literal::Literal,
     solver::variable::{Variable, VariableValue},
 };
 use rand::{Rng, SeedableRng, seq::SliceRandom};
 
 pub mod clause;
+mod heap;
 pub mod literal;
 mod options;
+mod preprocess;
 mod restart;
+mod sharing;
+mod tracer;
 pub mod variable;
+mod xor;
 
-/// Verbosity level enum
-#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub use options::{
+    BranchingHeuristic, InitialPolarity, LearningScheme, MinimizationLevel, ReductionStrategy,
+    RestartStrategy, SolverOptions,
+};
+pub use sharing::ClauseExchange;
+
+/// Verbosity level enum; higher levels print everything lower levels do
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug)]
 pub enum VerbosityLevel {
     OnlyResult = 0,
-    All = 1,
+    /// Problem statistics and final summary, but no search progress
+    Summary = 1,
+    All = 2,
 }
 
 /// Enum representing the solver status exit codes
@@ -28,8 +53,24 @@ pub enum SolverExitCode {
     Unsat = 20,
 }
 
+/// Result of `Solver::run` together with its evidence
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum SolveOutcome {
+    /// Satisfiable with the value of each variable
+    Sat(Vec<bool>),
+    /// Unsatisfiable; the indices of the original clauses of the UNSAT core
+    /// in the order of their addition if `SolverOptions::unsat_core` is set,
+    /// as returned by `Solver::unsat_core`; these are indices rather than
+    /// `ClauseRef`s, which garbage collection moves and which preprocessing
+    /// removes or rewrites
+    Unsat { core: Option<Vec<usize>> },
+    /// Interrupted or out of budget
+    Unknown,
+}
+
 /// Solver statistics
 #[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct SolverStatistics {
     /// Number of variables
     pub num_variables: usize,
@@ -43,12 +84,159 @@ pub struct SolverStatistics {
     pub num_literals_in_learned_clauses: usize,
     /// Number of search (re-)starts
     pub num_restarts: usize,
+    /// Number of restarts postponed because the trail was unusually long
+    pub num_blocked_restarts: usize,
     /// Number of made decisions
     pub num_decisions: usize,
     /// Number of total conflicts
     pub num_total_conflicts: usize,
     /// Number of total propagations
     pub num_propagations: usize,
+    /// Number of variables fixed at the top level by unit clauses,
+    /// propagation, simplification, or learned units
+    pub num_top_level_units: usize,
+    /// Number of pure literals assigned during simplification
+    pub num_pure_literals: usize,
+    /// Number of failed literals found by probing
+    pub num_failed_literals: usize,
+    /// Number of variables removed by variable elimination
+    pub num_eliminated_variables: usize,
+    /// Number of clauses removed by blocked clause elimination
+    pub num_blocked_clauses: usize,
+    /// Number of clauses removed by subsumption
+    pub num_subsumed_clauses: usize,
+    /// Number of clauses strengthened by self-subsuming resolution
+    pub num_strengthened_clauses: usize,
+    /// Number of literals removed from learned clauses by vivification
+    pub num_vivified_literals: usize,
+    /// Number of literals removed from learned clauses by minimization
+    pub num_minimized_literals: usize,
+    /// Number of learned clauses imported from other solvers
+    pub num_imported_clauses: usize,
+    /// Number of original clauses skipped as duplicates of earlier ones
+    pub num_duplicate_clauses: usize,
+    /// Highest decision level reached during search
+    pub max_decision_level: usize,
+    /// Longest assignment trail reached during search
+    pub max_trail_length: usize,
+    /// Number of literals in learned clauses deleted by reduction
+    pub num_learned_literals_deleted: usize,
+    /// Number of reductions of the learned clauses
+    pub num_reductions: usize,
+    /// Number of switches into the stabilizing mode
+    pub num_stabilizations: usize,
+}
+
+/// Work done between two restarts
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct RestartWindow {
+    /// Number of conflicts in the window
+    pub num_conflicts: usize,
+    /// Number of propagations in the window
+    pub num_propagations: usize,
+}
+
+/// Progress of the search at one point in time
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct SearchSnapshot {
+    /// Current decision level
+    pub decision_level: usize,
+    /// Number of assigned variables
+    pub trail_length: usize,
+    /// Number of learned clauses kept
+    pub num_learned_clauses: usize,
+    /// Number of conflicts so far
+    pub num_conflicts: usize,
+}
+
+/// Outcome of analyzing a single conflict, passed to the conflict callback
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct ConflictInfo {
+    /// Number of literals in the learned clause
+    pub learned_clause_len: usize,
+    /// Decision level the search backtracks to
+    pub backtrack_level: usize,
+    /// Literal block distance of the learned clause
+    pub lbd: usize,
+    /// Decision level at which the conflict occurred
+    pub decision_level: usize,
+}
+
+/// Observer of analyzed conflicts
+type ConflictCallback = Box<dyn FnMut(&ConflictInfo)>;
+
+/// Progress of the search, passed to the event callback
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum SolverEvent {
+    /// A restart window begins after `number` earlier ones of this call and
+    /// allows `limit` conflicts
+    Restart { number: usize, limit: usize },
+    /// A conflict was analyzed; `total` counts all conflicts so far, and the
+    /// learned clause has the literal block distance `lbd`
+    Conflict {
+        total: usize,
+        lbd: usize,
+        backtrack_level: usize,
+    },
+    /// The learned clauses were reduced to `kept` by deleting `deleted`
+    Reduce { kept: usize, deleted: usize },
+    /// Estimated fraction of the search space covered, logged with the
+    /// search statistics
+    ProgressTick { estimate: f64 },
+}
+
+/// Observer of search events
+type EventCallback = Box<dyn FnMut(SolverEvent)>;
+
+/// Solver statistics together with derived throughput metrics
+#[derive(Clone, Copy, PartialEq, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct SolveSummary {
+    /// Raw solver statistics
+    pub statistics: SolverStatistics,
+    /// Total time in seconds
+    pub total_time: f64,
+    /// Conflicts per second
+    pub conflicts_per_second: f64,
+    /// Propagations per second
+    pub propagations_per_second: f64,
+}
+
+impl SolveSummary {
+    /// Summarize `statistics` of a run that took `total_time` seconds; the
+    /// rates are 0 if no time was measured, as before the first solve
+    #[must_use]
+    pub fn new(statistics: SolverStatistics, total_time: f64) -> Self {
+        let per_second = |count| {
+            if total_time > 0.0 {
+                usize_to_f64(count) / total_time
+            } else {
+                0.0
+            }
+        };
+        Self {
+            statistics,
+            total_time,
+            conflicts_per_second: per_second(statistics.num_total_conflicts),
+            propagations_per_second: per_second(statistics.num_propagations),
+        }
+    }
+}
+
+/// Time in seconds spent in the phases of a `solve_timed` call
+#[derive(Clone, Copy, PartialEq, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct SolveTimings {
+    /// Preprocessing before the search
+    pub preprocessing: f64,
+    /// Unit propagation during the search
+    pub propagation: f64,
+    /// Conflict analysis including the minimization of learned clauses
+    pub conflict_analysis: f64,
+    /// Whole call, including the untimed phases such as vivification
+    pub total: f64,
 }
 
 /// Used for analyzing conflicts in `analyzeConflict`
@@ -83,16 +271,90 @@ pub struct Solver {
     variable_values: Vec<VariableValue>,
     /// Stores the preferred polarity of a variable (phase saving)
     variable_polarity: Vec<bool>,
+    /// First variable whose polarity awaits `InitialPolarity::Jeroslow`
+    first_unscored_variable: Variable,
+    /// Polarities of the longest conflicting trail since the last rephasing
+    best_polarity: Vec<bool>,
+    /// Length of the trail that `best_polarity` was taken from
+    best_trail_len: usize,
+    /// Polarities of the longest conflict-free trail, used while stabilizing
+    target_polarity: Vec<bool>,
+    /// Length of the trail that `target_polarity` was taken from
+    target_trail_len: usize,
+    /// Whether the search branches on the target polarities
+    is_stabilizing: bool,
+    /// Conflicts since the last switch of the stabilizing mode
+    stabilization_counter: usize,
+    /// Exponential moving average of the trail length at conflicts
+    trail_length_ema: f64,
+    /// Number of rephasing rounds; selects the next rephasing pattern
+    num_rephases: usize,
     /// Stores metadata for all variables
     variable_metadata: Vec<VariableMetadata>,
-    /// Maintains which clauses watch each literal
+    /// Maintains which clauses with more than two literals watch each literal
     literals_watched_by: Vec<Vec<Watch>>,
-    /// Unset variables
+    /// Maintains which binary clauses watch each literal; the blocker is the
+    /// other literal of the clause
+    binary_watched_by: Vec<Vec<Watch>>,
+    /// Unset variables for random branching
     unset_variables: Vec<Variable>,
+    /// Bumped activity of each variable for VSIDS branching
+    variable_activity: Vec<f64>,
+    /// Unset variables ordered by activity for VSIDS branching
+    variable_order: heap::VariableHeap,
+    /// All variables below are assigned or eliminated for in-order branching
+    in_order_cursor: Variable,
+    /// Whether a variable was removed by variable elimination
+    eliminated: Vec<bool>,
+    /// Whether a variable is that of the blocking literal of a clause removed
+    /// by blocked clause elimination
+    blocking: Vec<bool>,
+    /// Whether a variable is protected from pure literal, variable, and blocked
+    /// clause elimination
+    frozen: Vec<bool>,
+    /// Whether a variable may be picked for branching before all others are
+    /// assigned
+    decision_variables: Vec<bool>,
+    /// Whether a variable occurs in an added clause or XOR constraint
+    occurs: Vec<bool>,
+    /// Clauses removed by variable and blocked clause elimination, in the order
+    /// of their removal
+    eliminated_clauses: Vec<preprocess::Elimination>,
+    /// Literals assigned by pure literal elimination, which are not implied
+    /// and thus undone before clauses or assumptions could contradict them;
+    /// clauses satisfied by these only are kept
+    pure_literals: Vec<Literal>,
+    /// Variables whose values `extend_model` flipped to satisfy blocked clauses
+    flipped_by_extension: Vec<Variable>,
+    /// Literals assumed true by the current `solve` call
+    assumptions: Vec<Literal>,
+    /// Literals assumed true by every `solve` call until popped, in the order
+    /// of `push_assumption`
+    assumption_stack: Vec<Literal>,
+    /// Assumptions responsible for the last UNSAT result, if any
+    failed_assumptions: Vec<Literal>,
+    /// XOR constraints over at least two variables each
+    xor_rows: Vec<xor::XorRow>,
+    /// Indices of the XOR rows watching each variable
+    xor_watches: Vec<Vec<usize>>,
+    /// Index of the next trail literal to propagate through the XOR rows
+    xor_propagation_head: usize,
+    /// Position in the XOR watches of the variable at `xor_propagation_head`
+    /// to continue from after an implication
+    xor_watch_position: usize,
+    /// Sorted original clauses added so far by their hashes, which filter
+    /// the candidates compared on the next addition; only filled with
+    /// duplicate clause detection and cleared after preprocessing
+    original_clauses_by_hash: HashMap<u64, Vec<Vec<Literal>>>,
+    /// Marks of the literals of the clause being added, indexed by
+    /// `Literal::repr()`; all unmarked between additions
+    literal_seen: Vec<bool>,
 
     // -- Solver state
     /// Logging level
     logging_level: VerbosityLevel,
+    /// Solver options
+    options: SolverOptions,
     /// Maximum number of learned clauses allowed
     /// (is dynamically scaled, therefore `double`)
     max_learned_clauses: f64,
@@ -100,16 +362,60 @@ pub struct Solver {
     learned_size_adjust_on_conflict: f64,
     /// Specifies after how many conflicts to adjust the learned clauses size
     learned_size_adjust_count: usize,
+    /// Conflicts since the last scheduled reduction of the learned clauses
+    reduce_db_counter: usize,
+    /// Conflicts between the last and the next scheduled reduction
+    reduce_db_interval: f64,
+    /// Amount by which learned clause activities are bumped
+    clause_activity_increment: f64,
+    /// Amount by which variable activities are bumped
+    variable_activity_increment: f64,
+    /// Top-level trail length without the pure literals after the last
+    /// probing round that reached every variable
+    last_probe_trail_len: Option<usize>,
+    /// Variable the next probing round starts from
+    next_probe_variable: Variable,
     /// Random generator
     random_gen: rand::rngs::SmallRng,
+    /// Whether the instance may still be satisfiable; `false` once a clause
+    /// added or a search proved it UNSAT
+    ok: bool,
     /// Solver statistics
     stats: SolverStatistics,
+    /// Work done per restart window of the last `solve` call
+    restart_windows: Vec<RestartWindow>,
+    /// Time spent in `solve` in seconds
+    solve_time: f64,
+    /// Time spent per phase, measured only during `solve_timed`
+    timings: Option<SolveTimings>,
+    /// Called after each analyzed conflict
+    conflict_callback: Option<ConflictCallback>,
+    /// Outcome of the most recently analyzed conflict
+    last_conflict: Option<ConflictInfo>,
+    /// Called at restarts, conflicts, reductions, and progress ticks
+    event_callback: Option<EventCallback>,
+    /// Stops the search once set
+    interrupt: Option<Arc<AtomicBool>>,
+    /// Stops the search once the number of propagations exceeds it
+    propagation_limit: Option<usize>,
+    /// Exchange of learned clauses with other solvers
+    sharing: Option<sharing::SharingEndpoint>,
+    /// Occurrence lists of the original clauses, maintained while preprocessing
+    occurrence_lists: Option<preprocess::OccurrenceLists>,
+    /// Clause identifiers of the proof being written
+    proof: Option<tracer::ProofTracer>,
 }
 
 impl Solver {
     /// Create a new solver
     #[must_use]
     pub fn new(logging_level: VerbosityLevel) -> Self {
+        Self::with_options(logging_level, SolverOptions::default())
+    }
+
+    /// Create a new solver with the given options
+    #[must_use]
+    pub fn with_options(logging_level: VerbosityLevel, options: SolverOptions) -> Self {
         Self {
             clauses: Clauses::default(),
             learned_clauses: Clauses::default(),
@@ -118,15 +424,66 @@ impl Solver {
             trail_propagation_head: 0,
             variable_values: Vec::new(),
             variable_polarity: Vec::new(),
+            first_unscored_variable: 0,
+            best_polarity: Vec::new(),
+            best_trail_len: 0,
+            target_polarity: Vec::new(),
+            target_trail_len: 0,
+            is_stabilizing: false,
+            stabilization_counter: 0,
+            trail_length_ema: 0.0,
+            num_rephases: 0,
             variable_metadata: Vec::new(),
             literals_watched_by: Vec::new(),
+            binary_watched_by: Vec::new(),
             unset_variables: Vec::new(),
+            variable_activity: Vec::new(),
+            variable_order: heap::VariableHeap::new(
+                options.branching == BranchingHeuristic::DeterministicVsids,
+            ),
+            in_order_cursor: 0,
+            eliminated: Vec::new(),
+            blocking: Vec::new(),
+            frozen: Vec::new(),
+            decision_variables: Vec::new(),
+            occurs: Vec::new(),
+            eliminated_clauses: Vec::new(),
+            pure_literals: Vec::new(),
+            flipped_by_extension: Vec::new(),
+            assumptions: Vec::new(),
+            assumption_stack: Vec::new(),
+            failed_assumptions: Vec::new(),
+            xor_rows: Vec::new(),
+            xor_watches: Vec::new(),
+            xor_propagation_head: 0,
+            xor_watch_position: 0,
+            original_clauses_by_hash: HashMap::new(),
+            literal_seen: Vec::new(),
             logging_level,
+            options,
             max_learned_clauses: 0.0,
             learned_size_adjust_on_conflict: 100.0,
             learned_size_adjust_count: 100,
-            random_gen: rand::rngs::SmallRng::seed_from_u64(42),
+            reduce_db_counter: 0,
+            reduce_db_interval: 0.0,
+            clause_activity_increment: 1.0,
+            variable_activity_increment: 1.0,
+            last_probe_trail_len: None,
+            next_probe_variable: 0,
+            random_gen: rand::rngs::SmallRng::seed_from_u64(options.random_seed),
+            ok: true,
             stats: SolverStatistics::default(),
+            restart_windows: Vec::new(),
+            solve_time: 0.0,
+            timings: None,
+            conflict_callback: None,
+            event_callback: None,
+            last_conflict: None,
+            interrupt: None,
+            propagation_limit: None,
+            sharing: None,
+            occurrence_lists: None,
+            proof: options.unsat_core.then(tracer::ProofTracer::for_unsat_core),
         }
     }
 
@@ -136,57 +493,1037 @@ impl Solver {
         self.stats.num_variables
     }
 
+    /// Number of variables not assigned at the top level
+    #[must_use]
+    pub fn num_free_variables(&self) -> usize {
+        let top_level_assigned = self
+            .trail_separators
+            .first()
+            .copied()
+            .unwrap_or(self.trail.len());
+        self.stats.num_variables - top_level_assigned
+    }
+
+    /// Number of assigned variables, i.e., the length of the trail
+    #[must_use]
+    pub const fn num_assigned(&self) -> usize {
+        self.trail.len()
+    }
+
+    /// Current progress of the search, cheap enough to poll frequently
+    #[must_use]
+    pub const fn snapshot(&self) -> SearchSnapshot {
+        SearchSnapshot {
+            decision_level: self.decision_level(),
+            trail_length: self.trail.len(),
+            num_learned_clauses: self.stats.num_learned_clauses,
+            num_conflicts: self.stats.num_total_conflicts,
+        }
+    }
+
     /// Number of clauses
     #[must_use]
     pub const fn num_clauses(&self) -> usize {
         self.stats.num_clauses
     }
 
+    /// Number of original clauses of each length, indexed by the length; unit
+    /// clauses are assigned instead of stored and are not counted
+    #[must_use]
+    pub fn clause_length_histogram(&self) -> Vec<usize> {
+        let mut histogram = Vec::new();
+        for clause_ref in self.clauses.refs() {
+            let len = self.clauses[clause_ref].len();
+            if histogram.len() <= len {
+                histogram.resize(len + 1, 0);
+            }
+            histogram[len] += 1;
+        }
+        histogram
+    }
+
+    /// Solver options
+    #[must_use]
+    pub const fn options(&self) -> &SolverOptions {
+        &self.options
+    }
+
     /// Solver statistics
     #[must_use]
     pub const fn statistics(&self) -> &SolverStatistics {
         &self.stats
     }
 
+    /// Conflicts and propagations per restart window of the last `solve` call
+    #[must_use]
+    pub fn restart_windows(&self) -> &[RestartWindow] {
+        &self.restart_windows
+    }
+
+    /// Estimated heap memory used by the solver in bytes, based on the
+    /// capacities of its clause arenas, watch lists, and per-variable vectors
+    #[must_use]
+    pub fn memory_usage_bytes(&self) -> usize {
+        fn vec_bytes<T>(vec: &Vec<T>) -> usize {
+            vec.capacity() * size_of::<T>()
+        }
+        let watch_list_bytes = |watch_lists: &Vec<Vec<Watch>>| {
+            vec_bytes(watch_lists) + watch_lists.iter().map(vec_bytes).sum::<usize>()
+        };
+        let eliminated_clause_bytes = vec_bytes(&self.eliminated_clauses)
+            + self
+                .eliminated_clauses
+                .iter()
+                .map(preprocess::Elimination::heap_size_bytes)
+                .sum::<usize>();
+        self.clauses.heap_size_bytes()
+            + self.learned_clauses.heap_size_bytes()
+            + watch_list_bytes(&self.literals_watched_by)
+            + watch_list_bytes(&self.binary_watched_by)
+            + vec_bytes(&self.trail)
+            + vec_bytes(&self.trail_separators)
+            + vec_bytes(&self.variable_values)
+            + vec_bytes(&self.variable_polarity)
+            + vec_bytes(&self.best_polarity)
+            + vec_bytes(&self.target_polarity)
+            + vec_bytes(&self.variable_metadata)
+            + vec_bytes(&self.unset_variables)
+            + vec_bytes(&self.variable_activity)
+            + self.variable_order.heap_size_bytes()
+            + vec_bytes(&self.eliminated)
+            + vec_bytes(&self.blocking)
+            + vec_bytes(&self.frozen)
+            + vec_bytes(&self.decision_variables)
+            + vec_bytes(&self.occurs)
+            + eliminated_clause_bytes
+            + vec_bytes(&self.pure_literals)
+            + vec_bytes(&self.xor_rows)
+            + self
+                .xor_rows
+                .iter()
+                .map(xor::XorRow::heap_size_bytes)
+                .sum::<usize>()
+            + vec_bytes(&self.xor_watches)
+            + self.xor_watches.iter().map(vec_bytes).sum::<usize>()
+            + self.original_clauses_by_hash.capacity()
+                * (size_of::<u64>() + size_of::<Vec<Vec<Literal>>>())
+            + self
+                .original_clauses_by_hash
+                .values()
+                .map(|clauses| vec_bytes(clauses) + clauses.iter().map(vec_bytes).sum::<usize>())
+                .sum::<usize>()
+            + vec_bytes(&self.literal_seen)
+            + vec_bytes(&self.restart_windows)
+    }
+
+    /// Solver statistics with throughput metrics based on the time spent in `solve`
+    #[must_use]
+    pub fn summary(&self) -> SolveSummary {
+        SolveSummary::new(self.stats, self.solve_time)
+    }
+
+    /// Solver summary serialized as JSON
+    ///
+    /// # Panics
+    ///
+    /// Never; serializing plain numbers cannot fail
+    #[cfg(feature = "serde")]
+    #[must_use]
+    pub fn statistics_json(&self) -> String {
+        serde_json::to_string(&self.summary()).expect("statistics are serializable")
+    }
+
+    /// Adds a clause given as DIMACS-style signed integers, where `n` and `-n`
+    /// are the positive and negative literals of the `n`-th variable; the
+    /// variable space grows to the largest variable as needed; returns `false`
+    /// if the instance is found to be UNSAT
+    ///
+    /// # Panics
+    ///
+    /// If a literal is `0`, which DIMACS reserves as the clause terminator, or
+    /// exceeds `SolverOptions::max_variables`
+    pub fn add_clause_ints(&mut self, lits: &[i32]) -> bool {
+        assert!(!lits.contains(&0), "0 is not a valid literal");
+        let literals: Vec<Literal> = lits.iter().copied().map(Literal::from_dimacs).collect();
+        self.add_clause_incremental(&literals)
+    }
+
+    /// Adds a clause before or between calls to `solve`; the variable space
+    /// grows to the largest variable as needed; returns `false` if the
+    /// instance is found to be UNSAT
+    ///
+    /// The model of an earlier call is discarded, whereas the learned clauses,
+    /// saved polarities, clause activities, statistics, and top-level facts
+    /// carry over, as all of them remain valid for the extended instance.
+    /// Pure literals are undone, as the added clause may contradict them.
+    /// Variables removed by variable elimination or blocking removed clauses
+    /// must not occur in added clauses; freeze them before the first `solve`.
+    /// The model repair flips the blocking literals, which could falsify the
+    /// added clause; the other variables of blocked clauses may occur.
+    ///
+    /// # Panics
+    ///
+    /// If a literal is over a variable removed by variable elimination or
+    /// blocking a clause removed by blocked clause elimination, or exceeds
+    /// `SolverOptions::max_variables`
+    pub fn add_clause_incremental(&mut self, literals: &[Literal]) -> bool {
+        // Clauses are added on the top level with a retracted model
+        self.revert_trail(0);
+        self.retract_extended_model();
+
+        if let Some(max_var) = literals.iter().map(Literal::var).max()
+            && max_var >= self.num_variables()
+        {
+            assert!(
+                self.create_variables(max_var + 1),
+                "variable {max_var} exceeds the maximum number of variables"
+            );
+        }
+        for literal in literals {
+            assert!(
+                !self.eliminated[literal.var()],
+                "variable {} was eliminated",
+                literal.var()
+            );
+            assert!(
+                !self.blocking[literal.var()],
+                "variable {} blocks a clause removed by blocked clause elimination",
+                literal.var()
+            );
+        }
+        self.add_clause(literals)
+    }
+
+    /// Sets the saved polarity of `var`, which branching tries first; this
+    /// only biases the search and does not constrain the model
+    ///
+    /// # Panics
+    ///
+    /// If `var` is not a variable of the instance
+    pub fn set_initial_phase(&mut self, var: Variable, polarity: bool) {
+        assert!(var < self.num_variables(), "unknown variable {var}");
+        self.variable_polarity[var] = polarity;
+    }
+
+    /// Sets the saved polarities of the first `phases.len()` variables like
+    /// `set_initial_phase`
+    ///
+    /// # Panics
+    ///
+    /// If there are more phases than variables
+    pub fn set_initial_phases(&mut self, phases: &[bool]) {
+        assert!(
+            phases.len() <= self.num_variables(),
+            "more phases than variables"
+        );
+        self.variable_polarity[..phases.len()].copy_from_slice(phases);
+    }
+
+    /// Registers `callback` to observe each conflict right after it is
+    /// analyzed; replaces any earlier callback
+    pub fn set_conflict_callback(&mut self, callback: impl FnMut(&ConflictInfo) + 'static) {
+        self.conflict_callback = Some(Box::new(callback));
+    }
+
+    /// Removes the conflict callback
+    pub fn clear_conflict_callback(&mut self) {
+        self.conflict_callback = None;
+    }
+
+    /// Registers `callback` to observe restarts, conflicts, reductions, and
+    /// progress ticks as typed events, such as for a front end; replaces any
+    /// earlier callback
+    pub fn set_event_callback(&mut self, callback: impl FnMut(SolverEvent) + 'static) {
+        self.event_callback = Some(Box::new(callback));
+    }
+
+    /// Removes the event callback
+    pub fn clear_event_callback(&mut self) {
+        self.event_callback = None;
+    }
+
+    /// Passes `event` to the event callback, if any
+    fn emit(&mut self, event: SolverEvent) {
+        if let Some(callback) = &mut self.event_callback {
+            callback(event);
+        }
+    }
+
+    /// Literal block distance of the clause learned from the most recent
+    /// conflict, or `None` if no conflict was analyzed yet
+    #[must_use]
+    pub fn last_learned_lbd(&self) -> Option<u32> {
+        self.last_conflict
+            .map(|info| u32::try_from(info.lbd).expect("LBD is bounded by the number of variables"))
+    }
+
+    /// Number of literals of the clause learned from the most recent conflict,
+    /// or `None` if no conflict was analyzed yet
+    #[must_use]
+    pub fn last_learned_size(&self) -> Option<usize> {
+        self.last_conflict.map(|info| info.learned_clause_len)
+    }
+
+    /// Shares `flag` with other threads; once it is set, `solve` returns
+    /// `Unknown` at the next decision or restart; the flag is not reset
+    pub fn set_interrupt(&mut self, flag: Arc<AtomicBool>) {
+        self.interrupt = Some(flag);
+    }
+
+    /// Whether the interrupt flag is set or the propagation budget is exhausted
+    fn is_interrupted(&self) -> bool {
+        self.interrupt
+            .as_ref()
+            .is_some_and(|flag| flag.load(Ordering::Relaxed))
+            || self
+                .propagation_limit
+                .is_some_and(|limit| self.stats.num_propagations > limit)
+    }
+
+    /// Protects `var` from pure literal, variable, and blocked clause
+    /// elimination, so that clauses over it can be added after solving; freeze
+    /// variables before the first `solve` as variable and blocked clause
+    /// eliminations of earlier calls remain
+    ///
+    /// # Panics
+    ///
+    /// If `var` is not a variable of the instance
+    pub fn freeze(&mut self, var: Variable) {
+        assert!(var < self.num_variables(), "unknown variable {var}");
+        self.frozen[var] = true;
+    }
+
+    /// Restricts branching to `vars`, such as the inputs of an encoding with
+    /// auxiliary variables; all variables are decision variables by default
+    ///
+    /// The other variables are left to propagation. Should a model leave some
+    /// of them unassigned, they are branched on once all decision variables
+    /// are assigned, so that models stay complete.
+    ///
+    /// # Panics
+    ///
+    /// If a variable of `vars` is not a variable of the instance
+    pub fn set_decision_variables(&mut self, vars: &[Variable]) {
+        self.decision_variables.fill(false);
+        for &var in vars {
+            assert!(var < self.num_variables(), "unknown variable {var}");
+            self.decision_variables[var] = true;
+        }
+    }
+
+    /// Whether `var` occurs in any added clause or XOR constraint, even if
+    /// the clause was satisfied or simplified away
+    ///
+    /// # Panics
+    ///
+    /// If `var` is not a variable of the instance
+    #[must_use]
+    pub fn var_occurs(&self, var: Variable) -> bool {
+        assert!(var < self.num_variables(), "unknown variable {var}");
+        self.occurs[var]
+    }
+
+    /// Variables that occur in no added clause or XOR constraint; they are
+    /// unconstrained and may take any value in a model
+    #[must_use]
+    pub fn unused_variables(&self) -> Vec<Variable> {
+        (0..self.num_variables())
+            .filter(|&var| !self.occurs[var])
+            .collect()
+    }
+
+    /// Activities of the variables used by `BranchingHeuristic::Vsids` and
+    /// `DeterministicVsids`; all zero with random or in-order branching
+    #[must_use]
+    pub fn variable_activities(&self) -> &[f64] {
+        &self.variable_activity
+    }
+
+    /// Assigned literals in assignment order; after `solve` returned SAT, this
+    /// includes the decisions and implications that led to the model
+    #[must_use]
+    pub fn trail(&self) -> &[Literal] {
+        &self.trail
+    }
+
+    /// Decision level at which `var` was assigned; only meaningful while
+    /// `var` is assigned
+    #[must_use]
+    pub fn decision_level_of(&self, var: Variable) -> usize {
+        self.variable_metadata[var].decision_level
+    }
+
+    /// Clause that implied the value of `var`; `None` for decisions and
+    /// top-level facts, whose reasons may have been removed; only meaningful
+    /// while `var` is assigned
+    #[must_use]
+    pub fn reason_of(&self, var: Variable) -> Option<ClauseRef> {
+        let metadata = &self.variable_metadata[var];
+        (metadata.reason_clause_idx.valid() && metadata.decision_level > 0)
+            .then_some(metadata.reason_clause_idx)
+    }
+
+    /// Literals of an original or learned clause, such as a reason; a reason
+    /// has the implied literal at position 0
+    #[must_use]
+    pub fn clause(&self, clause_ref: ClauseRef) -> &[Literal] {
+        self.clause_at(clause_ref)
+    }
+
+    /// Literals of the original clauses in order of creation; clauses removed
+    /// by preprocessing are skipped, and top-level simplification may have
+    /// removed false literals
+    pub fn original_clauses(&self) -> impl Iterator<Item = &[Literal]> + '_ {
+        self.clauses
+            .refs()
+            .map(|clause_ref| &self.clauses[clause_ref])
+    }
+
+    /// Literals of the learned clauses currently kept, in order of creation
+    pub fn learned_clauses(&self) -> impl Iterator<Item = &[Literal]> + '_ {
+        self.learned_clauses
+            .refs()
+            .map(|clause_ref| &self.learned_clauses[clause_ref])
+    }
+
     /// Contains the model if SAT
     #[must_use]
     pub const fn model(&self) -> &Vec<VariableValue> {
         &self.variable_values
     }
 
+    /// Partial model of the last SAT result; variables that no clause depends
+    /// on are `None` and may take any value
+    ///
+    /// Variables are unset greedily in index order, so the partial model is
+    /// minimal but not necessarily minimum. Variables implied on the top
+    /// level or occurring in XOR constraints are kept, as the clauses they
+    /// satisfied may have been removed by simplification; pure literals keep
+    /// the clauses they satisfy.
+    #[must_use]
+    pub fn minimize_model(&self) -> Vec<Option<bool>> {
+        let mut partial_model: Vec<Option<bool>> = self
+            .variable_values
+            .iter()
+            .map(|value| (!value.is_unset()).then(|| value.is_true()))
+            .collect();
+
+        // Count the true literals of each clause, including eliminated ones
+        let clauses: Vec<&[Literal]> = self
+            .original_clauses()
+            .chain(
+                self.eliminated_clauses
+                    .iter()
+                    .flat_map(preprocess::Elimination::clauses)
+                    .map(Vec::as_slice),
+            )
+            .collect();
+        let mut num_true_literals = vec![0; clauses.len()];
+        let mut satisfied_clauses = vec![Vec::new(); self.num_variables()];
+        for (idx, clause) in clauses.iter().enumerate() {
+            for literal in clause
+                .iter()
+                .filter(|literal| literal.is_true(&self.variable_values))
+            {
+                num_true_literals[idx] += 1;
+                satisfied_clauses[literal.var()].push(idx);
+            }
+        }
+
+        let mut required = vec![false; self.num_variables()];
+        let top_level_assigned = self
+            .trail_separators
+            .first()
+            .copied()
+            .unwrap_or(self.trail.len());
+        for literal in &self.trail[..top_level_assigned] {
+            required[literal.var()] = true;
+        }
+        for literal in &self.pure_literals {
+            required[literal.var()] = false;
+        }
+        for &var in self.xor_rows.iter().flat_map(xor::XorRow::vars) {
+            required[var] = true;
+        }
+
+        // Unset variables whose clauses are all satisfied by other literals
+        for var in 0..self.num_variables() {
+            if required[var]
+                || partial_model[var].is_none()
+                || satisfied_clauses[var]
+                    .iter()
+                    .any(|&idx| num_true_literals[idx] == 1)
+            {
+                continue;
+            }
+            for &idx in &satisfied_clauses[var] {
+                num_true_literals[idx] -= 1;
+            }
+            partial_model[var] = None;
+        }
+        partial_model
+    }
+
     /// Solves the loaded problem instance
     pub fn solve(&mut self) -> SolverExitCode {
-        // Check that clauses are non-empty
-        if self.num_variables() == 0 || self.num_clauses() == 0 {
-            return SolverExitCode::Unknown;
+        self.solve_with_output(&mut io::stdout())
+    }
+
+    /// Solves the loaded problem instance; search progress is written to `out`
+    pub fn solve_with_output(&mut self, out: &mut dyn Write) -> SolverExitCode {
+        let start_time = Instant::now();
+        self.failed_assumptions.clear();
+        self.assumptions
+            .splice(0..0, self.assumption_stack.iter().copied());
+        let status = if self.ok {
+            self.solve_inner(out)
+        } else {
+            SolverExitCode::Unsat
+        };
+        self.assumptions.clear();
+        if status == SolverExitCode::Unsat && self.failed_assumptions.is_empty() {
+            self.ok = false;
+        }
+        self.solve_time += duration_in_seconds(start_time, Instant::now());
+        status
+    }
+
+    /// Solves the loaded problem instance like `solve` and returns the model
+    /// or the UNSAT core with the result
+    pub fn run(&mut self) -> SolveOutcome {
+        match self.solve() {
+            SolverExitCode::Sat => SolveOutcome::Sat(
+                self.variable_values
+                    .iter()
+                    .map(|value| value.is_true())
+                    .collect(),
+            ),
+            SolverExitCode::Unsat => SolveOutcome::Unsat {
+                core: self.options.unsat_core.then(|| self.unsat_core()),
+            },
+            SolverExitCode::Unknown => SolveOutcome::Unknown,
+        }
+    }
+
+    /// Solves the loaded problem instance like `solve` and measures the time
+    /// spent in its phases
+    pub fn solve_timed(&mut self) -> (SolverExitCode, SolveTimings) {
+        let start_time = Instant::now();
+        self.timings = Some(SolveTimings::default());
+        let status = self.solve();
+        let mut timings = self.timings.take().unwrap_or_default();
+        timings.total = duration_in_seconds(start_time, Instant::now());
+        (status, timings)
+    }
+
+    /// Adds the time since `start` to the phase of the timings selected by
+    /// `phase`; `start` is `None` unless timings are measured
+    fn record_time(&mut self, start: Option<Instant>, phase: fn(&mut SolveTimings) -> &mut f64) {
+        if let (Some(timings), Some(start)) = (&mut self.timings, start) {
+            *phase(timings) += duration_in_seconds(start, Instant::now());
+        }
+    }
+
+    /// Solves the loaded problem instance under the assumption that all
+    /// `assumptions` are true; if UNSAT only under the assumptions,
+    /// `failed_assumptions` returns the assumptions involved
+    ///
+    /// The assumptions only hold for this call. Their variables are frozen,
+    /// and pure literals of earlier calls are undone; with variable or blocked
+    /// clause elimination, freeze them before the first `solve` as these
+    /// eliminations of earlier calls remain.
+    ///
+    /// # Panics
+    ///
+    /// If an assumption is not over a variable of the instance
+    pub fn solve_with_assumptions(&mut self, assumptions: &[Literal]) -> SolverExitCode {
+        for assumption in assumptions {
+            self.freeze(assumption.var());
+        }
+        self.assumptions = assumptions.to_vec();
+        self.solve()
+    }
+
+    /// Assumes `literal` true for every following `solve` call until it is
+    /// popped by `pop_assumption`; the assumptions of `solve_with_assumptions`
+    /// come after the pushed ones
+    ///
+    /// The search never derives clauses from assumptions, so learned clauses,
+    /// activities, and saved polarities carry over when assumptions are pushed
+    /// or popped. Both revert the trail to the top level, which discards the
+    /// model of the last call; this also unlocks the reasons of the literals
+    /// implied by the assumptions. The variable of `literal` is frozen, and
+    /// the next `solve` undoes the pure literals of earlier calls; with
+    /// variable or blocked clause elimination, freeze it before the first
+    /// `solve` as these eliminations of earlier calls remain.
+    ///
+    /// # Panics
+    ///
+    /// If `literal` is not over a variable of the instance
+    pub fn push_assumption(&mut self, literal: Literal) {
+        self.freeze(literal.var());
+        self.revert_trail(0);
+        self.retract_extended_model();
+        self.assumption_stack.push(literal);
+    }
+
+    /// Removes and returns the most recently pushed assumption, or `None` if
+    /// no assumption is pushed; see `push_assumption`
+    pub fn pop_assumption(&mut self) -> Option<Literal> {
+        self.revert_trail(0);
+        self.retract_extended_model();
+        self.assumption_stack.pop()
+    }
+
+    /// Assumptions pushed by `push_assumption` and not yet popped, oldest first
+    #[must_use]
+    pub fn pushed_assumptions(&self) -> &[Literal] {
+        &self.assumption_stack
+    }
+
+    /// Solves the loaded problem instance like `solve`, but returns `Unknown`
+    /// once this call made more than `max_propagations` propagations; the
+    /// budget is checked at every decision and restart
+    ///
+    /// Unlike time limits, the budget gives the same result on every machine.
+    /// The propagations of preprocessing count towards the budget, but
+    /// preprocessing always runs to completion.
+    pub fn solve_with_propagation_budget(&mut self, max_propagations: usize) -> SolverExitCode {
+        self.propagation_limit = Some(self.stats.num_propagations.saturating_add(max_propagations));
+        let status = self.solve();
+        self.propagation_limit = None;
+        status
+    }
+
+    /// Subset of the assumptions of the last `solve` call, pushed ones
+    /// included, that cannot be true together; empty unless that call returned
+    /// UNSAT because of its assumptions
+    #[must_use]
+    pub fn failed_assumptions(&self) -> &[Literal] {
+        &self.failed_assumptions
+    }
+
+    /// Assigns `assumptions` in order and propagates them without search;
+    /// returns the literals implied by the clauses beyond the assumptions, or
+    /// the clause falsified by the assumptions and their implications; the
+    /// trail is reverted to the top level afterwards
+    ///
+    /// An assumption that is already false yields the reason of its negation,
+    /// which is invalid if that negation was assumed or fixed on the top level
+    /// or if the instance is UNSAT. Pure literals of an earlier `solve` are
+    /// undone first. Assumptions over eliminated variables are not meaningful;
+    /// freeze their variables before the first `solve`.
+    ///
+    /// # Errors
+    ///
+    /// Returns the falsified clause if propagation leads to a conflict
+    ///
+    /// # Panics
+    ///
+    /// If an assumption is not over a variable of the instance
+    pub fn propagate_assumptions(
+        &mut self,
+        assumptions: &[Literal],
+    ) -> Result<Vec<Literal>, ClauseRef> {
+        self.revert_trail(0);
+        self.retract_extended_model();
+        self.restore_pure_literals();
+        if !self.ok {
+            return Err(ClauseRef::default());
         }
 
-        // Initial simplification
-        if !self.simplify() {
+        let mut result = Ok(());
+        for &assumption in assumptions {
+            if assumption.is_true(&self.variable_values) {
+                continue;
+            }
+            if assumption.is_false(&self.variable_values) {
+                // Reasons of top-level assignments may have been removed since
+                let metadata = &self.variable_metadata[assumption.var()];
+                result = Err(if metadata.decision_level == 0 {
+                    ClauseRef::default()
+                } else {
+                    metadata.reason_clause_idx
+                });
+                break;
+            }
+            self.trail_separators.push(self.trail.len());
+            self.assign_literal(assumption, ClauseRef::default());
+            let conflict = self.propagate();
+            if conflict.valid() {
+                result = Err(conflict);
+                break;
+            }
+        }
+
+        // Each assumption opened a decision level with itself as first literal
+        let implied_literals = result.map(|()| {
+            let mut is_assumption = vec![false; self.trail.len()];
+            for &separator in &self.trail_separators {
+                is_assumption[separator] = true;
+            }
+            let top_level_assigned = self
+                .trail_separators
+                .first()
+                .copied()
+                .unwrap_or(self.trail.len());
+            (top_level_assigned..self.trail.len())
+                .filter(|&idx| !is_assumption[idx])
+                .map(|idx| self.trail[idx])
+                .collect()
+        });
+        self.revert_trail(0);
+        implied_literals
+    }
+
+    /// Enumerates all models of the loaded problem instance; `callback` is
+    /// invoked with every model and stops the enumeration by returning `false`;
+    /// returns the number of models found
+    ///
+    /// Preprocessing that discards models (pure literal, variable, and blocked
+    /// clause elimination) is suspended during the enumeration; the variable
+    /// and blocked clause eliminations of an earlier `solve` remain.
+    pub fn solve_all(&mut self, mut callback: impl FnMut(&[VariableValue]) -> bool) -> usize {
+        let saved_options = self.suspend_model_discarding_preprocessing();
+        let mut num_models = 0;
+        while self.solve() == SolverExitCode::Sat {
+            num_models += 1;
+            if !callback(&self.variable_values) {
+                break;
+            }
+
+            // Block the current model by negating all of its decisions;
+            // the remaining assignments are implied by the decisions
+            let blocking_clause: Vec<Literal> = self
+                .trail_separators
+                .iter()
+                .map(|&separator| !self.trail[separator])
+                .collect();
+            self.revert_trail(0);
+            if blocking_clause.is_empty() || !self.add_clause(&blocking_clause) {
+                break;
+            }
+        }
+        self.options = saved_options;
+        num_models
+    }
+
+    /// Counts the models of the loaded problem instance without storing them
+    ///
+    /// Every found model is reduced to the assignments required to satisfy all
+    /// clauses; the remaining "don't care" variables contribute `2^(free count)`
+    /// models at once before the reduced assignment is blocked. The number of
+    /// solver calls can still grow exponentially with the number of variables,
+    /// so this is only feasible for small or highly constrained instances.
+    /// The result saturates at `u128::MAX`. As in `solve_all`, preprocessing
+    /// that discards models is suspended while counting.
+    pub fn count_models(&mut self) -> u128 {
+        let saved_options = self.suspend_model_discarding_preprocessing();
+        let mut num_models: u128 = 0;
+        while self.solve() == SolverExitCode::Sat {
+            // Top-level assignments are shared by all remaining models; the
+            // variables of XOR constraints are counted by their assignments
+            let mut required = vec![false; self.num_variables()];
+            for (var, is_required) in required.iter_mut().enumerate() {
+                *is_required = self.variable_metadata[var].decision_level == 0;
+            }
+            for &var in self.xor_rows.iter().flat_map(xor::XorRow::vars) {
+                required[var] = true;
+            }
+
+            // Keep one true literal per clause unless already satisfied by a kept one
+            for clause_ref in self.clauses.refs() {
+                let clause = &self.clauses[clause_ref];
+                if clause
+                    .iter()
+                    .any(|lit| required[lit.var()] && lit.is_true(&self.variable_values))
+                {
+                    continue;
+                }
+                if let Some(lit) = clause.iter().find(|lit| lit.is_true(&self.variable_values)) {
+                    required[lit.var()] = true;
+                }
+            }
+
+            // Count all extensions of the required assignments
+            let num_free = required.iter().filter(|&&is_required| !is_required).count();
+            let num_extensions = u32::try_from(num_free)
+                .ok()
+                .and_then(|shift| 1u128.checked_shl(shift))
+                .unwrap_or(u128::MAX);
+            num_models = num_models.saturating_add(num_extensions);
+
+            // Block the required assignments that are not fixed at the top level
+            let blocking_clause: Vec<Literal> = (0..self.num_variables())
+                .filter(|&var| required[var] && self.variable_metadata[var].decision_level > 0)
+                .map(|var| {
+                    Literal::from_var_with_polarity(var, !self.variable_values[var].is_true())
+                })
+                .collect();
+            self.revert_trail(0);
+            if blocking_clause.is_empty() || !self.add_clause(&blocking_clause) {
+                break;
+            }
+        }
+        self.options = saved_options;
+        num_models
+    }
+
+    /// Disables all preprocessing that does not preserve every model;
+    /// returns the previous options
+    fn suspend_model_discarding_preprocessing(&mut self) -> SolverOptions {
+        let saved_options = self.options;
+        self.options.pure_literal_elimination = false;
+        self.options.variable_elimination = false;
+        self.options.blocked_clause_elimination = false;
+        saved_options
+    }
+
+    /// Runs a round of failed-literal probing on the top level, as
+    /// simplification does with the `failed_literal_probing` option; returns
+    /// `false` if the instance is found to be UNSAT
+    pub fn probe(&mut self) -> bool {
+        if !self.ok {
+            return false;
+        }
+        self.revert_trail(0);
+        self.retract_extended_model();
+        self.ok = self.probe_literals();
+        self.ok
+    }
+
+    /// Failed-literal probing: tentatively assigns each unset variable and
+    /// fixes the opposite polarity at the top level if propagation conflicts;
+    /// the work is bounded by `PROBE_PROPAGATION_BUDGET` propagations, and
+    /// the next round continues after the last variable probed; returns
+    /// `false` if the instance is found to be UNSAT
+    fn probe_literals(&mut self) -> bool {
+        self.revert_trail(0);
+        if self.propagate().valid() {
+            return false;
+        }
+
+        // Probing must not overwrite the saved phases
+        let saved_polarity = self.variable_polarity.clone();
+        let propagation_limit = self.stats.num_propagations + options::PROBE_PROPAGATION_BUDGET;
+        let mut is_satisfiable = true;
+        let mut num_probed = 0;
+        'probing: while num_probed < self.num_variables() {
+            let var = self.next_probe_variable;
+            if self.stats.num_propagations >= propagation_limit {
+                break;
+            }
+            self.next_probe_variable = (var + 1) % self.num_variables();
+            num_probed += 1;
+            for polarity in [false, true] {
+                if !self.variable_values[var].is_unset() || self.eliminated[var] {
+                    break;
+                }
+
+                // Tentatively assign literal on a new decision level
+                let literal = Literal::from_var_with_polarity(var, polarity);
+                self.trail_separators.push(self.trail.len());
+                self.assign_literal(literal, ClauseRef::default());
+                let conflict = self.propagate();
+                let failed_literal_id = if conflict.valid() {
+                    self.trace_conflict(&[!literal], conflict)
+                } else {
+                    None
+                };
+                self.revert_trail(0);
+
+                // Failed literal; its negation holds at the top level
+                if conflict.valid() {
+                    self.stats.num_failed_literals += 1;
+                    self.trace_unit(!literal, failed_literal_id);
+                    self.assign_literal(!literal, ClauseRef::default());
+                    if self.propagate().valid() {
+                        is_satisfiable = false;
+                        break 'probing;
+                    }
+                }
+            }
+        }
+        self.variable_polarity = saved_polarity;
+        self.last_probe_trail_len = (num_probed == self.num_variables())
+            .then(|| self.trail.len() - self.pure_literals.len());
+        is_satisfiable
+    }
+
+    /// Runs the preprocessing preceding the search: top-level simplification,
+    /// Gaussian elimination of the XOR constraints, and the enabled subsumption,
+    /// blocked clause elimination, and variable elimination; returns `false` if
+    /// the instance is found to be UNSAT
+    pub fn preprocess(&mut self) -> bool {
+        if !self.ok {
+            return false;
+        }
+
+        // Start from the top level if a previous call left a model on the trail;
+        // pure literals are found again for the current clauses and assumptions
+        self.revert_trail(0);
+        self.retract_extended_model();
+        self.restore_pure_literals();
+        if self.options.initial_polarity == InitialPolarity::Jeroslow {
+            self.apply_jeroslow_polarities();
+        }
+
+        // The preprocessing passes share occurrence lists
+        self.build_occurrence_lists();
+        let is_satisfiable = self.ok
+            && self.simplify()
+            && self.simplify_xors()
+            && (!self.options.subsumption || (self.subsume_clauses() && self.simplify()))
+            && (!self.options.blocked_clause_elimination || self.blocked_clause_elimination())
+            && (!self.options.variable_elimination
+                || (self.eliminate_variables() && self.simplify()));
+        self.original_clauses_by_hash = HashMap::new();
+        self.occurrence_lists = None;
+        self.ok = is_satisfiable;
+        is_satisfiable
+    }
+
+    /// Writes the original clauses in DIMACS CNF format without the clauses
+    /// satisfied and the literals falsified on the top level; after
+    /// `preprocess`, this is the simplified instance, which is satisfiable
+    /// if and only if the loaded one is
+    ///
+    /// XOR constraints are not written. An instance found to be UNSAT is
+    /// written as the empty clause.
+    ///
+    /// # Errors
+    ///
+    /// If writing to `out` fails
+    pub fn write_simplified_cnf(&self, out: &mut impl Write) -> io::Result<()> {
+        if !self.ok {
+            return writeln!(out, "p cnf {} 1\n0", self.num_variables());
+        }
+        let clause_refs: Vec<ClauseRef> = self
+            .clauses
+            .refs()
+            .filter(|&clause_ref| {
+                !self
+                    .clauses
+                    .is_clause_satisfied(clause_ref, &self.variable_values)
+            })
+            .collect();
+        writeln!(out, "p cnf {} {}", self.num_variables(), clause_refs.len())?;
+        for clause_ref in clause_refs {
+            for literal in &self.clauses[clause_ref] {
+                if literal.is_unset(&self.variable_values) {
+                    write!(out, "{} ", literal.to_dimacs())?;
+                }
+            }
+            writeln!(out, "0")?;
+        }
+        Ok(())
+    }
+
+    /// Postpones a due restart by `options::RESTART_BLOCKING_DELAY` conflicts
+    /// if the trail is significantly longer than its moving average, which
+    /// suggests that the solver is close to a model; updates the average
+    fn block_restart(&mut self, num_conflicts: &mut usize, allowed_num_of_conflicts: usize) {
+        let trail_length = usize_to_f64(self.trail.len());
+        if *num_conflicts >= allowed_num_of_conflicts
+            && self.stats.num_total_conflicts > options::RESTART_BLOCKING_MIN_CONFLICTS
+            && trail_length > options::RESTART_BLOCKING_MARGIN * self.trail_length_ema
+        {
+            *num_conflicts = num_conflicts.saturating_sub(options::RESTART_BLOCKING_DELAY);
+            self.stats.num_blocked_restarts += 1;
+        }
+
+        // The first conflict initializes the average
+        if self.trail_length_ema == 0.0 {
+            self.trail_length_ema = trail_length;
+        } else {
+            self.trail_length_ema +=
+                options::TRAIL_LENGTH_EMA_ALPHA * (trail_length - self.trail_length_ema);
+        }
+    }
+
+    /// Solves the loaded problem instance (untimed)
+    fn solve_inner(&mut self, out: &mut dyn Write) -> SolverExitCode {
+        let start = self.timings.is_some().then(Instant::now);
+        let is_satisfiable = self.preprocess();
+        self.record_time(start, |timings| &mut timings.preprocessing);
+        if !is_satisfiable {
             return SolverExitCode::Unsat;
         }
 
         // Update maximum learned clauses size
         self.max_learned_clauses =
             usize_to_f64(self.num_clauses()) * options::MAX_LEARNED_CLAUSES_FACTOR;
+        self.reduce_db_counter = 0;
+        self.reduce_db_interval = usize_to_f64(self.options.reduction_first_interval.unwrap_or(0));
+        self.is_stabilizing = false;
+        self.stabilization_counter = 0;
 
         // Main loop
         self.stats.num_restarts = 0;
+        self.stats.num_blocked_restarts = 0;
+        self.restart_windows.clear();
         let mut status = SolverExitCode::Unknown;
-        while status == SolverExitCode::Unknown {
+        while status == SolverExitCode::Unknown && !self.is_interrupted() {
             // Restart search after reaching a certain number of conflicts
-            // using the Luby restart sequence
-            let restart_value = restart::luby(self.stats.num_restarts) * options::RESTART_FIRST;
-            status = self.search(restart_value);
+            let restart_value = match self.options.restart_strategy {
+                RestartStrategy::Luby => restart::luby(self.stats.num_restarts),
+                RestartStrategy::Geometric => restart::geometric(self.stats.num_restarts),
+            }
+            .saturating_mul(options::RESTART_FIRST);
+            let conflicts_before = self.stats.num_total_conflicts;
+            let propagations_before = self.stats.num_propagations;
+            self.emit(SolverEvent::Restart {
+                number: self.stats.num_restarts,
+                limit: restart_value,
+            });
+            status = self.search(restart_value, out);
             self.stats.num_restarts += 1;
+            self.restart_windows.push(RestartWindow {
+                num_conflicts: self.stats.num_total_conflicts - conflicts_before,
+                num_propagations: self.stats.num_propagations - propagations_before,
+            });
+
+            // Learn from other solvers
+            if status == SolverExitCode::Unknown && !self.import_shared_clauses() {
+                status = SolverExitCode::Unsat;
+            }
+
+            // Periodically diversify the saved polarities
+            if status == SolverExitCode::Unknown
+                && self.options.rephasing
+                && self
+                    .stats
+                    .num_restarts
+                    .is_multiple_of(options::REPHASE_RESTART_INTERVAL)
+            {
+                self.rephase();
+            }
+
+            // Periodically shorten learned clauses
+            if status == SolverExitCode::Unknown
+                && self.options.vivification
+                && self
+                    .stats
+                    .num_restarts
+                    .is_multiple_of(options::VIVIFICATION_RESTART_INTERVAL)
+                && !self.vivify_learned_clauses()
+            {
+                status = SolverExitCode::Unsat;
+            }
+        }
+
+        // Assign eliminated variables
+        if status == SolverExitCode::Sat {
+            self.extend_model();
         }
 
         // Return solver exit status
         status
     }
 
-    /// Search for a model with the given number of allowed conflicts
-    fn search(&mut self, allowed_num_of_conflicts: usize) -> SolverExitCode {
+    /// Search for a model with the given number of allowed conflicts;
+    /// progress rows are written to `out`
+    fn search(&mut self, allowed_num_of_conflicts: usize, out: &mut dyn Write) -> SolverExitCode {
         // Number of conflicts
         let mut num_conflicts = 0;
         // Currently learned clause
@@ -195,13 +1532,29 @@ impl Solver {
         // Search until finding model or reaching allowed number of conflicts
         loop {
             // Propagate currently selected variables
+            let start = self.timings.is_some().then(Instant::now);
             let conflict = self.propagate();
+            self.record_time(start, |timings| &mut timings.propagation);
 
             // Check if conflict found
             if conflict.valid() {
                 // Found conflict
                 self.stats.num_total_conflicts += 1;
+                if self.options.rephasing && self.trail.len() > self.best_trail_len {
+                    self.save_best_polarity();
+                }
+                if self.options.stabilization_interval.is_some() {
+                    self.update_stabilization();
+                }
+                self.clause_activity_increment /= options::CLAUSE_ACTIVITY_DECAY;
+                if self.uses_activities() {
+                    self.variable_activity_increment /= options::VARIABLE_ACTIVITY_DECAY;
+                }
                 num_conflicts += 1;
+                self.reduce_db_counter += 1;
+                if self.options.restart_blocking {
+                    self.block_restart(&mut num_conflicts, allowed_num_of_conflicts);
+                }
 
                 // Conflict reached outer-most layer; UNSAT
                 if self.decision_level() == 0 {
@@ -210,15 +1563,41 @@ impl Solver {
 
                 // Analyze conflict
                 learned_clause.clear();
+                let start = self.timings.is_some().then(Instant::now);
                 let backtrack_level = self.analyze_conflict(conflict, &mut learned_clause);
+                self.record_time(start, |timings| &mut timings.conflict_analysis);
+                let lbd = self.literal_block_distance(&learned_clause);
+                let conflict_info = ConflictInfo {
+                    learned_clause_len: learned_clause.len(),
+                    backtrack_level,
+                    lbd,
+                    decision_level: self.decision_level(),
+                };
+                self.last_conflict = Some(conflict_info);
+                if let Some(callback) = &mut self.conflict_callback {
+                    callback(&conflict_info);
+                }
+                self.emit(SolverEvent::Conflict {
+                    total: self.stats.num_total_conflicts,
+                    lbd,
+                    backtrack_level,
+                });
+                let learned_id = self.trace_conflict(&learned_clause, conflict);
                 self.revert_trail(backtrack_level);
+                self.export_learned_clause(&learned_clause, lbd);
 
+                // All literals up to the backtrack level were propagated before
+                debug_assert_eq!(self.trail_propagation_head, self.trail.len());
                 if learned_clause.len() == 1 {
                     // Found single-literal reason for conflict, propagate
+                    self.trace_unit(learned_clause[0], learned_id);
                     self.assign_literal(learned_clause[0], ClauseRef::default());
                 } else {
                     // Else, learn clause and propagate first literal
-                    let clause_ref = self.attach_clause::<true>(learned_clause.clone());
+                    let clause_ref = self.attach_clause::<true>(&learned_clause);
+                    self.trace_attached_clause(clause_ref, learned_id);
+                    self.learned_clauses.set_lbd(clause_ref, lbd);
+                    self.bump_clause_activity(clause_ref);
                     self.assign_literal(learned_clause[0], clause_ref);
                 }
 
@@ -230,22 +1609,22 @@ impl Solver {
                         f64_to_usize_trunc(self.learned_size_adjust_on_conflict);
                     self.max_learned_clauses *= options::MAX_LEARNED_CLAUSES_INCREMENT;
 
-                    // Log progress
+                    // Report progress; the estimate takes a pass over all variables
+                    if self.event_callback.is_some() {
+                        let estimate = self.progress_estimate();
+                        self.emit(SolverEvent::ProgressTick { estimate });
+                    }
                     if self.logging_level == VerbosityLevel::All {
-                        let free_variables = self.stats.num_variables
-                            - (if self.trail_separators.is_empty() {
-                                self.trail.len()
-                            } else {
-                                self.trail_separators[0]
-                            });
                         let literals_per_learned =
                             usize_to_f64(self.stats.num_literals_in_learned_clauses)
                                 / usize_to_f64(self.stats.num_learned_clauses);
                         let progress_estimate_percent = self.progress_estimate() * 100.0;
-                        println!(
+                        report!(
+                            out,
+                            Debug,
                             "| {:>9} | {:>7} {:>8} {:>8} | {:>8.0} {:>8} {:>6.0} | {:>6.3} % |",
                             self.stats.num_total_conflicts,
-                            free_variables,
+                            self.num_free_variables(),
                             self.stats.num_clauses,
                             self.stats.num_literals_in_clauses,
                             self.max_learned_clauses,
@@ -257,8 +1636,9 @@ impl Solver {
                 }
             } else {
                 // No conflict
-                if num_conflicts >= allowed_num_of_conflicts {
-                    // Reached bound on number of conflicts; revert complete trail
+                if num_conflicts >= allowed_num_of_conflicts || self.is_interrupted() {
+                    // Reached bound on number of conflicts or interrupted;
+                    // revert complete trail
                     self.revert_trail(0);
                     return SolverExitCode::Unknown;
                 }
@@ -268,18 +1648,43 @@ impl Solver {
                     return SolverExitCode::Unsat;
                 }
 
-                // Reduce the set of learned clauses if too many
-                if usize_to_f64(self.learned_clauses.len())
-                    >= self.max_learned_clauses + usize_to_f64(self.trail.len())
+                // Reduce the set of learned clauses if too many or scheduled
+                let is_reduction_scheduled = self.options.reduction_first_interval.is_some()
+                    && usize_to_f64(self.reduce_db_counter) >= self.reduce_db_interval;
+                if is_reduction_scheduled {
+                    self.reduce_db_counter = 0;
+                    self.reduce_db_interval *= self.options.reduction_interval_growth;
+                }
+                if is_reduction_scheduled
+                    || usize_to_f64(self.learned_clauses.len())
+                        >= self.max_learned_clauses + usize_to_f64(self.trail.len())
                 {
                     self.prune_learned_clauses();
                 }
-
-                // New variable decision
+                self.collect_garbage();
+
+                // New variable decision; assumptions are decided first
+                debug_assert_eq!(self.trail_propagation_head, self.trail.len());
+                let mut next_literal = None;
+                while self.decision_level() < self.assumptions.len() {
+                    let assumption = self.assumptions[self.decision_level()];
+                    if assumption.is_true(&self.variable_values) {
+                        // Empty decision level for an already satisfied assumption
+                        self.trail_separators.push(self.trail.len());
+                    } else if assumption.is_false(&self.variable_values) {
+                        self.analyze_final(!assumption);
+                        return SolverExitCode::Unsat;
+                    } else {
+                        next_literal = Some(assumption);
+                        break;
+                    }
+                }
                 self.stats.num_decisions += 1;
-                if let Some(next_literal) = self.pick_branch_literal() {
+                if let Some(next_literal) = next_literal.or_else(|| self.pick_branch_literal()) {
                     // Increase decision level
                     self.trail_separators.push(self.trail.len());
+                    self.stats.max_decision_level =
+                        self.stats.max_decision_level.max(self.decision_level());
 
                     // Enqueue next branch literal
                     self.assign_literal(next_literal, ClauseRef::default());
@@ -291,6 +1696,38 @@ impl Solver {
         }
     }
 
+    /// Collects the assumptions responsible for `literal` being true, including
+    /// the assumption `!literal` itself, into `failed_assumptions`
+    fn analyze_final(&mut self, literal: Literal) {
+        self.failed_assumptions.clear();
+        self.failed_assumptions.push(!literal);
+        if self.decision_level() == 0 {
+            return;
+        }
+
+        // Follow the reasons back to the decisions, which are all assumptions
+        let mut variable_seen = vec![false; self.num_variables()];
+        variable_seen[literal.var()] = true;
+        for index in (self.trail_separators[0]..self.trail.len()).rev() {
+            let trail_literal = self.trail[index];
+            if !variable_seen[trail_literal.var()] {
+                continue;
+            }
+            let reason = self.variable_metadata[trail_literal.var()].reason_clause_idx;
+            if reason.valid() {
+                for j in 1..self.clause_at(reason).len() {
+                    let reason_literal = self.clause_at(reason)[j];
+                    if self.variable_metadata[reason_literal.var()].decision_level > 0 {
+                        variable_seen[reason_literal.var()] = true;
+                    }
+                }
+            } else {
+                debug_assert!(self.assumptions.contains(&trail_literal));
+                self.failed_assumptions.push(trail_literal);
+            }
+        }
+    }
+
     /// Analyze the given conflict; returns the backtrack level
     /// and the learned clause
     fn analyze_conflict(
@@ -306,42 +1743,76 @@ impl Solver {
         let mut initial_pass = true;
         let mut asserting_literal = Literal::default();
         let mut variable_seen = vec![VariableStatus::Unset; self.num_variables()];
+        let scheme = self.options.learning_scheme;
 
-        // Build learned conflict clause
-        while initial_pass || path_length > 0 {
-            debug_assert!(conflict.valid());
-            initial_pass = false;
-            let conflict_clause_len = self.clause_at(conflict).len();
-            let start = usize::from(asserting_literal.valid());
-            for j in start..conflict_clause_len {
-                let conflict_literal = self.clause_at(conflict)[j];
-
-                // Check unseen variables in clause
-                if variable_seen[conflict_literal.var()] == VariableStatus::Unset
-                    && self.variable_metadata[conflict_literal.var()].decision_level > 0
-                {
-                    variable_seen[conflict_literal.var()] = VariableStatus::IsSource;
-
-                    if self.variable_metadata[conflict_literal.var()].decision_level
-                        >= self.decision_level()
+        // Build learned conflict clause by resolving the literals on the path;
+        // other literals go into the clause directly
+        loop {
+            // Decisions of earlier levels have no reason to resolve
+            if conflict.valid() {
+                if conflict.is_learned() {
+                    self.bump_clause_activity(conflict);
+                }
+                let conflict_clause_len = self.clause_at(conflict).len();
+                let start = usize::from(!initial_pass);
+                initial_pass = false;
+                for j in start..conflict_clause_len {
+                    let conflict_literal = self.clause_at(conflict)[j];
+
+                    // Check unseen variables in clause
+                    let decision_level =
+                        self.variable_metadata[conflict_literal.var()].decision_level;
+                    if variable_seen[conflict_literal.var()] == VariableStatus::Unset
+                        && decision_level > 0
                     {
-                        path_length += 1;
-                    } else {
-                        out_learned_clause.push(conflict_literal);
+                        variable_seen[conflict_literal.var()] = VariableStatus::IsSource;
+                        if self.uses_activities() {
+                            self.bump_variable_activity(conflict_literal.var());
+                        }
+
+                        if decision_level >= self.decision_level()
+                            || scheme == LearningScheme::AllDecisions
+                        {
+                            path_length += 1;
+                        } else {
+                            out_learned_clause.push(conflict_literal);
+                        }
                     }
                 }
             }
 
-            // Select next clause to look at
+            // Literals whose reasons only contain top-level literals end the path
+            if path_length == 0 {
+                break;
+            }
+
+            // Select next literal on the path; the last one of the current
+            // level is asserting
             while variable_seen[self.trail[index - 1].var()] == VariableStatus::Unset {
                 index -= 1;
             }
             index -= 1;
-
-            asserting_literal = self.trail[index];
-            conflict = self.variable_metadata[asserting_literal.var()].reason_clause_idx;
-            variable_seen[asserting_literal.var()] = VariableStatus::Unset;
+            let literal = self.trail[index];
+            conflict = self.variable_metadata[literal.var()].reason_clause_idx;
             path_length -= 1;
+            let is_current_level =
+                self.variable_metadata[literal.var()].decision_level >= self.decision_level();
+            if is_current_level {
+                asserting_literal = literal;
+            }
+
+            // Decisions of earlier levels remain in the clause
+            if !conflict.valid() && !is_current_level {
+                out_learned_clause.push(!literal);
+            } else {
+                variable_seen[literal.var()] = VariableStatus::Unset;
+            }
+
+            // 1-UIP stops at the first literal dominating the current level;
+            // the other schemes continue to the decisions
+            if path_length == 0 && (scheme == LearningScheme::FirstUip || !conflict.valid()) {
+                break;
+            }
         }
         out_learned_clause[0] = !asserting_literal;
 
@@ -350,19 +1821,26 @@ impl Solver {
         let mut j = 1;
         while i < out_learned_clause.len() {
             // Literal needed if it has top-level assignment or is not redundant
-            if !self.variable_metadata[out_learned_clause[i].var()]
+            let literal = out_learned_clause[i];
+            let is_redundant = self.variable_metadata[literal.var()]
                 .reason_clause_idx
                 .valid()
-                || !self.is_literal_redundant_in_conflict_clause(
-                    &mut variable_seen,
-                    out_learned_clause[i],
-                )
-            {
-                out_learned_clause[j] = out_learned_clause[i];
+                && match self.options.minimization {
+                    MinimizationLevel::None => false,
+                    MinimizationLevel::Local => {
+                        self.is_literal_locally_redundant(&variable_seen, literal)
+                    }
+                    MinimizationLevel::Recursive => {
+                        self.is_literal_redundant_in_conflict_clause(&mut variable_seen, literal)
+                    }
+                };
+            if !is_redundant {
+                out_learned_clause[j] = literal;
                 j += 1;
             }
             i += 1;
         }
+        self.stats.num_minimized_literals += out_learned_clause.len() - j;
         out_learned_clause.resize(j, Literal::default());
 
         // Find correct backtrack level
@@ -461,57 +1939,374 @@ impl Solver {
         }
     }
 
+    /// Checks whether all literals of the reason for `literal` are in the
+    /// conflict clause or assigned at the top level
+    fn is_literal_locally_redundant(
+        &self,
+        variable_seen: &[VariableStatus],
+        literal: Literal,
+    ) -> bool {
+        let reason = self.clause_at(self.variable_metadata[literal.var()].reason_clause_idx);
+        reason[1..].iter().all(|parent| {
+            self.variable_metadata[parent.var()].decision_level == 0
+                || variable_seen[parent.var()] == VariableStatus::IsSource
+        })
+    }
+
     /// Prune learned clauses if too many
     fn prune_learned_clauses(&mut self) {
-        for i in 0..self.learned_clauses.len() {
-            let clause_ref = ClauseRef::from_idx(i, true);
-            let clause = &self.learned_clauses[clause_ref];
+        self.stats.num_reductions += 1;
+        // Do not delete binary clauses
+        let clause_refs: Vec<ClauseRef> = self
+            .learned_clauses
+            .refs()
+            .filter(|&clause_ref| self.learned_clauses[clause_ref].len() > 2)
+            .collect();
+        let num_to_delete = self.learned_clauses.len() / 2;
+        let to_delete: Vec<ClauseRef> = match self.options.reduction_strategy {
+            ReductionStrategy::Random => {
+                // Randomly delete learned clauses; do not delete referenced clauses
+                let mut to_delete = Vec::new();
+                for clause_ref in clause_refs {
+                    if self.random_gen.random_bool(0.5) && !self.is_locked_clause(clause_ref) {
+                        to_delete.push(clause_ref);
+                    }
+                }
+                to_delete
+            }
+            ReductionStrategy::Activity => {
+                // Delete the less active half; do not delete referenced clauses
+                let mut candidates = self.unlocked_clauses(clause_refs);
+                let learned_clauses = &self.learned_clauses;
+                candidates.sort_by(|&a, &b| {
+                    learned_clauses
+                        .activity(a)
+                        .total_cmp(&learned_clauses.activity(b))
+                });
+                candidates.truncate(num_to_delete);
+                candidates
+            }
+            ReductionStrategy::Lbd => {
+                // Delete the half with the highest LBD, keeping glue clauses;
+                // do not delete referenced clauses
+                let mut candidates = self.unlocked_clauses(clause_refs);
+                let learned_clauses = &self.learned_clauses;
+                candidates
+                    .retain(|&clause_ref| learned_clauses.lbd(clause_ref) > options::GLUE_LBD);
+                candidates.sort_by(|&a, &b| {
+                    learned_clauses.lbd(b).cmp(&learned_clauses.lbd(a)).then(
+                        learned_clauses
+                            .activity(a)
+                            .total_cmp(&learned_clauses.activity(b)),
+                    )
+                });
+                candidates.truncate(num_to_delete);
+                candidates
+            }
+        };
 
-            // Skip deleted clauses
-            if clause.is_empty() {
-                continue;
+        for &clause_ref in &to_delete {
+            self.stats.num_learned_literals_deleted += self.learned_clauses[clause_ref].len();
+            self.detach_clause(clause_ref);
+        }
+        self.emit(SolverEvent::Reduce {
+            kept: self.learned_clauses.len(),
+            deleted: to_delete.len(),
+        });
+    }
+
+    /// Learned clauses of `clause_refs` that are not the reason of an assignment
+    fn unlocked_clauses(&self, clause_refs: Vec<ClauseRef>) -> Vec<ClauseRef> {
+        clause_refs
+            .into_iter()
+            .filter(|&clause_ref| !self.is_locked_clause(clause_ref))
+            .collect()
+    }
+
+    /// Increases the activity of a learned clause involved in a conflict
+    #[allow(clippy::cast_possible_truncation)]
+    fn bump_clause_activity(&mut self, clause_ref: ClauseRef) {
+        let activity =
+            f64::from(self.learned_clauses.activity(clause_ref)) + self.clause_activity_increment;
+        self.learned_clauses
+            .set_activity(clause_ref, activity as f32);
+
+        // Rescale all activities to stay within floating point range
+        if activity > options::CLAUSE_ACTIVITY_RESCALE_LIMIT {
+            let clause_refs: Vec<ClauseRef> = self.learned_clauses.refs().collect();
+            for clause_ref in clause_refs {
+                let rescaled = f64::from(self.learned_clauses.activity(clause_ref))
+                    / options::CLAUSE_ACTIVITY_RESCALE_LIMIT;
+                self.learned_clauses
+                    .set_activity(clause_ref, rescaled as f32);
             }
+            self.clause_activity_increment /= options::CLAUSE_ACTIVITY_RESCALE_LIMIT;
+        }
+    }
 
-            // Randomly delete learned clauses;
-            // do not delete binary or referenced clauses
-            if clause.len() > 2
-                && self.random_gen.random_bool(0.5)
-                && !self.is_locked_clause(clause_ref)
-            {
-                self.detach_clause(clause_ref);
+    /// Increases the activity of a variable involved in a conflict; the
+    /// increment grows with every conflict, so recent conflicts weigh more
+    fn bump_variable_activity(&mut self, var: Variable) {
+        self.variable_activity[var] += self.variable_activity_increment;
+
+        // Rescale all activities to stay within floating point range; the
+        // order of the variables is kept
+        if self.variable_activity[var] > options::VARIABLE_ACTIVITY_RESCALE_LIMIT {
+            for activity in &mut self.variable_activity {
+                *activity /= options::VARIABLE_ACTIVITY_RESCALE_LIMIT;
+            }
+            self.variable_activity_increment /= options::VARIABLE_ACTIVITY_RESCALE_LIMIT;
+        }
+        self.variable_order.increase(var, &self.variable_activity);
+    }
+
+    /// Remembers the polarities of the current trail as the best phase
+    fn save_best_polarity(&mut self) {
+        self.best_trail_len = self.trail.len();
+        for &literal in &self.trail {
+            self.best_polarity[literal.var()] = literal.polarity();
+        }
+    }
+
+    /// Saves the polarities of the trail below the conflict level if it is the
+    /// longest conflict-free trail so far, and switches the stabilizing mode
+    /// every `SolverOptions::stabilization_interval` conflicts
+    fn update_stabilization(&mut self) {
+        let conflict_free_len = self.trail_separators.last().copied().unwrap_or(0);
+        if conflict_free_len > self.target_trail_len {
+            self.target_trail_len = conflict_free_len;
+            for &literal in &self.trail[..conflict_free_len] {
+                self.target_polarity[literal.var()] = literal.polarity();
+            }
+        }
+
+        self.stabilization_counter += 1;
+        if self
+            .options
+            .stabilization_interval
+            .is_some_and(|interval| self.stabilization_counter >= interval)
+        {
+            self.stabilization_counter = 0;
+            self.is_stabilizing = !self.is_stabilizing;
+            if self.is_stabilizing {
+                self.stats.num_stabilizations += 1;
+            }
+        }
+    }
+
+    /// Sets the saved polarities of the variables created since the last call
+    /// to their literals with the higher Jeroslow-Wang score
+    fn apply_jeroslow_polarities(&mut self) {
+        let first_var = self.first_unscored_variable;
+        self.first_unscored_variable = self.num_variables();
+        if first_var == self.num_variables() {
+            return;
+        }
+        let mut scores = vec![0.0; 2 * self.num_variables()];
+        for clause_ref in self.clauses.refs() {
+            let clause = &self.clauses[clause_ref];
+            let score = 0.5_f64.powi(i32::try_from(clause.len()).unwrap_or(i32::MAX));
+            for literal in clause {
+                scores[literal.repr()] += score;
+            }
+        }
+        for var in first_var..self.num_variables() {
+            let positive = Literal::from_var_with_polarity(var, true);
+            self.variable_polarity[var] = scores[positive.repr()] > scores[(!positive).repr()];
+        }
+    }
+
+    /// Overwrites the saved polarities with the next rephasing pattern:
+    /// all-true, all-false, random, and the best phase in turn
+    fn rephase(&mut self) {
+        match self.num_rephases % 4 {
+            0 => self.variable_polarity.fill(true),
+            1 => self.variable_polarity.fill(false),
+            2 => {
+                for polarity in &mut self.variable_polarity {
+                    *polarity = self.random_gen.random_bool(0.5);
+                }
+            }
+            _ => {
+                self.variable_polarity.copy_from_slice(&self.best_polarity);
+                self.best_trail_len = 0;
+            }
+        }
+        self.num_rephases += 1;
+    }
+
+    /// Number of distinct decision levels among the literals of a clause
+    fn literal_block_distance(&self, literals: &[Literal]) -> usize {
+        let mut levels: Vec<usize> = literals
+            .iter()
+            .map(|literal| self.variable_metadata[literal.var()].decision_level)
+            .collect();
+        levels.sort_unstable();
+        levels.dedup();
+        levels.len()
+    }
+
+    /// Compacts the clause arenas if too much of them is occupied by deleted
+    /// clauses and rewrites all clause references
+    fn collect_garbage(&mut self) {
+        if usize_to_f64(self.clauses.wasted())
+            > usize_to_f64(self.clauses.arena_len()) * options::GARBAGE_FRACTION
+        {
+            let relocation_map = self.clauses.collect_garbage();
+            self.relocate_clause_refs(&relocation_map);
+        }
+        if usize_to_f64(self.learned_clauses.wasted())
+            > usize_to_f64(self.learned_clauses.arena_len()) * options::GARBAGE_FRACTION
+        {
+            let relocation_map = self.learned_clauses.collect_garbage();
+            self.relocate_clause_refs(&relocation_map);
+        }
+    }
+
+    /// Rewrites the clause references in watches and reasons after a garbage collection
+    fn relocate_clause_refs(&mut self, relocation_map: &RelocationMap) {
+        if let Some(proof) = &mut self.proof {
+            proof.relocate(relocation_map);
+        }
+        let watch_lists = self
+            .literals_watched_by
+            .iter_mut()
+            .chain(self.binary_watched_by.iter_mut());
+        for watches in watch_lists {
+            for watch in watches {
+                watch.clause_ref = relocation_map.relocate(watch.clause_ref);
+                debug_assert!(watch.clause_ref.valid());
             }
         }
+        for (metadata, value) in self.variable_metadata.iter_mut().zip(&self.variable_values) {
+            // Reasons of unassigned variables are outdated anyway
+            metadata.reason_clause_idx = if value.is_unset() {
+                ClauseRef::default()
+            } else {
+                relocation_map.relocate(metadata.reason_clause_idx)
+            };
+        }
     }
 
-    /// Progress estimate
+    /// Progress estimate in `[0, 1]` as in MiniSat; the variables assigned
+    /// at decision level `i` are weighted by `F^i` with `F = 1 / num_variables`
     fn progress_estimate(&self) -> f64 {
-        let set_vars = *self.trail_separators.first().unwrap_or(&self.trail.len());
-        usize_to_f64(set_vars) / usize_to_f64(self.num_variables())
+        if self.num_variables() == 0 {
+            return 0.0;
+        }
+        let factor = 1.0 / usize_to_f64(self.num_variables());
+        let mut weight = 1.0;
+        let mut progress = 0.0;
+        for level in 0..=self.decision_level() {
+            let begin = if level == 0 {
+                0
+            } else {
+                self.trail_separators[level - 1]
+            };
+            let end = if level == self.decision_level() {
+                self.trail.len()
+            } else {
+                self.trail_separators[level]
+            };
+            progress += weight * usize_to_f64(end - begin);
+            weight *= factor;
+        }
+        progress / usize_to_f64(self.num_variables())
     }
 
     /// Pick next literal to branch on
     fn pick_branch_literal(&mut self) -> Option<Literal> {
-        // Random decision
+        let var = match self.options.branching {
+            BranchingHeuristic::Random => self.pick_random_variable(),
+            BranchingHeuristic::Vsids | BranchingHeuristic::DeterministicVsids => {
+                self.pick_most_active_variable()
+            }
+            BranchingHeuristic::InOrder => self.pick_lowest_variable(),
+        }
+        .or_else(|| self.pick_non_decision_variable())?;
+
+        // Choose polarity based on preferred or, while stabilizing, target
+        // polarity; occasionally flip it unless branching must not be random
+        let mut polarity = if self.is_stabilizing {
+            self.target_polarity[var]
+        } else {
+            self.variable_polarity[var]
+        };
+        if !matches!(
+            self.options.branching,
+            BranchingHeuristic::InOrder | BranchingHeuristic::DeterministicVsids
+        ) && self.options.random_phase_prob > 0.0
+            && self.random_gen.random_bool(self.options.random_phase_prob)
+        {
+            polarity = !polarity;
+        }
+        Some(Literal::from_var_with_polarity(var, polarity))
+    }
+
+    /// Removes random variables from `unset_variables` until one is an unset
+    /// decision variable
+    fn pick_random_variable(&mut self) -> Option<Variable> {
         while !self.unset_variables.is_empty() {
             // Select random unset variable
             let idx = self.random_gen.random_range(0..self.unset_variables.len());
             let var = self.unset_variables.swap_remove(idx);
 
             // Check whether variable is unset
-            if self.variable_values[var].is_unset() {
-                // Choose polarity based on preferred polarity
-                return Some(Literal::from_var_with_polarity(
-                    var,
-                    self.variable_polarity[var],
-                ));
+            if self.variable_values[var].is_unset() && self.decision_variables[var] {
+                return Some(var);
+            }
+        }
+
+        None
+    }
+
+    /// Advances `in_order_cursor` to the lowest unset, not eliminated decision
+    /// variable
+    fn pick_lowest_variable(&mut self) -> Option<Variable> {
+        while self.in_order_cursor < self.num_variables() {
+            let var = self.in_order_cursor;
+            if self.variable_values[var].is_unset()
+                && !self.eliminated[var]
+                && self.decision_variables[var]
+            {
+                return Some(var);
+            }
+            self.in_order_cursor += 1;
+        }
+
+        None
+    }
+
+    /// Whether the branching heuristic orders the variables by their activities
+    const fn uses_activities(&self) -> bool {
+        matches!(
+            self.options.branching,
+            BranchingHeuristic::Vsids | BranchingHeuristic::DeterministicVsids
+        )
+    }
+
+    /// Removes the most active variables from `variable_order` until one is an
+    /// unset decision variable
+    fn pick_most_active_variable(&mut self) -> Option<Variable> {
+        while let Some(var) = self.variable_order.pop_max(&self.variable_activity) {
+            if self.variable_values[var].is_unset() && self.decision_variables[var] {
+                return Some(var);
             }
         }
 
         None
     }
 
+    /// Lowest unset, not eliminated variable once all decision variables are
+    /// assigned; scans all variables, which happens at most once per model
+    /// unless propagation leaves non-decision variables unassigned
+    fn pick_non_decision_variable(&self) -> Option<Variable> {
+        (0..self.num_variables())
+            .find(|&var| self.variable_values[var].is_unset() && !self.eliminated[var])
+    }
+
     /// Accesses an original or learned clause
-    fn clause_at(&self, clause_ref: ClauseRef) -> &Vec<Literal> {
+    fn clause_at(&self, clause_ref: ClauseRef) -> &[Literal] {
         if clause_ref.is_learned() {
             &self.learned_clauses[clause_ref]
         } else {
@@ -520,7 +2315,7 @@ impl Solver {
     }
 
     /// Accesses an original or learned clause
-    fn clause_at_mut(&mut self, clause_ref: ClauseRef) -> &mut Vec<Literal> {
+    fn clause_at_mut(&mut self, clause_ref: ClauseRef) -> &mut [Literal] {
         if clause_ref.is_learned() {
             &mut self.learned_clauses[clause_ref]
         } else {
@@ -538,9 +2333,28 @@ impl Solver {
             && self.variable_metadata[clause[0].var()].reason_clause_idx == clause_ref
     }
 
-    /// Propagate all facts in `trail` starting from `trail_propagation_head`;
+    /// Propagate all facts in `trail` through the clauses and XOR rows;
     /// returns conflicting clause index or `UNDEF_CLAUSE` if none
     fn propagate(&mut self) -> ClauseRef {
+        let conflict = loop {
+            let conflict = self.propagate_clauses();
+            if conflict.valid() || self.xor_rows.is_empty() {
+                break conflict;
+            }
+            let conflict = self.propagate_xors();
+            if conflict.valid() || self.trail_propagation_head == self.trail.len() {
+                break conflict;
+            }
+        };
+        if conflict.valid() {
+            self.trace_refutation(conflict);
+        }
+        conflict
+    }
+
+    /// Propagate all facts in `trail` starting from `trail_propagation_head`;
+    /// returns conflicting clause index or `UNDEF_CLAUSE` if none
+    fn propagate_clauses(&mut self) -> ClauseRef {
         // Current conflict
         let mut conflict = ClauseRef::default();
 
@@ -551,7 +2365,31 @@ impl Solver {
             self.trail_propagation_head += 1;
             self.stats.num_propagations += 1;
 
-            // Check all watches
+            // Binary clauses imply the other literal directly
+            for k in 0..self.binary_watched_by[literal_to_propagate.repr()].len() {
+                let watch = self.binary_watched_by[literal_to_propagate.repr()][k];
+                let other_literal = watch.blocker;
+                if other_literal.is_true(&self.variable_values) {
+                    continue;
+                }
+                if other_literal.is_false(&self.variable_values) {
+                    conflict = watch.clause_ref;
+                    self.trail_propagation_head = self.trail.len();
+                    break;
+                }
+
+                // Reasons keep the implied literal at position 0
+                let clause = self.clause_at_mut(watch.clause_ref);
+                if clause[0] != other_literal {
+                    clause.swap(0, 1);
+                }
+                self.assign_literal(other_literal, watch.clause_ref);
+            }
+            if conflict.valid() {
+                break;
+            }
+
+            // Check all watches of longer clauses
             let num_watches = self.literals_watched_by[literal_to_propagate.repr()].len();
             let mut i = 0;
             let mut j = 0;
@@ -660,22 +2498,53 @@ impl Solver {
                 let polarity = literal_to_revert.polarity();
 
                 // Unset assignment and save preferred polarity
-                self.variable_values[variable] = VariableValue::Unset;
+                self.unassign_variable(variable);
                 self.variable_polarity[variable] = polarity;
-                self.unset_variables.push(variable);
                 c -= 1;
             }
 
-            // Shrink `trail` and `trail_separators` to specified `level`
-            self.trail_propagation_head = self.trail_separators[level];
+            // Shrink `trail` and `trail_separators` to specified `level`;
+            // literals of the kept levels not yet propagated stay pending
+            self.trail_propagation_head = self
+                .trail_propagation_head
+                .min(self.trail_separators[level]);
+            self.rewind_xor_propagation(self.trail_separators[level]);
             self.trail
-                .resize(self.trail_propagation_head, Literal::default());
+                .resize(self.trail_separators[level], Literal::default());
             self.trail_separators.resize(level, 0);
         }
     }
 
-    /// Assigns the given literal (must be unset previously)
+    /// Unsets the value of `variable` and makes it available for branching
+    fn unassign_variable(&mut self, variable: Variable) {
+        self.variable_values[variable] = VariableValue::Unset;
+        match self.options.branching {
+            BranchingHeuristic::Random => self.unset_variables.push(variable),
+            BranchingHeuristic::Vsids | BranchingHeuristic::DeterministicVsids => {
+                self.variable_order
+                    .insert(variable, &self.variable_activity);
+            }
+            BranchingHeuristic::InOrder => {
+                self.in_order_cursor = self.in_order_cursor.min(variable);
+            }
+        }
+    }
+
+    /// Assigns the given literal (must be unset previously); assignments at
+    /// the top level are recorded as facts
     fn assign_literal(&mut self, literal: Literal, reason_clause_idx: ClauseRef) {
+        self.push_assignment(literal, reason_clause_idx);
+        if self.decision_level() == 0 {
+            self.stats.num_top_level_units += 1;
+            if reason_clause_idx.valid() {
+                self.trace_top_level_unit(literal, reason_clause_idx);
+            }
+        }
+    }
+
+    /// Assigns the given literal (must be unset previously) without recording
+    /// it as a top-level fact
+    fn push_assignment(&mut self, literal: Literal, reason_clause_idx: ClauseRef) {
         // Assigned literal must be unset previously
         let var = literal.var();
         debug_assert!(self.variable_values[var].is_unset());
@@ -685,12 +2554,13 @@ impl Solver {
         self.variable_metadata[var].decision_level = self.decision_level();
         self.variable_metadata[var].reason_clause_idx = reason_clause_idx;
         self.trail.push(literal);
+        self.stats.max_trail_length = self.stats.max_trail_length.max(self.trail.len());
     }
 
-    /// Removes a watch from `literals_watched_by`
-    fn remove_watch(&mut self, literal: Literal, watch_to_remove: Watch) {
+    /// Removes a watch from `literals_watched_by` or `binary_watched_by`
+    fn remove_watch(watch_lists: &mut [Vec<Watch>], literal: Literal, watch_to_remove: Watch) {
         // Find watch
-        let watches = &mut self.literals_watched_by[literal.repr()];
+        let watches = &mut watch_lists[literal.repr()];
         let mut i = 0;
         while i < watches.len() && watches[i] != watch_to_remove {
             i += 1;
@@ -704,39 +2574,52 @@ impl Solver {
     }
 
     /// Attaches a clause by creating watches
-    fn attach_clause<const IS_LEARNED: bool>(&mut self, literals: Vec<Literal>) -> ClauseRef {
+    fn attach_clause<const IS_LEARNED: bool>(&mut self, literals: &[Literal]) -> ClauseRef {
         // Add clause
         let first_literal = literals[0];
         let second_literal = literals[1];
         let clause_ref = if IS_LEARNED {
             self.stats.num_learned_clauses += 1;
             self.stats.num_literals_in_learned_clauses += literals.len();
-            self.learned_clauses.add_clause(literals, true)
+            self.learned_clauses.add_clause(literals)
         } else {
             self.stats.num_clauses += 1;
             self.stats.num_literals_in_clauses += literals.len();
-            self.clauses.add_clause(literals, false)
+            self.clauses.add_clause(literals)
         };
 
         // Keep two watches per clause
-        self.literals_watched_by[(!first_literal).repr()]
+        let watch_lists = if literals.len() == 2 {
+            &mut self.binary_watched_by
+        } else {
+            &mut self.literals_watched_by
+        };
+        watch_lists[(!first_literal).repr()]
             .push(Watch::from_ref_and_blocker(clause_ref, second_literal));
-        self.literals_watched_by[(!second_literal).repr()]
+        watch_lists[(!second_literal).repr()]
             .push(Watch::from_ref_and_blocker(clause_ref, first_literal));
         clause_ref
     }
 
     /// Removes a clause by removing watches and clearing literals
     fn detach_clause(&mut self, clause_ref: ClauseRef) {
+        self.trace_removal(clause_ref);
         let (first_lit, second_lit, len) = {
             let clause = self.clause_at(clause_ref);
             (clause[0], clause[1], clause.len())
         };
-        self.remove_watch(
+        let watch_lists = if len == 2 {
+            &mut self.binary_watched_by
+        } else {
+            &mut self.literals_watched_by
+        };
+        Self::remove_watch(
+            watch_lists,
             !first_lit,
             Watch::from_ref_and_blocker(clause_ref, second_lit),
         );
-        self.remove_watch(
+        Self::remove_watch(
+            watch_lists,
             !second_lit,
             Watch::from_ref_and_blocker(clause_ref, first_lit),
         );
@@ -751,31 +2634,28 @@ impl Solver {
         } else {
             self.stats.num_clauses -= 1;
             self.stats.num_literals_in_clauses -= len;
+            if let Some(occurrences) = &mut self.occurrence_lists {
+                occurrences.remove(clause_ref, &self.clauses[clause_ref]);
+            }
             self.clauses.remove_clause(clause_ref);
         }
     }
 
     /// Remove the satisfied clauses in the given container
     fn remove_satisfied_clauses<const IS_LEARNED: bool>(&mut self) {
-        let clause_len = if IS_LEARNED {
-            self.learned_clauses.len()
+        let clause_refs: Vec<ClauseRef> = if IS_LEARNED {
+            self.learned_clauses.refs().collect()
         } else {
-            self.clauses.len()
+            self.clauses.refs().collect()
         };
 
-        for clause_idx in 0..clause_len {
-            let clause_ref = ClauseRef::from_idx(clause_idx, IS_LEARNED);
-            let is_clause_deleted = if IS_LEARNED {
-                self.learned_clauses[clause_ref].is_empty()
-            } else {
-                self.clauses[clause_ref].is_empty()
-            };
-
-            // Skip deleted clauses
-            if is_clause_deleted {
-                continue;
-            }
+        // Clauses satisfied by pure literals only are kept
+        let mut is_pure = vec![false; self.num_variables()];
+        for literal in &self.pure_literals {
+            is_pure[literal.var()] = true;
+        }
 
+        for clause_ref in clause_refs {
             // Check if clause already satisfied
             let is_clause_already_satisfied = if IS_LEARNED {
                 self.learned_clauses
@@ -785,11 +2665,19 @@ impl Solver {
                     .is_clause_satisfied(clause_ref, &self.variable_values)
             };
             if is_clause_already_satisfied {
-                // Remove clause
-                self.detach_clause(clause_ref);
+                // Remove clause unless only pure literals satisfy it
+                if self.clause_at(clause_ref).iter().any(|literal| {
+                    literal.is_true(&self.variable_values) && !is_pure[literal.var()]
+                }) {
+                    self.detach_clause(clause_ref);
+                }
             } else {
                 // Trim clause; first two literals cannot be true since otherwise
                 // `isClauseSatisfied()` and cannot be false by invariant
+                let old_literals = self
+                    .proof
+                    .is_some()
+                    .then(|| self.clause_at(clause_ref).to_vec());
                 let clause = if IS_LEARNED {
                     &mut self.learned_clauses[clause_ref]
                 } else {
@@ -798,16 +2686,127 @@ impl Solver {
                 debug_assert!(clause.len() > 1);
                 debug_assert!(self.variable_values[clause[0].var()].is_unset());
                 debug_assert!(self.variable_values[clause[1].var()].is_unset());
+                let old_len = clause.len();
+                let mut len = old_len;
                 let mut i = 2;
-                while i < clause.len() {
+                while i < len {
                     if clause[i].is_false(&self.variable_values) {
-                        clause.swap_remove(i);
-                        i -= 1;
+                        len -= 1;
+                        clause.swap(i, len);
+                    } else {
+                        i += 1;
                     }
-                    i += 1;
+                }
+                let (first_lit, second_lit) = (clause[0], clause[1]);
+                if IS_LEARNED {
+                    self.learned_clauses.truncate_clause(clause_ref, len);
+                } else {
+                    self.clauses.truncate_clause(clause_ref, len);
+                }
+                if let Some(old_literals) = old_literals
+                    && len < old_len
+                {
+                    self.trace_trimmed_clause(clause_ref, &old_literals);
+                }
+
+                // Clauses trimmed to binary ones move to `binary_watched_by`
+                if len == 2 && old_len > 2 {
+                    for (watched, other) in [(first_lit, second_lit), (second_lit, first_lit)] {
+                        let watch = Watch::from_ref_and_blocker(clause_ref, other);
+                        Self::remove_watch(&mut self.literals_watched_by, !watched, watch);
+                        self.binary_watched_by[(!watched).repr()].push(watch);
+                    }
+                }
+            }
+        }
+    }
+
+    /// Assigns all unset variables occurring with a single polarity in the
+    /// unsatisfied original and learned clauses; returns `false` on a conflict
+    ///
+    /// Such assignments are not implied, so nothing is derived from them:
+    /// they falsify no literal, and `restore_pure_literals` undoes them.
+    fn eliminate_pure_literals(&mut self) -> bool {
+        debug_assert!(self.decision_level() == 0);
+
+        // Mark occurring literals (indexed by `Literal::repr()`); use the
+        // occurrence lists while preprocessing
+        let mut literal_occurs = vec![false; 2 * self.num_variables()];
+        if self.occurrence_lists.is_some() {
+            for (repr, occurs) in literal_occurs.iter_mut().enumerate() {
+                *occurs = !self.live_occurrences(Literal::from_repr(repr)).is_empty();
+            }
+        } else {
+            for clause_ref in self.clauses.refs() {
+                if self
+                    .clauses
+                    .is_clause_satisfied(clause_ref, &self.variable_values)
+                {
+                    continue;
+                }
+                for &literal in &self.clauses[clause_ref] {
+                    literal_occurs[literal.repr()] = true;
+                }
+            }
+        }
+        for clause_ref in self.learned_clauses.refs() {
+            if !self
+                .learned_clauses
+                .is_clause_satisfied(clause_ref, &self.variable_values)
+            {
+                for &literal in &self.learned_clauses[clause_ref] {
+                    literal_occurs[literal.repr()] = true;
                 }
             }
         }
+
+        // Assign literals whose negation does not occur
+        for var in 0..self.num_variables() {
+            if !self.variable_values[var].is_unset() || self.frozen[var] {
+                continue;
+            }
+            let positive = Literal::from_var_with_polarity(var, true);
+            if literal_occurs[positive.repr()] != literal_occurs[(!positive).repr()] {
+                let pure_literal = if literal_occurs[positive.repr()] {
+                    positive
+                } else {
+                    !positive
+                };
+                // Pure literals are undone before later calls, so no facts
+                self.push_assignment(pure_literal, ClauseRef::default());
+                self.pure_literals.push(pure_literal);
+                self.stats.num_pure_literals += 1;
+            }
+        }
+
+        // Pure literals propagate nothing, but move the propagation head
+        !self.propagate().valid()
+    }
+
+    /// Undoes the assignments of pure literal elimination, which keeps all
+    /// other top-level facts
+    pub(super) fn restore_pure_literals(&mut self) {
+        debug_assert!(self.decision_level() == 0);
+        if self.pure_literals.is_empty() {
+            return;
+        }
+        let mut is_pure = vec![false; self.num_variables()];
+        for literal in std::mem::take(&mut self.pure_literals) {
+            is_pure[literal.var()] = true;
+            self.unassign_variable(literal.var());
+        }
+        let first_pure = self
+            .trail
+            .iter()
+            .position(|literal| is_pure[literal.var()])
+            .unwrap_or(self.trail.len());
+        self.trail.retain(|literal| !is_pure[literal.var()]);
+
+        // Clauses satisfied by a pure literal were skipped when literals
+        // assigned after it falsified their watches
+        self.trail_propagation_head = self.trail_propagation_head.min(first_pure);
+        self.rewind_xor_propagation(first_pure);
+        self.ok &= !self.propagate().valid();
     }
 
     /// Simplify by removing satisfied clauses
@@ -820,15 +2819,37 @@ impl Solver {
             return false;
         }
 
+        // Probe again only if the last round ran out of budget or new
+        // top-level facts were derived
+        if self.options.failed_literal_probing
+            && self.last_probe_trail_len != Some(self.trail.len() - self.pure_literals.len())
+            && !self.probe_literals()
+        {
+            return false;
+        }
+
+        // Assign pure literals
+        if self.options.pure_literal_elimination && !self.eliminate_pure_literals() {
+            return false;
+        }
+
         // Remove satisfied clauses
         self.remove_satisfied_clauses::<true>();
         self.remove_satisfied_clauses::<false>();
 
         // Update unset variables
         self.unset_variables.clear();
+        self.variable_order.clear();
+        self.in_order_cursor = 0;
         for var in 0..self.variable_values.len() {
-            if self.variable_values[var].is_unset() {
-                self.unset_variables.push(var);
+            if self.variable_values[var].is_unset() && !self.eliminated[var] {
+                match self.options.branching {
+                    BranchingHeuristic::Random => self.unset_variables.push(var),
+                    BranchingHeuristic::Vsids | BranchingHeuristic::DeterministicVsids => {
+                        self.variable_order.insert(var, &self.variable_activity);
+                    }
+                    BranchingHeuristic::InOrder => {}
+                }
             }
         }
         self.unset_variables.shuffle(&mut self.random_gen);
@@ -836,82 +2857,171 @@ impl Solver {
         // Problem instance still satisfiable
         true
     }
-}
-
-/// Adding clauses to a solver
-impl ClauseReceiver for Solver {
-    fn create_variables(&mut self, num_variables: usize) {
-        self.stats.num_variables = num_variables;
-        self.variable_values
-            .resize(num_variables, VariableValue::Unset);
-        self.variable_polarity.resize(num_variables, false);
-        self.variable_metadata
-            .resize(num_variables, VariableMetadata::default());
-        self.trail.reserve(num_variables + 1);
-        self.unset_variables.reserve(num_variables);
-        self.literals_watched_by
-            .resize(num_variables * 2, Vec::new());
-    }
 
-    fn add_clause(&mut self, literals: &[Literal]) -> bool {
+    /// Simplifies and adds an original clause, which is clause `id` of the
+    /// proof if one is written; returns whether the instance is still
+    /// satisfiable and the reference of the attached clause, if any
+    fn add_original_clause(
+        &mut self,
+        literals: &[Literal],
+        id: Option<ClauseId>,
+    ) -> (bool, Option<ClauseRef>) {
         debug_assert!(self.decision_level() == 0);
-        debug_assert!(!literals.is_empty());
 
-        // Copy literals and sort (positive and negative literals
-        // of the same variable are consecutive)
-        let mut copied_literals = Vec::from(literals);
-        copied_literals.sort();
+        // The clause may contradict the pure literals
+        self.restore_pure_literals();
+        if !self.ok {
+            return (false, None);
+        }
 
-        // Check for satisfied clauses and duplicate literals
-        let mut last_literal = Literal::default();
-        let mut num_final_elems = 0;
-        let mut i = 0;
-        while i < copied_literals.len() {
-            let curr_literal = copied_literals[i];
+        // Check for satisfied clauses and duplicate literals in linear time
+        // by marking the literals copied so far
+        let mut copied_literals = Vec::with_capacity(literals.len());
+        let mut is_satisfied = false;
+        for &curr_literal in literals {
             let var = curr_literal.var();
             let polarity = curr_literal.polarity();
             debug_assert!(var < self.num_variables());
-            i += 1;
 
-            // Clause already satisfied
-            if self.variable_values[var] == polarity {
-                return true;
-            }
-            // `not A or A` is always true
-            if curr_literal == !last_literal {
-                return true;
-            }
-            // Literal false; no need to add
-            if self.variable_values[var] == !polarity {
-                continue;
+            // Clause already satisfied, or `not A or A` is always true
+            if self.variable_values[var] == polarity || self.literal_seen[(!curr_literal).repr()] {
+                is_satisfied = true;
+                break;
             }
-            // Duplicate literal, continue
-            if curr_literal == last_literal {
+            // Literal false or duplicate; no need to add
+            if self.variable_values[var] == !polarity || self.literal_seen[curr_literal.repr()] {
                 continue;
             }
 
             // Add literal to final output
-            last_literal = curr_literal;
-            copied_literals[num_final_elems] = curr_literal;
-            num_final_elems += 1;
+            self.literal_seen[curr_literal.repr()] = true;
+            copied_literals.push(curr_literal);
+        }
+        for literal in &copied_literals {
+            self.literal_seen[literal.repr()] = false;
+        }
+        if is_satisfied {
+            self.trace_deletion(id, literals);
+            return (true, None);
         }
 
-        // Update clause size
-        copied_literals.resize(num_final_elems, Literal::default());
+        // Literals false on the top level are resolved away with their units
+        let mut id = id;
+        if let Some(original_id) = id
+            && literals
+                .iter()
+                .any(|literal| literal.is_false(&self.variable_values))
+        {
+            id = self.trace_derivation(&copied_literals, literals, original_id);
+            self.trace_deletion(Some(original_id), literals);
+        }
 
         // If literals are empty, instance is UNSAT
         if copied_literals.is_empty() {
-            return false;
+            if literals.is_empty()
+                && let Some(id) = id
+            {
+                self.trace_clause(&[], &[id]);
+            }
+            self.ok = false;
+            return (false, None);
         }
 
         // Add fact for next propagation if singleton
         if copied_literals.len() == 1 {
+            self.trace_unit(copied_literals[0], id);
             self.assign_literal(copied_literals[0], ClauseRef::default());
-            return !self.propagate().valid(); // Check conflicts
+            self.ok = !self.propagate().valid(); // Check conflicts
+            return (self.ok, None);
+        }
+
+        // Skip clause if an identical one was added before; clauses with
+        // the same hash may still differ
+        if self.options.duplicate_clause_detection {
+            let mut sorted_literals = copied_literals.clone();
+            sorted_literals.sort_unstable();
+            let mut hasher = DefaultHasher::new();
+            sorted_literals.hash(&mut hasher);
+            let candidates = self
+                .original_clauses_by_hash
+                .entry(hasher.finish())
+                .or_default();
+            if candidates.contains(&sorted_literals) {
+                self.stats.num_duplicate_clauses += 1;
+                self.trace_deletion(id, &copied_literals);
+                return (true, None);
+            }
+            candidates.push(sorted_literals);
         }
 
         // Add clause
-        self.attach_clause::<false>(copied_literals);
+        let clause_ref = self.attach_clause::<false>(&copied_literals);
+        self.trace_attached_clause(clause_ref, id);
+        if let Some(occurrences) = &mut self.occurrence_lists {
+            occurrences.add(clause_ref, &copied_literals);
+        }
+        (true, Some(clause_ref))
+    }
+}
+
+/// Adding clauses to a solver
+impl ClauseReceiver for Solver {
+    fn create_variables(&mut self, num_variables: usize) -> bool {
+        // Both polarities of each variable get watch lists
+        if num_variables > self.options.max_variables || num_variables.checked_mul(2).is_none() {
+            return false;
+        }
+        self.stats.num_variables = num_variables;
+        self.variable_values
+            .resize(num_variables, VariableValue::Unset);
+        let num_old_variables = self.variable_polarity.len();
+        self.variable_polarity.resize(num_variables, false);
+        match self.options.initial_polarity {
+            InitialPolarity::False | InitialPolarity::Jeroslow => {}
+            InitialPolarity::True => self.variable_polarity[num_old_variables..].fill(true),
+            InitialPolarity::Random => {
+                for polarity in &mut self.variable_polarity[num_old_variables..] {
+                    *polarity = self.random_gen.random_bool(0.5);
+                }
+            }
+        }
+        self.best_polarity.resize(num_variables, false);
+        self.target_polarity.resize(num_variables, false);
+        self.variable_metadata
+            .resize(num_variables, VariableMetadata::default());
+        self.eliminated.resize(num_variables, false);
+        self.blocking.resize(num_variables, false);
+        self.frozen.resize(num_variables, false);
+        self.decision_variables.resize(num_variables, true);
+        self.occurs.resize(num_variables, false);
+        self.trail.reserve(num_variables + 1);
+        self.unset_variables.reserve(num_variables);
+        self.variable_activity.resize(num_variables, 0.0);
+        self.variable_order.resize(num_variables);
+        self.literals_watched_by
+            .resize(num_variables * 2, Vec::new());
+        self.binary_watched_by.resize(num_variables * 2, Vec::new());
+        self.xor_watches.resize(num_variables, Vec::new());
+        self.literal_seen.resize(num_variables * 2, false);
+        if let Some(occurrences) = &mut self.occurrence_lists {
+            occurrences.resize(num_variables);
+        }
+        if let Some(proof) = &mut self.proof {
+            proof.resize(num_variables);
+        }
         true
     }
+
+    fn add_clause(&mut self, literals: &[Literal]) -> bool {
+        for literal in literals {
+            assert!(
+                literal.var() < self.num_variables(),
+                "unknown variable {}",
+                literal.var()
+            );
+            self.occurs[literal.var()] = true;
+        }
+        let id = self.trace_original_clause();
+        self.add_original_clause(literals, id).0
+    }
 }
@@ -1,31 +1,40 @@
-use crate::solver::{
-    literal::Literal,
-    variable::{Variable, VariableValue},
-};
+use std::fmt;
+
+use crate::solver::{literal::Literal, variable::VariableValue};
 
 /// Clause reference type
-#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
 pub struct ClauseRef {
-    /// Even indices are original clauses; odd indices are learned clauses
-    repr: usize,
+    /// Even indices are original clauses; odd indices are learned clauses;
+    /// stored as `u32` like `Literal`, limiting each arena to `2^31` slots
+    repr: u32,
 }
 
 impl ClauseRef {
     /// Invalid reference
-    const INVALID: usize = usize::MAX;
+    const INVALID: u32 = u32::MAX;
 
     /// New clause reference
+    ///
+    /// # Panics
+    ///
+    /// If `idx` does not fit into the `2^31` slots of an arena
     #[must_use]
+    #[allow(clippy::cast_possible_truncation)]
     pub const fn from_idx(idx: usize, is_learned: bool) -> Self {
+        assert!(
+            idx < (Self::INVALID >> 1) as usize,
+            "clause arena exceeds 2^31 slots"
+        );
         ClauseRef {
-            repr: 2 * idx + is_learned as Variable,
+            repr: 2 * idx as u32 + is_learned as u32,
         }
     }
 
     /// Index
     #[must_use]
-    pub const fn idx(&self) -> Variable {
-        self.repr >> 1
+    pub const fn idx(&self) -> usize {
+        (self.repr >> 1) as usize
     }
     /// Whether clause is learned
     #[must_use]
@@ -47,6 +56,39 @@ impl Default for ClauseRef {
     }
 }
 
+/// Displays a reference as `O#idx` for original and `L#idx` for learned clauses
+impl fmt::Display for ClauseRef {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if !self.valid() {
+            write!(f, "invalid")
+        } else if self.is_learned() {
+            write!(f, "L#{}", self.idx())
+        } else {
+            write!(f, "O#{}", self.idx())
+        }
+    }
+}
+
+/// Same as `Display`, which is more readable than the representation
+impl fmt::Debug for ClauseRef {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(self, f)
+    }
+}
+
+/// Displays a clause in DIMACS notation, such as `1 -2 0`
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct DimacsClause<'a>(pub &'a [Literal]);
+
+impl fmt::Display for DimacsClause<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for literal in self.0 {
+            write!(f, "{literal} ")?;
+        }
+        write!(f, "0")
+    }
+}
+
 /// Literal is watched by `clause_idx`.
 /// If `Watch::blocker` is satisfied, clause is not required to be inspected
 #[derive(Clone, Copy, Eq, Debug, Default)]
@@ -82,20 +124,66 @@ pub struct VariableMetadata {
     pub decision_level: usize,
 }
 
+/// Maps the references of the clauses kept by `Clauses::collect_garbage`
+/// to their new location
+#[derive(Clone, Default, Debug)]
+pub struct RelocationMap {
+    /// Pairs of old and new references sorted by old index
+    relocations: Vec<(ClauseRef, ClauseRef)>,
+}
+
+impl RelocationMap {
+    /// New location of a clause; references into the other container are kept
+    /// and references of deleted clauses become invalid
+    #[must_use]
+    pub fn relocate(&self, clause_ref: ClauseRef) -> ClauseRef {
+        let Some(&(first_ref, _)) = self.relocations.first() else {
+            return clause_ref;
+        };
+        if !clause_ref.valid() || clause_ref.is_learned() != first_ref.is_learned() {
+            return clause_ref;
+        }
+        match self
+            .relocations
+            .binary_search_by_key(&clause_ref.idx(), |(old_ref, _)| old_ref.idx())
+        {
+            Ok(pos) => self.relocations[pos].1,
+            Err(_) => ClauseRef::default(),
+        }
+    }
+}
+
+/// Number of arena slots in front of the literals of a clause
+const HEADER_SIZE: usize = 4;
+/// Header slot holding the number of literals allocated for the clause
+const HEADER_CAPACITY: usize = 0;
+/// Header slot holding the current number of literals; zero if deleted
+const HEADER_LEN: usize = 1;
+/// Header slot holding the literal block distance
+const HEADER_LBD: usize = 2;
+/// Header slot holding the bits of the `f32` activity
+const HEADER_ACTIVITY: usize = 3;
+
 /// Class managing the creation, deletion, and access of clauses
+///
+/// All clauses live in a single arena: every clause is a header of
+/// `HEADER_SIZE` slots followed by its literals, and a `ClauseRef` stores the
+/// offset of the header. Deleted clauses keep their space in the arena.
 #[derive(Clone, Default)]
 pub struct Clauses<const IS_LEARNED: bool> {
-    /// Stores all clauses
-    container: Vec<Vec<Literal>>,
-    /// Stores indices of empty vectors
-    free_indices: Vec<usize>,
+    /// Stores headers and literals of all clauses
+    arena: Vec<Literal>,
+    /// Number of clauses not deleted
+    num_clauses: usize,
+    /// Number of arena slots occupied by deleted clauses or trimmed literals
+    wasted: usize,
 }
 
 impl<const IS_LEARNED: bool> Clauses<IS_LEARNED> {
-    /// Size
+    /// Number of clauses
     #[must_use]
     pub const fn len(&self) -> usize {
-        self.container.len()
+        self.num_clauses
     }
 
     /// Is empty
@@ -104,30 +192,128 @@ impl<const IS_LEARNED: bool> Clauses<IS_LEARNED> {
         self.len() == 0
     }
 
-    /// Move clause into container
-    pub fn add_clause(&mut self, literals: Vec<Literal>, is_learned: bool) -> ClauseRef {
-        if let Some(idx) = self.free_indices.pop() {
-            // Use free slot if any
-            self.container[idx] = literals;
-            ClauseRef::from_idx(idx, is_learned)
-        } else {
-            // Append at the end
-            let idx = self.container.len();
-            self.container.push(literals);
-            ClauseRef::from_idx(idx, is_learned)
-        }
+    /// Size of the arena; an upper bound on `ClauseRef::idx()`
+    #[must_use]
+    pub const fn arena_len(&self) -> usize {
+        self.arena.len()
+    }
+
+    /// Heap memory reserved by the arena in bytes
+    #[must_use]
+    pub const fn heap_size_bytes(&self) -> usize {
+        self.arena.capacity() * size_of::<Literal>()
+    }
+
+    /// Number of arena slots not used by any clause
+    #[must_use]
+    pub const fn wasted(&self) -> usize {
+        self.wasted
     }
 
-    /// Remove clause
+    /// References of all clauses not deleted, in order of creation
+    pub fn refs(&self) -> impl Iterator<Item = ClauseRef> + '_ {
+        let mut offset = 0;
+        std::iter::from_fn(move || {
+            while offset < self.arena.len() {
+                let clause_offset = offset;
+                offset += HEADER_SIZE + self.header(clause_offset, HEADER_CAPACITY);
+                if self.header(clause_offset, HEADER_LEN) != 0 {
+                    return Some(ClauseRef::from_idx(clause_offset, IS_LEARNED));
+                }
+            }
+            None
+        })
+    }
+
+    /// Copy clause into arena
+    ///
+    /// # Panics
+    ///
+    /// If the clause is too long for its header or the arena exceeds `2^31`
+    /// slots
+    pub fn add_clause(&mut self, literals: &[Literal]) -> ClauseRef {
+        debug_assert!(!literals.is_empty());
+        let len = u32::try_from(literals.len()).expect("clause length exceeds the header");
+        let offset = self.arena.len();
+        self.arena.reserve(HEADER_SIZE + literals.len());
+        self.arena.push(Literal::from_repr(len as usize));
+        self.arena.push(Literal::from_repr(len as usize));
+        self.arena.push(Literal::from_repr(0));
+        self.arena
+            .push(Literal::from_repr(0.0f32.to_bits() as usize));
+        self.arena.extend_from_slice(literals);
+        self.num_clauses += 1;
+        ClauseRef::from_idx(offset, IS_LEARNED)
+    }
+
+    /// Remove clause; no-op if it is removed already or not in the arena, so
+    /// that the counts cannot underflow
     pub fn remove_clause(&mut self, clause_ref: ClauseRef) {
         debug_assert_eq!(clause_ref.is_learned(), IS_LEARNED);
-        let idx = clause_ref.idx();
-        if idx == self.container.len() - 1 {
-            self.container.pop();
-        } else {
-            self.container[idx].clear();
-            self.free_indices.push(clause_ref.idx());
+        if clause_ref.idx() >= self.arena.len() || self.is_removed(clause_ref) {
+            return;
+        }
+        let offset = clause_ref.idx();
+        self.wasted += HEADER_SIZE + self.header(offset, HEADER_LEN);
+        self.arena[offset + HEADER_LEN] = Literal::from_repr(0);
+        self.num_clauses -= 1;
+    }
+
+    /// Whether clause has been removed
+    #[must_use]
+    pub fn is_removed(&self, clause_ref: ClauseRef) -> bool {
+        self.header(clause_ref.idx(), HEADER_LEN) == 0
+    }
+
+    /// Shortens clause to its first `len` literals
+    pub fn truncate_clause(&mut self, clause_ref: ClauseRef, len: usize) {
+        let offset = clause_ref.idx();
+        let old_len = self.header(offset, HEADER_LEN);
+        debug_assert!(len > 0 && len <= old_len);
+        self.wasted += old_len - len;
+        self.arena[offset + HEADER_LEN] = Literal::from_repr(len);
+    }
+
+    /// Literal block distance of the clause
+    #[must_use]
+    pub fn lbd(&self, clause_ref: ClauseRef) -> usize {
+        self.header(clause_ref.idx(), HEADER_LBD)
+    }
+
+    /// Sets the literal block distance of the clause
+    pub fn set_lbd(&mut self, clause_ref: ClauseRef, lbd: usize) {
+        self.arena[clause_ref.idx() + HEADER_LBD] = Literal::from_repr(lbd);
+    }
+
+    /// Activity of the clause
+    #[must_use]
+    #[allow(clippy::cast_possible_truncation)]
+    pub fn activity(&self, clause_ref: ClauseRef) -> f32 {
+        f32::from_bits(self.header(clause_ref.idx(), HEADER_ACTIVITY) as u32)
+    }
+
+    /// Sets the activity of the clause
+    pub fn set_activity(&mut self, clause_ref: ClauseRef, activity: f32) {
+        self.arena[clause_ref.idx() + HEADER_ACTIVITY] =
+            Literal::from_repr(activity.to_bits() as usize);
+    }
+
+    /// Compacts the arena by dropping deleted clauses and trimmed literals;
+    /// all references held outside must be rewritten with the returned map
+    pub fn collect_garbage(&mut self) -> RelocationMap {
+        let mut arena = Vec::with_capacity(self.arena.len() - self.wasted);
+        let mut relocations = Vec::with_capacity(self.num_clauses);
+        for clause_ref in self.refs() {
+            let offset = clause_ref.idx();
+            let new_offset = arena.len();
+            arena.push(Literal::from_repr(self.header(offset, HEADER_LEN)));
+            arena.extend_from_slice(&self.arena[offset + HEADER_LEN..offset + HEADER_SIZE]);
+            arena.extend_from_slice(&self[clause_ref]);
+            relocations.push((clause_ref, ClauseRef::from_idx(new_offset, IS_LEARNED)));
         }
+        self.arena = arena;
+        self.wasted = 0;
+        RelocationMap { relocations }
     }
 
     /// Whether clause is satisfied
@@ -145,20 +331,28 @@ impl<const IS_LEARNED: bool> Clauses<IS_LEARNED> {
         }
         false
     }
+
+    /// Header slot of the clause at `offset`
+    fn header(&self, offset: usize, slot: usize) -> usize {
+        self.arena[offset + slot].repr()
+    }
 }
 
-/// Clause at given index
+/// Clause at given index; deleted clauses are empty
 impl<const IS_LEARNED: bool> std::ops::Index<ClauseRef> for Clauses<IS_LEARNED> {
-    type Output = Vec<Literal>;
+    type Output = [Literal];
 
     fn index(&self, index: ClauseRef) -> &Self::Output {
         debug_assert!(index.valid());
-        &self.container[index.idx()]
+        let offset = index.idx() + HEADER_SIZE;
+        &self.arena[offset..offset + self.header(index.idx(), HEADER_LEN)]
     }
 }
 impl<const IS_LEARNED: bool> std::ops::IndexMut<ClauseRef> for Clauses<IS_LEARNED> {
     fn index_mut(&mut self, index: ClauseRef) -> &mut Self::Output {
         debug_assert!(index.valid());
-        &mut self.container[index.idx()]
+        let offset = index.idx() + HEADER_SIZE;
+        let len = self.header(index.idx(), HEADER_LEN);
+        &mut self.arena[offset..offset + len]
     }
 }
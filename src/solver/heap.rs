@@ -0,0 +1,133 @@
+use crate::solver::variable::Variable;
+
+/// Position of a variable not in the heap
+const NOT_IN_HEAP: usize = usize::MAX;
+
+/// Binary max-heap of variables ordered by their activities, which are kept
+/// outside of the heap; activities may only grow or be scaled uniformly while
+/// their variables are in the heap
+#[derive(Clone, Debug, Default)]
+pub(super) struct VariableHeap {
+    /// Variables in heap order
+    heap: Vec<Variable>,
+    /// Position of each variable in `heap`, or `NOT_IN_HEAP`
+    positions: Vec<usize>,
+    /// Whether the lower variable index wins a tie of activities; otherwise
+    /// ties are left to the heap order
+    break_ties_by_index: bool,
+}
+
+impl VariableHeap {
+    /// Creates an empty heap; see `break_ties_by_index`
+    pub(super) const fn new(break_ties_by_index: bool) -> Self {
+        Self {
+            heap: Vec::new(),
+            positions: Vec::new(),
+            break_ties_by_index,
+        }
+    }
+
+    /// Grows the heap to `num_variables` variables; new variables are not inserted
+    pub(super) fn resize(&mut self, num_variables: usize) {
+        self.positions.resize(num_variables, NOT_IN_HEAP);
+    }
+
+    /// Removes all variables
+    pub(super) fn clear(&mut self) {
+        for &var in &self.heap {
+            self.positions[var] = NOT_IN_HEAP;
+        }
+        self.heap.clear();
+    }
+
+    /// Whether `var` is in the heap
+    pub(super) fn contains(&self, var: Variable) -> bool {
+        self.positions[var] != NOT_IN_HEAP
+    }
+
+    /// Inserts `var` unless it is in the heap already
+    pub(super) fn insert(&mut self, var: Variable, activities: &[f64]) {
+        if self.contains(var) {
+            return;
+        }
+        self.positions[var] = self.heap.len();
+        self.heap.push(var);
+        self.sift_up(self.heap.len() - 1, activities);
+    }
+
+    /// Restores the heap order after the activity of `var` increased
+    pub(super) fn increase(&mut self, var: Variable, activities: &[f64]) {
+        if self.contains(var) {
+            self.sift_up(self.positions[var], activities);
+        }
+    }
+
+    /// Removes and returns the variable with the highest activity
+    pub(super) fn pop_max(&mut self, activities: &[f64]) -> Option<Variable> {
+        let max = *self.heap.first()?;
+        let last = self.heap.pop().expect("heap is not empty");
+        self.positions[max] = NOT_IN_HEAP;
+        if !self.heap.is_empty() {
+            self.heap[0] = last;
+            self.positions[last] = 0;
+            self.sift_down(0, activities);
+        }
+        Some(max)
+    }
+
+    /// Heap memory reserved by the heap in bytes
+    pub(super) const fn heap_size_bytes(&self) -> usize {
+        (self.heap.capacity() + self.positions.capacity()) * size_of::<usize>()
+    }
+
+    /// Whether `var` belongs above `other` in the heap
+    fn is_above(&self, var: Variable, other: Variable, activities: &[f64]) -> bool {
+        activities[var] > activities[other]
+            || (self.break_ties_by_index
+                && activities[var].total_cmp(&activities[other]).is_eq()
+                && var < other)
+    }
+
+    /// Moves the variable at `idx` up while its parent is less active
+    fn sift_up(&mut self, mut idx: usize, activities: &[f64]) {
+        let var = self.heap[idx];
+        while idx > 0 {
+            let parent = (idx - 1) / 2;
+            if !self.is_above(var, self.heap[parent], activities) {
+                break;
+            }
+            self.heap[idx] = self.heap[parent];
+            self.positions[self.heap[idx]] = idx;
+            idx = parent;
+        }
+        self.heap[idx] = var;
+        self.positions[var] = idx;
+    }
+
+    /// Moves the variable at `idx` down while a child is more active
+    fn sift_down(&mut self, mut idx: usize, activities: &[f64]) {
+        let var = self.heap[idx];
+        loop {
+            let left = 2 * idx + 1;
+            if left >= self.heap.len() {
+                break;
+            }
+            let right = left + 1;
+            let child = if right < self.heap.len()
+                && self.is_above(self.heap[right], self.heap[left], activities)
+            {
+                right
+            } else {
+                left
+            };
+            if !self.is_above(self.heap[child], var, activities) {
+                break;
+            }
+            self.heap[idx] = self.heap[child];
+            self.positions[self.heap[idx]] = idx;
+            idx = child;
+        }
+        self.heap[idx] = var;
+        self.positions[var] = idx;
+    }
+}
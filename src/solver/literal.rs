@@ -1,38 +1,89 @@
-use std::ops::Not;
+use std::{fmt, ops::Not};
 
 use crate::solver::variable::{Variable, VariableValue};
 
 /// Literal type
-#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug)]
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
 pub struct Literal {
     /// Literal representation; positive and negative literals are consecutive;
     /// `[    0, 1,     2, 3,     4, 5, ...]`
     /// `[not 0, 0, not 1, 1, not 2, 2, ...]`
-    repr: Variable,
+    ///
+    /// Stored as `u32` to halve the memory of clauses; this limits instances
+    /// to less than `2^31` variables
+    repr: u32,
 }
 
 impl Literal {
-    /// Invalid literal
-    const INVALID: Variable = Variable::MAX;
+    /// Invalid literal; no variable maps to this representation
+    const INVALID: u32 = u32::MAX;
 
     /// Constructs a literal from a variable (0..n)
     /// with polarity (+ : true, - : false)
+    ///
+    /// # Panics
+    ///
+    /// If `variable` does not fit into the `2^31 - 1` representable variables
     #[must_use]
+    #[allow(clippy::cast_possible_truncation)]
     pub const fn from_var_with_polarity(variable: Variable, polarity: bool) -> Self {
+        assert!(
+            variable < (Self::INVALID >> 1) as Variable,
+            "variable exceeds the u32 literal representation"
+        );
         Literal {
-            repr: 2 * variable + polarity as Variable,
+            repr: 2 * variable as u32 + polarity as u32,
         }
     }
 
+    /// Constructs a literal from a DIMACS-style signed integer,
+    /// where `n` and `-n` are the literals of the `n`-th variable
+    #[must_use]
+    pub const fn from_dimacs(value: i32) -> Self {
+        debug_assert!(value != 0);
+        Self::from_var_with_polarity(value.unsigned_abs() as Variable - 1, value > 0)
+    }
+
+    /// Constructs a literal from a DIMACS-style signed integer like
+    /// `from_dimacs`
+    ///
+    /// # Errors
+    ///
+    /// For the clause terminator `0` and for `i32::MIN`, whose variable
+    /// exceeds the representable ones
+    pub const fn try_from_dimacs(value: i32) -> Result<Self, DimacsLiteralError> {
+        match value {
+            0 => Err(DimacsLiteralError::Terminator),
+            i32::MIN => Err(DimacsLiteralError::OutOfRange),
+            _ => Ok(Self::from_dimacs(value)),
+        }
+    }
+
+    /// DIMACS-style signed integer of the literal; inverse of `from_dimacs`
+    #[must_use]
+    #[allow(clippy::cast_possible_truncation, clippy::cast_possible_wrap)]
+    pub const fn to_dimacs(&self) -> i32 {
+        let value = self.var() as i32 + 1;
+        if self.polarity() { value } else { -value }
+    }
+
+    /// Constructs a literal from its representation
+    #[must_use]
+    #[allow(clippy::cast_possible_truncation)]
+    pub(crate) const fn from_repr(repr: usize) -> Self {
+        debug_assert!(repr <= Self::INVALID as usize);
+        Literal { repr: repr as u32 }
+    }
+
     /// Access representation for indexing
     #[must_use]
     pub const fn repr(&self) -> usize {
-        self.repr
+        self.repr as usize
     }
     /// The variable used in the literal for indexing purposes
     #[must_use]
-    pub const fn var(&self) -> usize {
-        self.repr >> 1
+    pub const fn var(&self) -> Variable {
+        (self.repr >> 1) as Variable
     }
     /// The polarity of the literal (+ : true, - : false)
     #[must_use]
@@ -69,6 +120,53 @@ impl Default for Literal {
     }
 }
 
+/// Displays a literal as DIMACS-style signed integer, such as `-3`
+impl fmt::Display for Literal {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.valid() {
+            write!(f, "{}", self.to_dimacs())
+        } else {
+            write!(f, "invalid")
+        }
+    }
+}
+
+/// Reason why a DIMACS-style signed integer is not a literal
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum DimacsLiteralError {
+    /// `0` terminates clauses and is no literal
+    Terminator,
+    /// The variable exceeds the representable ones
+    OutOfRange,
+}
+
+impl fmt::Display for DimacsLiteralError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Terminator => write!(f, "0 terminates clauses and is no literal"),
+            Self::OutOfRange => write!(f, "variable exceeds the representable ones"),
+        }
+    }
+}
+
+impl std::error::Error for DimacsLiteralError {}
+
+/// Conversion from DIMACS-style signed integers; fails like `try_from_dimacs`
+impl TryFrom<i32> for Literal {
+    type Error = DimacsLiteralError;
+
+    fn try_from(value: i32) -> Result<Self, Self::Error> {
+        Self::try_from_dimacs(value)
+    }
+}
+
+/// Conversion to DIMACS-style signed integers
+impl From<Literal> for i32 {
+    fn from(literal: Literal) -> Self {
+        literal.to_dimacs()
+    }
+}
+
 /// Implement negation for literals
 impl Not for Literal {
     type Output = Self;
@@ -5,5 +5,218 @@ pub const MAX_LEARNED_CLAUSES_INCREMENT: f64 = 1.1;
 /// After how many conflicts to adjust the
 /// maximum number of learned clauses again
 pub const MAX_LEARNED_ADJUST_INCREMENT: f64 = 1.5;
+/// Maximum number of propagations per failed-literal probing round
+pub const PROBE_PROPAGATION_BUDGET: usize = 100_000;
+/// Maximum number of propagations per vivification round
+pub const VIVIFICATION_PROPAGATION_BUDGET: usize = 50_000;
+/// Number of restarts between vivification rounds
+pub const VIVIFICATION_RESTART_INTERVAL: usize = 8;
+/// Clauses with more subsumption candidates are not checked
+pub const SUBSUMPTION_OCCURRENCE_LIMIT: usize = 1_000;
+/// Variables with more occurrences are not considered for elimination
+pub const ELIMINATION_OCCURRENCE_LIMIT: usize = 16;
+/// Variables producing longer resolvents are not eliminated
+pub const ELIMINATION_RESOLVENT_LENGTH_LIMIT: usize = 20;
+/// Literals whose negation occurs in more clauses are not checked for blocking
+pub const BLOCKING_OCCURRENCE_LIMIT: usize = 100;
+/// Fraction of wasted arena slots that triggers a garbage collection
+pub const GARBAGE_FRACTION: f64 = 0.2;
+/// Factor by which learned clause activities decay per conflict
+pub const CLAUSE_ACTIVITY_DECAY: f64 = 0.999;
+/// Activity above which all learned clause activities are rescaled
+pub const CLAUSE_ACTIVITY_RESCALE_LIMIT: f64 = 1e20;
+/// Factor by which variable activities decay per conflict with
+/// `BranchingHeuristic::Vsids`
+pub const VARIABLE_ACTIVITY_DECAY: f64 = 0.95;
+/// Activity above which all variable activities are rescaled
+pub const VARIABLE_ACTIVITY_RESCALE_LIMIT: f64 = 1e100;
+/// Learned clauses with at most this literal block distance are never deleted
+/// by `ReductionStrategy::Lbd`
+pub const GLUE_LBD: usize = 2;
+/// Number of restarts between rephasing rounds
+pub const REPHASE_RESTART_INTERVAL: usize = 16;
 /// The base restart interval
 pub const RESTART_FIRST: usize = 100;
+/// Growth of the restart interval with `RestartStrategy::Geometric`
+pub const RESTART_GEOMETRIC_FACTOR: f64 = 1.5;
+/// Restarts are only blocked after this many conflicts
+pub const RESTART_BLOCKING_MIN_CONFLICTS: usize = 10_000;
+/// Restarts are blocked if the trail exceeds its average by this factor
+pub const RESTART_BLOCKING_MARGIN: f64 = 1.4;
+/// Number of conflicts by which a blocked restart is postponed
+pub const RESTART_BLOCKING_DELAY: usize = 50;
+/// Weight of the latest trail length in its moving average
+pub const TRAIL_LENGTH_EMA_ALPHA: f64 = 1.0 / 5_000.0;
+/// Number of most recent learned clauses kept for import by other solvers
+pub const SHARED_CLAUSE_CAPACITY: usize = 10_000;
+
+/// How the number of conflicts between restarts develops
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub enum RestartStrategy {
+    /// Multiples of the Luby sequence `1,1,2,1,1,2,4,...`
+    #[default]
+    Luby,
+    /// Intervals growing by a constant factor
+    Geometric,
+}
+
+/// How the next decision variable is selected
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub enum BranchingHeuristic {
+    /// Uniformly random unset variable
+    #[default]
+    Random,
+    /// Unset variable most involved in recent conflicts (EVSIDS)
+    Vsids,
+    /// Unset variable with the lowest index and its saved polarity, without
+    /// any randomness; for reproducing and minimizing failures
+    InOrder,
+    /// Like `Vsids`, but activity ties go to the lowest variable index and
+    /// the saved polarity is never flipped at random, so that the decisions
+    /// do not depend on the seed
+    DeterministicVsids,
+}
+
+/// Saved polarity of new variables, which their first decisions use
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub enum InitialPolarity {
+    /// Negative polarity
+    #[default]
+    False,
+    /// Positive polarity
+    True,
+    /// Uniformly random polarity
+    Random,
+    /// Polarity of the literal with the higher Jeroslow-Wang score, the sum of
+    /// `2^-len` over the original clauses containing it; computed when solving
+    /// starts, which overrides earlier phase hints of the new variables
+    Jeroslow,
+}
+
+/// Which learned clauses are deleted when there are too many
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub enum ReductionStrategy {
+    /// Delete the half with the lowest activity
+    Activity,
+    /// Delete the half with the highest literal block distance
+    Lbd,
+    /// Delete each clause with probability 1/2
+    #[default]
+    Random,
+}
+
+/// Where conflict analysis stops resolving and which literals the learned
+/// clause consists of
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub enum LearningScheme {
+    /// Stop at the first unique implication point of the conflict level
+    #[default]
+    FirstUip,
+    /// Resolve up to the decision of the conflict level
+    LastUip,
+    /// Resolve up to the decisions of all levels (rel_sat)
+    AllDecisions,
+}
+
+/// Which literals are removed from learned clauses as implied by the others
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub enum MinimizationLevel {
+    /// Keep all literals
+    None,
+    /// Remove literals whose reason literals are all in the clause
+    Local,
+    /// Remove literals whose reasons are implied by the clause transitively
+    #[default]
+    Recursive,
+}
+
+/// Configurable solver options
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct SolverOptions {
+    /// Assign pure literals at the top level during simplification; as they
+    /// are not implied, they are undone before the next `solve` and whenever
+    /// clauses or assumptions are added
+    pub pure_literal_elimination: bool,
+    /// Probe unset variables for failed literals during top-level
+    /// simplification
+    pub failed_literal_probing: bool,
+    /// Run subsumption and self-subsuming resolution before search
+    pub subsumption: bool,
+    /// Run bounded variable elimination before search
+    pub variable_elimination: bool,
+    /// Run blocked clause elimination before search
+    pub blocked_clause_elimination: bool,
+    /// Shorten learned clauses by vivification every few restarts
+    pub vivification: bool,
+    /// How learned clauses are selected for deletion
+    pub reduction_strategy: ReductionStrategy,
+    /// Conflicts until the first reduction of the learned clauses that is
+    /// scheduled regardless of their number, as in MiniSat; `None` reduces
+    /// only when they exceed the growing limit
+    pub reduction_first_interval: Option<usize>,
+    /// Factor by which the interval between scheduled reductions grows
+    pub reduction_interval_growth: f64,
+    /// Saved polarity of new variables
+    pub initial_polarity: InitialPolarity,
+    /// Probability in `[0, 1]` of branching against the saved polarity
+    pub random_phase_prob: f64,
+    /// Periodically reset the saved polarities to all-true, all-false, random,
+    /// or the best phase seen so far, in turn
+    pub rephasing: bool,
+    /// Conflicts after which the search alternates between the normal mode
+    /// and a stabilizing mode, which branches on the target phases of the
+    /// longest conflict-free trail instead of the saved polarities; `None`
+    /// never stabilizes
+    pub stabilization_interval: Option<usize>,
+    /// Which literals learned clauses consist of
+    pub learning_scheme: LearningScheme,
+    /// How learned clauses are minimized
+    pub minimization: MinimizationLevel,
+    /// How the intervals between restarts are chosen
+    pub restart_strategy: RestartStrategy,
+    /// Postpone restarts while the trail is much longer than on average
+    pub restart_blocking: bool,
+    /// How decision variables are selected
+    pub branching: BranchingHeuristic,
+    /// Seed of the random generator for branching, phases, and reduction
+    pub random_seed: u64,
+    /// Skip original clauses identical to one added before, up to literal
+    /// order and duplicate literals; checked until preprocessing is done
+    pub duplicate_clause_detection: bool,
+    /// Largest number of variables `create_variables` allocates; guards
+    /// against exhausting memory on corrupt headers
+    pub max_variables: usize,
+    /// Record the clauses every derived clause is resolved from, so that
+    /// `Solver::unsat_core` can name the original clauses refuting the
+    /// instance
+    pub unsat_core: bool,
+}
+
+impl Default for SolverOptions {
+    fn default() -> Self {
+        Self {
+            pure_literal_elimination: false,
+            failed_literal_probing: false,
+            subsumption: false,
+            variable_elimination: false,
+            blocked_clause_elimination: false,
+            vivification: false,
+            reduction_strategy: ReductionStrategy::default(),
+            reduction_first_interval: None,
+            reduction_interval_growth: 1.1,
+            initial_polarity: InitialPolarity::default(),
+            random_phase_prob: 0.0,
+            rephasing: false,
+            stabilization_interval: None,
+            learning_scheme: LearningScheme::default(),
+            minimization: MinimizationLevel::default(),
+            restart_strategy: RestartStrategy::default(),
+            restart_blocking: false,
+            branching: BranchingHeuristic::default(),
+            random_seed: 42,
+            duplicate_clause_detection: false,
+            max_variables: 1 << 26,
+            unsat_core: false,
+        }
+    }
+}
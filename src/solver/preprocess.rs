@@ -0,0 +1,590 @@
+use std::slice;
+
+use crate::{
+    proof::ClauseId,
+    solver::{
+        Solver,
+        clause::{ClauseRef, Clauses},
+        literal::Literal,
+        options,
+        variable::{Variable, VariableValue},
+    },
+};
+
+/// Original clauses containing each literal, indexed by `Literal::repr()`;
+/// kept up to date while clauses are added and removed, except that clauses
+/// trimmed by top-level simplification remain listed under their removed
+/// literals
+#[derive(Clone, Debug, Default)]
+pub(super) struct OccurrenceLists {
+    /// Clauses per literal
+    lists: Vec<Vec<ClauseRef>>,
+}
+
+impl OccurrenceLists {
+    /// Builds the occurrence lists of `clauses`
+    pub(super) fn new(clauses: &Clauses<false>, num_variables: usize) -> Self {
+        let mut occurrences = Self {
+            lists: vec![Vec::new(); 2 * num_variables],
+        };
+        for clause_ref in clauses.refs() {
+            occurrences.add(clause_ref, &clauses[clause_ref]);
+        }
+        occurrences
+    }
+
+    /// Grows the lists to `num_variables` variables
+    pub(super) fn resize(&mut self, num_variables: usize) {
+        self.lists.resize(2 * num_variables, Vec::new());
+    }
+
+    /// Lists an added clause under its literals
+    pub(super) fn add(&mut self, clause_ref: ClauseRef, literals: &[Literal]) {
+        for literal in literals {
+            self.lists[literal.repr()].push(clause_ref);
+        }
+    }
+
+    /// Unlists a removed clause from its literals
+    pub(super) fn remove(&mut self, clause_ref: ClauseRef, literals: &[Literal]) {
+        for literal in literals {
+            self.lists[literal.repr()].retain(|&other_ref| other_ref != clause_ref);
+        }
+    }
+
+    /// Clauses listed under `literal`
+    pub(super) fn get(&self, literal: Literal) -> &[ClauseRef] {
+        &self.lists[literal.repr()]
+    }
+
+    /// Number of clauses listed under `literal` or its negation
+    fn num_occurrences_of_var(&self, literal: Literal) -> usize {
+        self.lists[literal.repr()].len() + self.lists[(!literal).repr()].len()
+    }
+}
+
+/// Original clauses removed by preprocessing that models of the remaining
+/// clauses are extended to
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub(super) enum Elimination {
+    /// Clauses of a variable removed by variable elimination
+    Variable(Variable, Vec<Vec<Literal>>),
+    /// Clause removed by blocked clause elimination with its blocking literal
+    BlockedClause(Literal, Vec<Literal>),
+}
+
+impl Elimination {
+    /// Removed clauses
+    pub(super) fn clauses(&self) -> &[Vec<Literal>] {
+        match self {
+            Self::Variable(_, clauses) => clauses,
+            Self::BlockedClause(_, clause) => slice::from_ref(clause),
+        }
+    }
+
+    /// Heap memory reserved by the removed clauses in bytes
+    pub(super) fn heap_size_bytes(&self) -> usize {
+        match self {
+            Self::Variable(_, clauses) => {
+                clauses.capacity() * size_of::<Vec<Literal>>()
+                    + clauses
+                        .iter()
+                        .map(|clause| clause.capacity() * size_of::<Literal>())
+                        .sum::<usize>()
+            }
+            Self::BlockedClause(_, clause) => clause.capacity() * size_of::<Literal>(),
+        }
+    }
+}
+
+/// Relation of a clause to another clause
+enum Subsumption {
+    /// No relation
+    None,
+    /// All literals of the clause occur in the other clause
+    Subsumes,
+    /// The given literal can be removed from the other clause
+    Strengthens(Literal),
+}
+
+impl Solver {
+    /// Bounded variable elimination: resolves out variables whose original
+    /// clauses can be replaced by at most as many non-tautological resolvents;
+    /// the removed clauses are kept to reconstruct the eliminated variables'
+    /// values in the model; returns `false` if the instance is found to be UNSAT
+    pub(super) fn eliminate_variables(&mut self) -> bool {
+        self.revert_trail(0);
+        // Pure literals hide the clauses they satisfy
+        self.restore_pure_literals();
+        if self.propagate().valid() {
+            return false;
+        }
+        self.build_occurrence_lists();
+
+        // Try variables with few occurrences first
+        let mut candidates: Vec<Variable> = (0..self.num_variables())
+            .filter(|&var| {
+                self.variable_values[var].is_unset() && !self.eliminated[var] && !self.frozen[var]
+            })
+            .collect();
+        let occurrences = self.occurrence_lists();
+        candidates.sort_by_key(|&var| {
+            occurrences.num_occurrences_of_var(Literal::from_var_with_polarity(var, true))
+        });
+
+        for var in candidates {
+            if !self.variable_values[var].is_unset() {
+                continue;
+            }
+            let positive = Literal::from_var_with_polarity(var, true);
+            let positive_clauses = self.live_occurrences(positive);
+            let negative_clauses = self.live_occurrences(!positive);
+            let num_clauses = positive_clauses.len() + negative_clauses.len();
+            if num_clauses > options::ELIMINATION_OCCURRENCE_LIMIT {
+                continue;
+            }
+
+            // Elimination must not increase the number of clauses
+            let mut resolvents = Vec::new();
+            let mut is_bounded = true;
+            'resolving: for &positive_ref in &positive_clauses {
+                for &negative_ref in &negative_clauses {
+                    let resolvent = Self::resolve(
+                        &self.clauses[positive_ref],
+                        &self.clauses[negative_ref],
+                        var,
+                    );
+                    if let Some(resolvent) = resolvent {
+                        if resolvents.len() == num_clauses
+                            || resolvent.len() > options::ELIMINATION_RESOLVENT_LENGTH_LIMIT
+                        {
+                            is_bounded = false;
+                            break 'resolving;
+                        }
+                        resolvents.push((resolvent, [positive_ref, negative_ref]));
+                    }
+                }
+            }
+            if !is_bounded {
+                continue;
+            }
+
+            // Replace the clauses containing `var` by their resolvents, which
+            // the proof derives before the clauses are removed
+            let resolvent_ids: Vec<Option<ClauseId>> = resolvents
+                .iter()
+                .map(|(resolvent, antecedents)| self.trace_resolvent(resolvent, antecedents))
+                .collect();
+            let mut removed_clauses = Vec::with_capacity(num_clauses);
+            for &clause_ref in positive_clauses.iter().chain(&negative_clauses) {
+                removed_clauses.push(self.clauses[clause_ref].to_vec());
+                self.detach_clause(clause_ref);
+            }
+            self.eliminated_clauses
+                .push(Elimination::Variable(var, removed_clauses));
+            self.eliminated[var] = true;
+            self.stats.num_eliminated_variables += 1;
+            for ((resolvent, _), id) in resolvents.into_iter().zip(resolvent_ids) {
+                if !self.add_original_clause(&resolvent, id).0 {
+                    return false;
+                }
+            }
+        }
+
+        // Learned clauses must not mention eliminated variables
+        let clause_refs: Vec<ClauseRef> = self.learned_clauses.refs().collect();
+        for clause_ref in clause_refs {
+            if self.learned_clauses[clause_ref]
+                .iter()
+                .any(|literal| self.eliminated[literal.var()])
+            {
+                self.detach_clause(clause_ref);
+            }
+        }
+
+        true
+    }
+
+    /// Blocked clause elimination: removes original clauses containing a
+    /// literal whose resolvents with all clauses containing its negation are
+    /// tautologies; the removed clauses are kept to repair the model; returns
+    /// `false` if the instance is found to be UNSAT
+    pub(super) fn blocked_clause_elimination(&mut self) -> bool {
+        self.revert_trail(0);
+        // Pure literals hide the clauses they satisfy
+        self.restore_pure_literals();
+        if self.propagate().valid() {
+            return false;
+        }
+        self.build_occurrence_lists();
+
+        let clause_refs: Vec<ClauseRef> = self.clauses.refs().collect();
+        for clause_ref in clause_refs {
+            if self.clauses.is_removed(clause_ref)
+                || self
+                    .clauses
+                    .is_clause_satisfied(clause_ref, &self.variable_values)
+            {
+                continue;
+            }
+
+            // Frozen variables may occur in clauses added later
+            let blocking_literal = self.clauses[clause_ref].iter().copied().find(|&literal| {
+                literal.is_unset(&self.variable_values)
+                    && !self.frozen[literal.var()]
+                    && self.is_blocking_literal(clause_ref, literal)
+            });
+            if let Some(literal) = blocking_literal {
+                let clause = self.clauses[clause_ref].to_vec();
+                self.detach_clause(clause_ref);
+                self.blocking[literal.var()] = true;
+                self.eliminated_clauses
+                    .push(Elimination::BlockedClause(literal, clause));
+                self.stats.num_blocked_clauses += 1;
+            }
+        }
+
+        true
+    }
+
+    /// Whether all resolvents of the clause with the clauses containing the
+    /// negation of `literal` are tautologies
+    fn is_blocking_literal(&self, clause_ref: ClauseRef, literal: Literal) -> bool {
+        let negative_clauses = self.live_occurrences(!literal);
+        if negative_clauses.len() > options::BLOCKING_OCCURRENCE_LIMIT {
+            return false;
+        }
+        let clause = &self.clauses[clause_ref];
+        negative_clauses.iter().all(|&other_ref| {
+            self.clauses[other_ref]
+                .iter()
+                .any(|&other| other != !literal && clause.contains(&!other))
+        })
+    }
+
+    /// Removes original clauses that are subsumed by another original clause and
+    /// strengthens clauses by self-subsuming resolution; returns `false` if the
+    /// instance is found to be UNSAT
+    pub(super) fn subsume_clauses(&mut self) -> bool {
+        self.revert_trail(0);
+        if self.propagate().valid() {
+            return false;
+        }
+
+        // Clause signatures
+        self.build_occurrence_lists();
+        let mut signatures = vec![0u64; self.clauses.arena_len()];
+        let mut queue: Vec<ClauseRef> = self.clauses.refs().collect();
+        for &clause_ref in &queue {
+            signatures[clause_ref.idx()] = Self::signature(&self.clauses[clause_ref]);
+        }
+
+        // Short clauses subsume the most clauses
+        queue.sort_by_key(|&clause_ref| self.clauses[clause_ref].len());
+        let mut queue_idx = 0;
+        while queue_idx < queue.len() {
+            let clause_ref = queue[queue_idx];
+            queue_idx += 1;
+            if self.clauses.is_removed(clause_ref) {
+                continue;
+            }
+
+            // Candidates contain the literal with the fewest occurrences or its negation
+            let occurrences = self.occurrence_lists();
+            let pivot = *self.clauses[clause_ref]
+                .iter()
+                .min_by_key(|&&literal| occurrences.num_occurrences_of_var(literal))
+                .unwrap_or(&Literal::default());
+            let mut candidates = occurrences.get(pivot).to_vec();
+            candidates.extend_from_slice(occurrences.get(!pivot));
+            if candidates.len() > options::SUBSUMPTION_OCCURRENCE_LIMIT {
+                continue;
+            }
+
+            for other_ref in candidates {
+                if other_ref == clause_ref
+                    || self.clauses.is_removed(other_ref)
+                    || self.clauses.is_removed(clause_ref)
+                    || self.clauses[other_ref].len() < self.clauses[clause_ref].len()
+                    || signatures[clause_ref.idx()] & !signatures[other_ref.idx()] != 0
+                {
+                    continue;
+                }
+                match Self::subsumption(&self.clauses[clause_ref], &self.clauses[other_ref]) {
+                    Subsumption::None => {}
+                    Subsumption::Subsumes => {
+                        self.detach_clause(other_ref);
+                        self.stats.num_subsumed_clauses += 1;
+                    }
+                    Subsumption::Strengthens(literal) => {
+                        // Replace other clause by its strengthened version
+                        let strengthened: Vec<Literal> = self.clauses[other_ref]
+                            .iter()
+                            .copied()
+                            .filter(|&other_literal| other_literal != literal)
+                            .collect();
+                        let id = self.trace_resolvent(&strengthened, &[clause_ref, other_ref]);
+                        self.detach_clause(other_ref);
+                        self.stats.num_strengthened_clauses += 1;
+                        let (is_satisfiable, new_ref) = self.add_original_clause(&strengthened, id);
+                        if !is_satisfiable {
+                            return false;
+                        }
+                        if let Some(new_ref) = new_ref {
+                            if new_ref.idx() >= signatures.len() {
+                                signatures.resize(new_ref.idx() + 1, 0);
+                            }
+                            signatures[new_ref.idx()] = Self::signature(&self.clauses[new_ref]);
+                            queue.push(new_ref);
+                        }
+                    }
+                }
+            }
+        }
+
+        true
+    }
+
+    /// Runs a round of learned clause vivification on the top level, as
+    /// `solve` does between restarts with the `vivification` option; returns
+    /// `false` if the instance is found to be UNSAT
+    pub fn vivify(&mut self) -> bool {
+        if !self.ok {
+            return false;
+        }
+        self.revert_trail(0);
+        self.retract_extended_model();
+        self.ok = self.vivify_learned_clauses();
+        self.ok
+    }
+
+    /// Vivification: shortens learned clauses by assigning the negation of their
+    /// literals one at a time and dropping literals that are implied false;
+    /// the work is bounded by `VIVIFICATION_PROPAGATION_BUDGET` propagations;
+    /// returns `false` if the instance is found to be UNSAT
+    pub(super) fn vivify_learned_clauses(&mut self) -> bool {
+        self.revert_trail(0);
+        if self.propagate().valid() {
+            return false;
+        }
+
+        // Vivification must not overwrite the saved phases
+        let saved_polarity = self.variable_polarity.clone();
+        let propagation_limit =
+            self.stats.num_propagations + options::VIVIFICATION_PROPAGATION_BUDGET;
+        let mut is_satisfiable = true;
+        let clause_refs: Vec<ClauseRef> = self.learned_clauses.refs().collect();
+        for clause_ref in clause_refs {
+            if self.stats.num_propagations >= propagation_limit {
+                break;
+            }
+            if self.learned_clauses[clause_ref].len() <= 2
+                || self.is_locked_clause(clause_ref)
+                || self
+                    .learned_clauses
+                    .is_clause_satisfied(clause_ref, &self.variable_values)
+            {
+                continue;
+            }
+
+            // The clause must not propagate its own literals
+            let literals = self.learned_clauses[clause_ref].to_vec();
+            let lbd = self.learned_clauses.lbd(clause_ref);
+            let activity = self.learned_clauses.activity(clause_ref);
+            let old_id = self.untrace_clause(clause_ref);
+            self.detach_clause(clause_ref);
+
+            // Keep literals until the clause is implied
+            self.trail_separators.push(self.trail.len());
+            let mut kept_literals = Vec::with_capacity(literals.len());
+            let mut implication = ClauseRef::default();
+            for &literal in &literals {
+                if literal.is_true(&self.variable_values) {
+                    kept_literals.push(literal);
+                    implication = self.variable_metadata[literal.var()].reason_clause_idx;
+                    break;
+                }
+                if literal.is_false(&self.variable_values) {
+                    continue;
+                }
+                kept_literals.push(literal);
+                self.assign_literal(!literal, ClauseRef::default());
+                implication = self.propagate();
+                if implication.valid() {
+                    break;
+                }
+            }
+
+            // The proof derives the shortened clause from the propagations
+            // that implied it, or from the clause itself
+            let id = match old_id {
+                Some(old_id) if kept_literals.len() < literals.len() => {
+                    let id = if implication.valid() {
+                        self.trace_conflict(&kept_literals, implication)
+                    } else {
+                        self.trace_derivation(&kept_literals, &literals, old_id)
+                    };
+                    self.trace_deletion(Some(old_id), &literals);
+                    id
+                }
+                _ => old_id,
+            };
+            self.revert_trail(0);
+            self.stats.num_vivified_literals += literals.len() - kept_literals.len();
+
+            // Re-attach the (possibly shortened) clause
+            match kept_literals.len() {
+                0 => {
+                    is_satisfiable = false;
+                    break;
+                }
+                1 => {
+                    self.trace_unit(kept_literals[0], id);
+                    self.assign_literal(kept_literals[0], ClauseRef::default());
+                    if self.propagate().valid() {
+                        is_satisfiable = false;
+                        break;
+                    }
+                }
+                _ => {
+                    let new_ref = self.attach_clause::<true>(&kept_literals);
+                    self.trace_attached_clause(new_ref, id);
+                    self.learned_clauses
+                        .set_lbd(new_ref, lbd.min(kept_literals.len()));
+                    self.learned_clauses.set_activity(new_ref, activity);
+                }
+            }
+        }
+        self.variable_polarity = saved_polarity;
+        is_satisfiable
+    }
+
+    /// Assigns the eliminated variables and flips blocking literals such that
+    /// the removed clauses are satisfied, in the reverse order of removal
+    pub(super) fn extend_model(&mut self) {
+        for elimination in self.eliminated_clauses.iter().rev() {
+            match elimination {
+                Elimination::Variable(var, clauses) => {
+                    // Clauses containing the negative literal are satisfied by default;
+                    // a violated clause forces the positive literal
+                    self.variable_values[*var] = VariableValue::False;
+                    for clause in clauses {
+                        if !clause
+                            .iter()
+                            .any(|literal| literal.is_true(&self.variable_values))
+                        {
+                            self.variable_values[*var] = VariableValue::True;
+                        }
+                    }
+                }
+                Elimination::BlockedClause(blocking_literal, clause) => {
+                    // Clauses containing the negation of the blocking literal stay
+                    // satisfied, as each shares another literal negated with the clause
+                    if !clause
+                        .iter()
+                        .any(|literal| literal.is_true(&self.variable_values))
+                    {
+                        self.flipped_by_extension.push(blocking_literal.var());
+                        self.variable_values[blocking_literal.var()] =
+                            VariableValue::from_bool(blocking_literal.polarity());
+                    }
+                }
+            }
+        }
+    }
+
+    /// Restores the variables changed by `extend_model`
+    pub(super) fn retract_extended_model(&mut self) {
+        // The trail holds the values before flipping, also if it was reverted since
+        if !self.flipped_by_extension.is_empty() {
+            for var in self.flipped_by_extension.drain(..) {
+                self.variable_values[var] = VariableValue::Unset;
+            }
+            for literal in &self.trail {
+                self.variable_values[literal.var()] = VariableValue::from_bool(literal.polarity());
+            }
+        }
+        for elimination in &self.eliminated_clauses {
+            if let Elimination::Variable(var, _) = elimination {
+                self.variable_values[*var] = VariableValue::Unset;
+            }
+        }
+    }
+
+    /// Builds the occurrence lists unless they are kept up to date already
+    pub(super) fn build_occurrence_lists(&mut self) {
+        if self.occurrence_lists.is_none() {
+            self.occurrence_lists = Some(OccurrenceLists::new(&self.clauses, self.num_variables()));
+        }
+    }
+
+    /// Occurrence lists built by `build_occurrence_lists`
+    fn occurrence_lists(&self) -> &OccurrenceLists {
+        self.occurrence_lists
+            .as_ref()
+            .expect("occurrence lists are built")
+    }
+
+    /// Live, unsatisfied original clauses that contain `literal`
+    pub(super) fn live_occurrences(&self, literal: Literal) -> Vec<ClauseRef> {
+        // Trimmed clauses may have lost the literal
+        let mut live: Vec<ClauseRef> = self
+            .occurrence_lists()
+            .get(literal)
+            .iter()
+            .copied()
+            .filter(|&clause_ref| {
+                self.clauses[clause_ref].contains(&literal)
+                    && !self
+                        .clauses
+                        .is_clause_satisfied(clause_ref, &self.variable_values)
+            })
+            .collect();
+        live.sort_by_key(ClauseRef::idx);
+        live.dedup();
+        live
+    }
+
+    /// Bloom filter over the variables of a clause
+    fn signature(clause: &[Literal]) -> u64 {
+        clause.iter().fold(0, |signature, literal| {
+            signature | (1 << (literal.var() % 64))
+        })
+    }
+
+    /// Whether `clause` subsumes `other` or strengthens it by self-subsuming resolution
+    fn subsumption(clause: &[Literal], other: &[Literal]) -> Subsumption {
+        let mut result = Subsumption::Subsumes;
+        for &literal in clause {
+            if other.contains(&literal) {
+                continue;
+            }
+            match result {
+                Subsumption::Subsumes if other.contains(&!literal) => {
+                    result = Subsumption::Strengthens(!literal);
+                }
+                _ => return Subsumption::None,
+            }
+        }
+        result
+    }
+
+    /// Resolvent of `positive` and `negative` on `var`; `None` if tautological
+    fn resolve(positive: &[Literal], negative: &[Literal], var: Variable) -> Option<Vec<Literal>> {
+        let mut resolvent: Vec<Literal> = positive
+            .iter()
+            .copied()
+            .filter(|literal| literal.var() != var)
+            .collect();
+        for &literal in negative {
+            if literal.var() == var || resolvent.contains(&literal) {
+                continue;
+            }
+            if resolvent.contains(&!literal) {
+                return None;
+            }
+            resolvent.push(literal);
+        }
+        Some(resolvent)
+    }
+}
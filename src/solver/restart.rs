@@ -1,3 +1,5 @@
+use crate::{helper::f64_to_usize_trunc, solver::options::RESTART_GEOMETRIC_FACTOR};
+
 /// Luby restart sequence (Luby, Sinclair, Zuckerman 1993);
 /// `1,1,2,1,1,2,4,1,1,2,1,1,2,4,8,...`
 pub fn luby(idx: usize) -> usize {
@@ -18,3 +20,10 @@ pub fn luby(idx: usize) -> usize {
 
     2usize.pow(seq)
 }
+
+/// Geometric restart sequence of truncated powers of
+/// `RESTART_GEOMETRIC_FACTOR`; `1,1,2,3,5,7,11,17,...`
+pub fn geometric(idx: usize) -> usize {
+    let exponent = i32::try_from(idx).unwrap_or(i32::MAX);
+    f64_to_usize_trunc(RESTART_GEOMETRIC_FACTOR.powi(exponent)).max(1)
+}
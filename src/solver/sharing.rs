@@ -0,0 +1,173 @@
+use std::{
+    collections::VecDeque,
+    sync::{Arc, Mutex},
+};
+
+use crate::solver::{Solver, clause::ClauseRef, literal::Literal, options};
+
+/// Learned clause exported by one solver of a group
+#[derive(Clone, PartialEq, Eq, Debug)]
+struct SharedClause {
+    /// Identifier of the exporting solver
+    source: usize,
+    /// Literal block distance when exported
+    lbd: usize,
+    /// Literals of the clause
+    literals: Vec<Literal>,
+}
+
+/// Most recent exports; older clauses are dropped once the buffer is full
+#[derive(Debug, Default)]
+struct SharedClauses {
+    /// Exported clauses, oldest first
+    clauses: VecDeque<SharedClause>,
+    /// Number of clauses dropped from the front
+    num_dropped: usize,
+}
+
+/// Bounded buffer through which solvers of the same instance exchange short
+/// learned clauses
+///
+/// Learned clauses are only implied by the instance if no solver of the group
+/// ran variable or blocked clause elimination, as these keep satisfiability
+/// but not equivalence.
+#[derive(Debug, Default)]
+pub struct ClauseExchange {
+    /// Exported clauses
+    buffer: Mutex<SharedClauses>,
+}
+
+impl ClauseExchange {
+    /// Creates an empty exchange
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Appends a clause; drops the oldest clause if the buffer is full
+    fn export(&self, source: usize, lbd: usize, literals: &[Literal]) {
+        let mut buffer = self.buffer.lock().expect("no solver thread panicked");
+        buffer.clauses.push_back(SharedClause {
+            source,
+            lbd,
+            literals: literals.to_vec(),
+        });
+        if buffer.clauses.len() > options::SHARED_CLAUSE_CAPACITY {
+            buffer.clauses.pop_front();
+            buffer.num_dropped += 1;
+        }
+    }
+
+    /// Clauses of other solvers exported since `next_clause`, which is
+    /// advanced past them; dropped clauses are skipped
+    fn import(&self, target: usize, next_clause: &mut usize) -> Vec<SharedClause> {
+        let buffer = self.buffer.lock().expect("no solver thread panicked");
+        let start = next_clause.saturating_sub(buffer.num_dropped);
+        *next_clause = buffer.num_dropped + buffer.clauses.len();
+        buffer
+            .clauses
+            .iter()
+            .skip(start)
+            .filter(|clause| clause.source != target)
+            .cloned()
+            .collect()
+    }
+}
+
+/// Connection of a solver to a clause exchange
+#[derive(Debug)]
+pub(super) struct SharingEndpoint {
+    /// Shared buffer
+    exchange: Arc<ClauseExchange>,
+    /// Identifier of the solver within the group
+    id: usize,
+    /// Learned clauses with at most this literal block distance are exported
+    max_lbd: usize,
+    /// Index of the first clause not yet imported
+    next_clause: usize,
+}
+
+impl Solver {
+    /// Connects the solver to `exchange` under the group-unique `id`; learned
+    /// clauses with a literal block distance of at most `max_lbd` are exported
+    /// and the clauses of the other solvers are imported at every restart
+    ///
+    /// All solvers of the group must load the same clauses over the same
+    /// variables and must not run variable or blocked clause elimination.
+    pub fn set_clause_exchange(
+        &mut self,
+        exchange: Arc<ClauseExchange>,
+        id: usize,
+        max_lbd: usize,
+    ) {
+        self.sharing = Some(SharingEndpoint {
+            exchange,
+            id,
+            max_lbd,
+            next_clause: 0,
+        });
+    }
+
+    /// Exports a learned clause if it is short enough
+    pub(super) fn export_learned_clause(&self, literals: &[Literal], lbd: usize) {
+        if let Some(sharing) = &self.sharing
+            && lbd <= sharing.max_lbd
+        {
+            sharing.exchange.export(sharing.id, lbd, literals);
+        }
+    }
+
+    /// Adds the clauses exported by other solvers as learned clauses on the
+    /// top level; returns `false` if the instance is found to be UNSAT
+    pub(super) fn import_shared_clauses(&mut self) -> bool {
+        debug_assert!(self.decision_level() == 0);
+        let Some(sharing) = &mut self.sharing else {
+            return true;
+        };
+        let shared_clauses = sharing
+            .exchange
+            .import(sharing.id, &mut sharing.next_clause);
+
+        // Imported clauses may contradict the pure literals
+        if !shared_clauses.is_empty() {
+            self.restore_pure_literals();
+            if !self.ok {
+                return false;
+            }
+        }
+        for shared in shared_clauses {
+            debug_assert!(
+                shared
+                    .literals
+                    .iter()
+                    .all(|literal| literal.var() < self.num_variables())
+            );
+
+            // Skip satisfied clauses and remove top-level false literals
+            if shared
+                .literals
+                .iter()
+                .any(|literal| literal.is_true(&self.variable_values))
+            {
+                continue;
+            }
+            let literals: Vec<Literal> = shared
+                .literals
+                .into_iter()
+                .filter(|literal| literal.is_unset(&self.variable_values))
+                .collect();
+            self.stats.num_imported_clauses += 1;
+
+            match literals.as_slice() {
+                [] => return false,
+                &[literal] => self.assign_literal(literal, ClauseRef::default()),
+                _ => {
+                    let clause_ref = self.attach_clause::<true>(&literals);
+                    self.learned_clauses
+                        .set_lbd(clause_ref, shared.lbd.min(literals.len()));
+                }
+            }
+        }
+        !self.propagate().valid()
+    }
+}
@@ -0,0 +1,371 @@
+use std::{
+    collections::{HashMap, HashSet},
+    io,
+};
+
+use crate::{
+    proof::{ClauseId, ProofWriter},
+    solver::{
+        Solver,
+        clause::{ClauseRef, RelocationMap},
+        literal::Literal,
+    },
+};
+
+/// Identifiers reserved for the original clauses if no proof writer announced
+/// their number, which leaves the rest for the derived clauses
+const UNANNOUNCED_ORIGINAL_CLAUSES: ClauseId = 1 << 62;
+
+/// Identifiers of the clauses of the proof being written or the UNSAT core
+/// being recorded
+pub(super) struct ProofTracer {
+    /// Receiver of the proof steps, if a proof is written
+    writer: Option<Box<dyn ProofWriter>>,
+    /// Identifier of the next original clause
+    next_original_id: ClauseId,
+    /// Number of original clauses announced; derived clauses are numbered after them
+    num_original_clauses: ClauseId,
+    /// Identifier of the next derived clause
+    next_derived_id: ClauseId,
+    /// Identifiers of the attached original and learned clauses
+    clause_ids: HashMap<ClauseRef, ClauseId>,
+    /// Identifier of the unit clause of each variable fixed at the top level,
+    /// or `0`
+    unit_ids: Vec<ClauseId>,
+    /// Hints of every derived clause, if the UNSAT core is recorded
+    antecedents: Option<HashMap<ClauseId, Vec<ClauseId>>>,
+    /// Identifier of the empty clause, which ends the proof
+    empty_clause_id: Option<ClauseId>,
+    /// First error of the writer; no steps are written after it
+    error: Option<io::Error>,
+}
+
+impl ProofTracer {
+    /// Creates a tracer numbering `num_original_clauses` original clauses
+    /// before the derived ones
+    fn new(
+        writer: Option<Box<dyn ProofWriter>>,
+        num_original_clauses: ClauseId,
+        records_core: bool,
+        num_variables: usize,
+    ) -> Self {
+        Self {
+            writer,
+            next_original_id: 1,
+            num_original_clauses,
+            next_derived_id: num_original_clauses + 1,
+            clause_ids: HashMap::new(),
+            unit_ids: vec![0; num_variables],
+            antecedents: records_core.then(HashMap::new),
+            empty_clause_id: None,
+            error: None,
+        }
+    }
+
+    /// Creates a tracer that only records the UNSAT core
+    pub(super) fn for_unsat_core() -> Self {
+        Self::new(None, UNANNOUNCED_ORIGINAL_CLAUSES, true, 0)
+    }
+
+    /// Grows the unit identifiers to `num_variables` variables
+    pub(super) fn resize(&mut self, num_variables: usize) {
+        self.unit_ids.resize(num_variables, 0);
+    }
+
+    /// Rewrites the clause references after a garbage collection
+    pub(super) fn relocate(&mut self, relocation_map: &RelocationMap) {
+        self.clause_ids = self
+            .clause_ids
+            .drain()
+            .map(|(clause_ref, id)| (relocation_map.relocate(clause_ref), id))
+            .collect();
+    }
+
+    /// Passes a step to the writer unless the proof is complete or failed
+    fn write(&mut self, step: impl FnOnce(&mut dyn ProofWriter) -> io::Result<()>) {
+        if self.empty_clause_id.is_some() || self.error.is_some() {
+            return;
+        }
+        if let Some(writer) = &mut self.writer
+            && let Err(error) = step(writer.as_mut())
+        {
+            self.error = Some(error);
+        }
+    }
+
+    /// Indices of the original clauses the empty clause is derived from, in
+    /// increasing order
+    fn core(&self) -> Vec<usize> {
+        let (Some(antecedents), Some(empty_clause_id)) = (&self.antecedents, self.empty_clause_id)
+        else {
+            return Vec::new();
+        };
+        let mut visited = HashSet::from([empty_clause_id]);
+        let mut pending = vec![empty_clause_id];
+        let mut core = Vec::new();
+        while let Some(id) = pending.pop() {
+            if id <= self.num_original_clauses {
+                core.push((id - 1) as usize);
+                continue;
+            }
+            for &hint in &antecedents[&id] {
+                if visited.insert(hint) {
+                    pending.push(hint);
+                }
+            }
+        }
+        core.sort_unstable();
+        core
+    }
+}
+
+impl Solver {
+    /// Writes a proof of unsatisfiability to `writer` while solving; the
+    /// `num_original_clauses` clauses added next are numbered from `1` in
+    /// their order and the derived clauses after them, which all proof formats
+    /// share; `finish_proof` flushes the proof
+    ///
+    /// Learned clauses are justified by the reasons that conflict analysis
+    /// resolves, failed literals and vivified clauses by the propagations
+    /// that found them, and eliminated variables by the resolved clauses.
+    /// Pure literals are not written, as no step depends on them, and the
+    /// clauses they satisfy are kept. Proofs do not cover XOR constraints,
+    /// imported clauses, clauses added after the first `solve`, or UNSAT
+    /// results that depend on assumptions.
+    ///
+    /// # Panics
+    ///
+    /// If clauses were added before
+    pub fn set_proof_writer(
+        &mut self,
+        writer: impl ProofWriter + 'static,
+        num_original_clauses: usize,
+    ) {
+        assert!(
+            self.trail.is_empty() && self.clauses.refs().next().is_none(),
+            "proof writer must be set before adding clauses"
+        );
+        self.proof = Some(ProofTracer::new(
+            Some(Box::new(writer)),
+            num_original_clauses as ClauseId,
+            self.options.unsat_core,
+            self.num_variables(),
+        ));
+    }
+
+    /// Stops writing the proof and flushes it; the UNSAT core is still recorded
+    ///
+    /// # Errors
+    ///
+    /// The first error of writing or flushing the proof
+    pub fn finish_proof(&mut self) -> io::Result<()> {
+        let Some(proof) = &mut self.proof else {
+            return Ok(());
+        };
+        let writer = proof.writer.take();
+        let error = proof.error.take();
+        if proof.antecedents.is_none() {
+            self.proof = None;
+        }
+        match (error, writer) {
+            (Some(error), _) => Err(error),
+            (None, Some(mut writer)) => writer.flush(),
+            (None, None) => Ok(()),
+        }
+    }
+
+    /// Indices of original clauses, in the order of their addition, that are
+    /// unsatisfiable together; empty unless `SolverOptions::unsat_core` is set
+    /// and the instance was found UNSAT without assumptions
+    ///
+    /// An index is the zero-based position of the clause among all added
+    /// clauses, including those added incrementally and those dropped on
+    /// addition such as tautologies; unlike a `ClauseRef`, it stays valid
+    /// while garbage collection moves and preprocessing rewrites the clauses.
+    ///
+    /// The core consists of the clauses that the derivation of the empty
+    /// clause resolves, including those of eliminated variables; it is not
+    /// minimal. Cores do not cover XOR constraints or imported clauses.
+    #[must_use]
+    pub fn unsat_core(&self) -> Vec<usize> {
+        self.proof.as_ref().map_or_else(Vec::new, ProofTracer::core)
+    }
+
+    /// Identifier of the next original clause, if a proof is written or the
+    /// UNSAT core recorded
+    pub(super) fn trace_original_clause(&mut self) -> Option<ClauseId> {
+        let proof = self.proof.as_mut()?;
+        assert!(
+            proof.next_original_id <= proof.num_original_clauses,
+            "more original clauses than announced to the proof writer"
+        );
+        proof.next_original_id += 1;
+        Some(proof.next_original_id - 1)
+    }
+
+    /// Writes a derived clause implied by the clauses `hints` in order;
+    /// returns its identifier
+    pub(super) fn trace_clause(
+        &mut self,
+        literals: &[Literal],
+        hints: &[ClauseId],
+    ) -> Option<ClauseId> {
+        let proof = self.proof.as_mut()?;
+        let id = proof.next_derived_id;
+        proof.next_derived_id += 1;
+        proof.write(|writer| writer.add(id, literals, hints));
+        if let Some(antecedents) = &mut proof.antecedents {
+            antecedents.insert(id, hints.to_vec());
+        }
+        if literals.is_empty() && proof.empty_clause_id.is_none() {
+            proof.empty_clause_id = Some(id);
+        }
+        Some(id)
+    }
+
+    /// Writes a clause derived by resolving the given attached clauses in order
+    pub(super) fn trace_resolvent(
+        &mut self,
+        literals: &[Literal],
+        antecedents: &[ClauseRef],
+    ) -> Option<ClauseId> {
+        let proof = self.proof.as_ref()?;
+        let hints: Vec<ClauseId> = antecedents
+            .iter()
+            .map(|clause_ref| proof.clause_ids[clause_ref])
+            .collect();
+        self.trace_clause(literals, &hints)
+    }
+
+    /// Writes `derived`, whose negation falsifies the clause `conflict` through
+    /// the reasons on the trail and the top-level units; the reasons are
+    /// hinted in trail order, as conflict analysis resolves them in reverse
+    pub(super) fn trace_derivation(
+        &mut self,
+        derived: &[Literal],
+        conflict: &[Literal],
+        conflict_id: ClauseId,
+    ) -> Option<ClauseId> {
+        let proof = self.proof.as_ref()?;
+        let mut is_derived = vec![false; self.num_variables()];
+        for literal in derived {
+            is_derived[literal.var()] = true;
+        }
+
+        // Top-level variables are hinted by their units, others by their reasons
+        let mut is_needed = vec![false; self.num_variables()];
+        let mut unit_hints = Vec::new();
+        let mut need = |literal: Literal, is_needed: &mut [bool]| {
+            let var = literal.var();
+            if is_derived[var] || is_needed[var] {
+                return;
+            }
+            is_needed[var] = true;
+            if self.variable_metadata[var].decision_level == 0 {
+                debug_assert!(proof.unit_ids[var] != 0);
+                unit_hints.push(proof.unit_ids[var]);
+            }
+        };
+        for &literal in conflict {
+            need(literal, &mut is_needed);
+        }
+        let mut reason_hints = Vec::new();
+        let top_level_end = self
+            .trail_separators
+            .first()
+            .copied()
+            .unwrap_or(self.trail.len());
+        for idx in (top_level_end..self.trail.len()).rev() {
+            let var = self.trail[idx].var();
+            if !is_needed[var] {
+                continue;
+            }
+            let reason = self.variable_metadata[var].reason_clause_idx;
+            debug_assert!(reason.valid());
+            reason_hints.push(proof.clause_ids[&reason]);
+            for &literal in self.clause_at(reason) {
+                if literal.var() != var {
+                    need(literal, &mut is_needed);
+                }
+            }
+        }
+
+        let mut hints = unit_hints;
+        hints.extend(reason_hints.into_iter().rev());
+        hints.push(conflict_id);
+        self.trace_clause(derived, &hints)
+    }
+
+    /// Writes `derived`, whose negation falsifies the attached clause `conflict`
+    pub(super) fn trace_conflict(
+        &mut self,
+        derived: &[Literal],
+        conflict: ClauseRef,
+    ) -> Option<ClauseId> {
+        let conflict_id = self.proof.as_ref()?.clause_ids[&conflict];
+        let conflict_literals = self.clause_at(conflict).to_vec();
+        self.trace_derivation(derived, &conflict_literals, conflict_id)
+    }
+
+    /// Writes the unit clause of `literal`, which `reason` implies on the top level
+    pub(super) fn trace_top_level_unit(&mut self, literal: Literal, reason: ClauseRef) {
+        let id = self.trace_conflict(&[literal], reason);
+        self.trace_unit(literal, id);
+    }
+
+    /// Records clause `id` as the unit clause of `literal`
+    pub(super) fn trace_unit(&mut self, literal: Literal, id: Option<ClauseId>) {
+        if let (Some(proof), Some(id)) = (&mut self.proof, id) {
+            proof.unit_ids[literal.var()] = id;
+        }
+    }
+
+    /// Records clause `id` as the attached clause `clause_ref`
+    pub(super) fn trace_attached_clause(&mut self, clause_ref: ClauseRef, id: Option<ClauseId>) {
+        if let (Some(proof), Some(id)) = (&mut self.proof, id) {
+            proof.clause_ids.insert(clause_ref, id);
+        }
+    }
+
+    /// Forgets the identifier of `clause_ref` without writing its deletion,
+    /// so that it can be hinted until a replacement is written; returns it
+    pub(super) fn untrace_clause(&mut self, clause_ref: ClauseRef) -> Option<ClauseId> {
+        self.proof.as_mut()?.clause_ids.remove(&clause_ref)
+    }
+
+    /// Writes the deletion of clause `id`
+    pub(super) fn trace_deletion(&mut self, id: Option<ClauseId>, literals: &[Literal]) {
+        if let (Some(proof), Some(id)) = (&mut self.proof, id) {
+            proof.write(|writer| writer.delete(id, literals));
+        }
+    }
+
+    /// Writes the deletion of the attached clause `clause_ref`
+    pub(super) fn trace_removal(&mut self, clause_ref: ClauseRef) {
+        let id = self.untrace_clause(clause_ref);
+        if id.is_some() {
+            let literals = self.clause_at(clause_ref).to_vec();
+            self.trace_deletion(id, &literals);
+        }
+    }
+
+    /// Replaces the identifier of `clause_ref`, whose literals were `old_literals`
+    /// before removing literals false on the top level
+    pub(super) fn trace_trimmed_clause(&mut self, clause_ref: ClauseRef, old_literals: &[Literal]) {
+        let Some(old_id) = self.untrace_clause(clause_ref) else {
+            return;
+        };
+        let literals = self.clause_at(clause_ref).to_vec();
+        let id = self.trace_derivation(&literals, old_literals, old_id);
+        self.trace_deletion(Some(old_id), old_literals);
+        self.trace_attached_clause(clause_ref, id);
+    }
+
+    /// Writes the empty clause if the instance is refuted on the top level by
+    /// the falsified clause `conflict`
+    pub(super) fn trace_refutation(&mut self, conflict: ClauseRef) {
+        if self.decision_level() == 0 {
+            self.trace_conflict(&[], conflict);
+        }
+    }
+}
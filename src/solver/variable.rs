@@ -1,11 +1,14 @@
+use std::ops::Not;
+
 /// Variable type
 pub type Variable = usize;
 
 /// Variable value type
-#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
 pub enum VariableValue {
     False = 0,
     True = 1,
+    #[default]
     Unset = 2,
 }
 
@@ -18,29 +21,51 @@ impl VariableValue {
 
     /// Whether is false
     #[must_use]
-    pub fn is_false(self) -> bool {
-        self == VariableValue::False
+    pub const fn is_false(self) -> bool {
+        matches!(self, Self::False)
     }
     /// Whether is true
     #[must_use]
-    pub fn is_true(self) -> bool {
-        self == VariableValue::True
+    pub const fn is_true(self) -> bool {
+        matches!(self, Self::True)
     }
     /// Whether is unset
     #[must_use]
-    pub fn is_unset(self) -> bool {
-        self == VariableValue::Unset
+    pub const fn is_unset(self) -> bool {
+        matches!(self, Self::Unset)
+    }
+
+    /// Opposite value; unset stays unset
+    #[must_use]
+    pub const fn negate(self) -> Self {
+        match self {
+            Self::False => Self::True,
+            Self::True => Self::False,
+            Self::Unset => Self::Unset,
+        }
+    }
+
+    /// Bool of a set value, or `None` if unset
+    #[must_use]
+    pub const fn as_bool(self) -> Option<bool> {
+        match self {
+            Self::False => Some(false),
+            Self::True => Some(true),
+            Self::Unset => None,
+        }
     }
 }
 
-impl Default for VariableValue {
-    fn default() -> Self {
-        Self::Unset
+impl Not for VariableValue {
+    type Output = Self;
+
+    fn not(self) -> Self {
+        self.negate()
     }
 }
 
 impl PartialEq<bool> for VariableValue {
     fn eq(&self, other: &bool) -> bool {
-        *self == VariableValue::from_bool(*other)
+        self.as_bool() == Some(*other)
     }
 }
@@ -0,0 +1,318 @@
+use crate::{
+    parsing::ClauseReceiver,
+    solver::{Solver, clause::ClauseRef, literal::Literal, variable::Variable},
+};
+
+/// XOR constraint `vars[0] ^ vars[1] ^ ... == rhs`; `vars` is sorted and free
+/// of duplicates
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub(super) struct XorRow {
+    /// Variables of the constraint
+    vars: Vec<Variable>,
+    /// Required parity of the variables
+    rhs: bool,
+    /// Two variables of `vars` whose assignment triggers propagation; unset
+    /// ones while the row has any
+    watched: [Variable; 2],
+}
+
+/// Effect of an assigned watched variable on its XOR row
+enum XorWatch {
+    /// Another unset variable is watched instead
+    Moved,
+    /// The row stays watched by the variable
+    Kept,
+    /// The last unset variable of the row was assigned
+    Implied,
+    /// The row is violated by the reason clause
+    Conflict(ClauseRef),
+}
+
+impl XorRow {
+    /// Normalizes an XOR of literals; negative literals flip `rhs`
+    /// and pairs of the same variable cancel out
+    fn from_literals(literals: &[Literal], rhs: bool) -> Self {
+        let mut vars: Vec<Variable> = literals.iter().map(Literal::var).collect();
+        let rhs = literals
+            .iter()
+            .fold(rhs, |rhs, literal| rhs ^ !literal.polarity());
+        vars.sort_unstable();
+        let mut row = Self {
+            vars: Vec::new(),
+            rhs,
+            watched: [0; 2],
+        };
+        for var in vars {
+            if row.vars.last() == Some(&var) {
+                row.vars.pop();
+            } else {
+                row.vars.push(var);
+            }
+        }
+        row
+    }
+
+    /// Adds `other` to the row; variables occurring in both cancel out
+    fn add(&mut self, other: &Self) {
+        let mut vars = Vec::with_capacity(self.vars.len() + other.vars.len());
+        let (mut i, mut j) = (0, 0);
+        while i < self.vars.len() && j < other.vars.len() {
+            if self.vars[i] < other.vars[j] {
+                vars.push(self.vars[i]);
+                i += 1;
+            } else if self.vars[i] > other.vars[j] {
+                vars.push(other.vars[j]);
+                j += 1;
+            } else {
+                i += 1;
+                j += 1;
+            }
+        }
+        vars.extend_from_slice(&self.vars[i..]);
+        vars.extend_from_slice(&other.vars[j..]);
+        self.vars = vars;
+        self.rhs ^= other.rhs;
+    }
+
+    /// Variables of the constraint
+    pub(super) fn vars(&self) -> &[Variable] {
+        &self.vars
+    }
+
+    /// Heap memory reserved by the row in bytes
+    pub(super) const fn heap_size_bytes(&self) -> usize {
+        self.vars.capacity() * size_of::<Variable>()
+    }
+}
+
+impl Solver {
+    /// Adds the XOR constraint that an odd (`rhs == true`) or even
+    /// (`rhs == false`) number of `lits` is true; the variable space grows to
+    /// the largest variable as needed; returns `false` if the instance is
+    /// found to be UNSAT
+    ///
+    /// XOR constraints are reduced by Gaussian elimination before search and
+    /// propagated alongside the clauses. Their variables are frozen.
+    ///
+    /// # Panics
+    ///
+    /// If a literal exceeds `SolverOptions::max_variables` or a proof is
+    /// written or the UNSAT core recorded, as neither covers XOR constraints
+    pub fn add_xor(&mut self, lits: &[Literal], rhs: bool) -> bool {
+        assert!(
+            self.proof.is_none(),
+            "proofs and UNSAT cores do not cover XOR constraints"
+        );
+        // Constraints are added on the top level with a retracted model
+        self.revert_trail(0);
+        self.retract_extended_model();
+
+        if let Some(max_var) = lits.iter().map(Literal::var).max()
+            && max_var >= self.num_variables()
+        {
+            assert!(
+                self.create_variables(max_var + 1),
+                "variable {max_var} exceeds the maximum number of variables"
+            );
+        }
+        for literal in lits {
+            self.frozen[literal.var()] = true;
+            self.occurs[literal.var()] = true;
+        }
+        self.add_xor_row(XorRow::from_literals(lits, rhs))
+    }
+
+    /// Stores a normalized XOR row; rows over fewer than two variables are
+    /// added as clauses instead, so that every row implies clauses of at
+    /// least two literals
+    fn add_xor_row(&mut self, row: XorRow) -> bool {
+        match row.vars.as_slice() {
+            [] if row.rhs => {
+                self.ok = false;
+                false
+            }
+            [] => self.ok,
+            &[var] => self.add_clause(&[Literal::from_var_with_polarity(var, row.rhs)]),
+            _ => {
+                self.xor_rows.push(row);
+                self.watch_xor_row(self.xor_rows.len() - 1);
+                self.ok
+            }
+        }
+    }
+
+    /// Watches two variables of the XOR row `row_idx`, unset ones if
+    /// possible; the next propagation checks the row against the whole trail
+    fn watch_xor_row(&mut self, row_idx: usize) {
+        let values = &self.variable_values;
+        let row = &mut self.xor_rows[row_idx];
+        let (unset, assigned): (Vec<Variable>, Vec<Variable>) =
+            row.vars.iter().partition(|&&var| values[var].is_unset());
+        let mut candidates = unset.into_iter().chain(assigned);
+        for watched in &mut row.watched {
+            *watched = candidates.next().expect("rows have two variables");
+            self.xor_watches[*watched].push(row_idx);
+        }
+        self.rewind_xor_propagation(0);
+    }
+
+    /// Propagates the trail through the XOR rows again from position `len`
+    /// on, such as after the trail was shortened to `len` literals
+    pub(super) fn rewind_xor_propagation(&mut self, len: usize) {
+        if self.xor_propagation_head >= len {
+            self.xor_propagation_head = len;
+            self.xor_watch_position = 0;
+        }
+    }
+
+    /// Substitutes top-level assignments into the XOR rows and reduces them by
+    /// Gauss-Jordan elimination until no further unit rows arise; returns
+    /// `false` if the instance is found to be UNSAT
+    pub(super) fn simplify_xors(&mut self) -> bool {
+        debug_assert!(self.decision_level() == 0);
+        loop {
+            if self.propagate().valid() {
+                return false;
+            }
+
+            // Remove assigned variables
+            let mut rows = std::mem::take(&mut self.xor_rows);
+            for watches in &mut self.xor_watches {
+                watches.clear();
+            }
+            for row in &mut rows {
+                let values = &self.variable_values;
+                row.rhs = row
+                    .vars
+                    .iter()
+                    .fold(row.rhs, |rhs, &var| rhs ^ values[var].is_true());
+                row.vars.retain(|&var| values[var].is_unset());
+            }
+
+            // Eliminate the first variable of each row from all other rows
+            for i in 0..rows.len() {
+                let Some(&pivot) = rows[i].vars.first() else {
+                    continue;
+                };
+                let pivot_row = rows[i].clone();
+                for (j, row) in rows.iter_mut().enumerate() {
+                    if j != i && row.vars.binary_search(&pivot).is_ok() {
+                        row.add(&pivot_row);
+                    }
+                }
+            }
+
+            // Re-add the reduced rows; unit rows become top-level facts
+            let num_trail_literals = self.trail.len();
+            for row in rows {
+                if !self.add_xor_row(row) {
+                    return false;
+                }
+            }
+            if self.trail.len() == num_trail_literals {
+                return true;
+            }
+        }
+    }
+
+    /// Propagates the trail literals from `xor_propagation_head` on through
+    /// the XOR rows watching their variables; returns the first violated row
+    /// as a conflict
+    ///
+    /// Reasons and conflicts are added as learned clauses. The propagation
+    /// returns after each implication, so that the clauses are propagated
+    /// first; an identical learned clause would thus have implied the literal
+    /// before, and each reason clause is new.
+    pub(super) fn propagate_xors(&mut self) -> ClauseRef {
+        while self.xor_propagation_head < self.trail.len() {
+            let var = self.trail[self.xor_propagation_head].var();
+
+            // Watches move only to unset variables, so not onto `var`
+            let mut watches = std::mem::take(&mut self.xor_watches[var]);
+            let mut idx = self.xor_watch_position;
+            let mut effect = XorWatch::Kept;
+            while idx < watches.len() {
+                effect = self.update_xor_watch(watches[idx], var);
+                match effect {
+                    XorWatch::Moved => {
+                        watches.swap_remove(idx);
+                    }
+                    XorWatch::Kept => idx += 1,
+                    XorWatch::Implied | XorWatch::Conflict(_) => {
+                        idx += 1;
+                        break;
+                    }
+                }
+            }
+            self.xor_watches[var] = watches;
+            match effect {
+                XorWatch::Implied => {
+                    self.xor_watch_position = idx;
+                    return ClauseRef::default();
+                }
+                XorWatch::Conflict(conflict) => {
+                    self.xor_watch_position = idx;
+                    return conflict;
+                }
+                XorWatch::Moved | XorWatch::Kept => {
+                    self.xor_watch_position = 0;
+                    self.xor_propagation_head += 1;
+                }
+            }
+        }
+        ClauseRef::default()
+    }
+
+    /// Handles the assignment of `var`, watched by the XOR row `row_idx`;
+    /// watches another unset variable instead if there is one, and otherwise
+    /// assigns the last unset variable or reports a violated row
+    fn update_xor_watch(&mut self, row_idx: usize, var: Variable) -> XorWatch {
+        let row = &self.xor_rows[row_idx];
+        let slot = usize::from(row.watched[0] != var);
+        debug_assert_eq!(row.watched[slot], var);
+        let other = row.watched[1 - slot];
+        if let Some(&replacement) = row.vars.iter().find(|&&candidate| {
+            candidate != other && candidate != var && self.variable_values[candidate].is_unset()
+        }) {
+            self.xor_rows[row_idx].watched[slot] = replacement;
+            self.xor_watches[replacement].push(row_idx);
+            return XorWatch::Moved;
+        }
+
+        // All variables but `other` are assigned
+        let parity = row.vars.iter().fold(row.rhs, |parity, &var| {
+            parity ^ self.variable_values[var].is_true()
+        });
+        let unset_var = self.variable_values[other].is_unset().then_some(other);
+        if unset_var.is_none() && !parity {
+            return XorWatch::Kept;
+        }
+
+        // Clause of the false literals, headed by the implied literal;
+        // the second literal is the one assigned last
+        let implied = unset_var.map(|var| Literal::from_var_with_polarity(var, parity));
+        let mut clause: Vec<Literal> = row
+            .vars
+            .iter()
+            .filter(|&&var| Some(var) != unset_var)
+            .map(|&var| Literal::from_var_with_polarity(var, self.variable_values[var].is_false()))
+            .collect();
+        clause.sort_by_key(|literal| {
+            std::cmp::Reverse(self.variable_metadata[literal.var()].decision_level)
+        });
+        if let Some(implied) = implied {
+            clause.insert(0, implied);
+        }
+        let clause_ref = self.attach_clause::<true>(&clause);
+        let lbd = self.literal_block_distance(&clause);
+        self.learned_clauses.set_lbd(clause_ref, lbd);
+
+        match implied {
+            Some(implied) => {
+                self.assign_literal(implied, clause_ref);
+                XorWatch::Implied
+            }
+            None => XorWatch::Conflict(clause_ref),
+        }
+    }
+}
@@ -0,0 +1,328 @@
+use std::{fs, process::Command};
+
+use nanosat_rs::{
+    ModelFormat, solve_cnf_instance_to,
+    solver::{Solver, SolverExitCode, VerbosityLevel},
+    write_result,
+};
+
+fn run_cli(args: &[&str]) -> String {
+    let exe = env!("CARGO_BIN_EXE_nanosat-rs");
+    let output = Command::new(exe)
+        .args(args)
+        .output()
+        .expect("failed to run main binary");
+    assert_eq!(output.status.code(), Some(10));
+    String::from_utf8_lossy(&output.stdout).into_owned()
+}
+
+#[test]
+fn test_cli_verbosity_levels() {
+    let file_name = "res/success/medium_sat.cnf";
+
+    let quiet = run_cli(&["-q", "-q", file_name]);
+    assert!(quiet.starts_with("\nSAT "));
+    assert!(!quiet.contains("Summary"));
+
+    let summary = run_cli(&["-q", file_name]);
+    assert!(summary.contains("Problem Statistics"));
+    assert!(summary.contains("binary:                      306"));
+    assert!(summary.contains("Summary"));
+    assert!(!summary.contains("Search Statistics"));
+
+    // Search progress is printed by default
+    let verbose = run_cli(&[file_name]);
+    assert!(verbose.contains("Search Statistics"));
+    assert!(verbose.contains("Summary"));
+
+    // `-v` raises the level again
+    assert!(run_cli(&["-q", "-q", "-v", file_name]).contains("Summary"));
+    assert!(run_cli(&["-q", "-v", file_name]).contains("Search Statistics"));
+}
+
+#[test]
+fn test_cli_seed() {
+    let file_name = "res/success/medium_sat.cnf";
+    assert!(run_cli(&[file_name]).contains("Random seed:                    42"));
+    let seeded = run_cli(&["--seed", "7", file_name]);
+    assert!(seeded.contains("Random seed:                     7"));
+    assert!(run_cli(&["--seed", "7", "-q", "-q", file_name]).starts_with("\nSAT "));
+    assert!(run_cli(&["--competition", "--seed", "7", file_name]).starts_with("s SATISFIABLE\n"));
+
+    // The same seed reproduces the same run
+    let model = |output: &str| output.lines().last().map(str::to_owned);
+    assert_eq!(model(&seeded), model(&run_cli(&["--seed", "7", file_name])));
+
+    // `--seed` is short for `--random-seed`
+    assert_eq!(
+        model(&seeded),
+        model(&run_cli(&["--random-seed", "7", file_name]))
+    );
+
+    let exe = env!("CARGO_BIN_EXE_nanosat-rs");
+    let output = Command::new(exe)
+        .args(["--seed", "-1", file_name])
+        .output()
+        .expect("failed to run main binary");
+    assert_eq!(output.status.code(), Some(1));
+    assert!(String::from_utf8_lossy(&output.stderr).starts_with("Could not parse seed \"-1\""));
+}
+
+#[test]
+fn test_cli_model_format() {
+    let file_name = "res/success/small_sat.cnf";
+    let inline = run_cli(&["-q", "-q", file_name]);
+    assert_eq!(
+        run_cli(&["--model-format", "inline", "-q", "-q", file_name]),
+        inline
+    );
+    let literals: Vec<&str> = inline.trim().split(' ').skip(1).collect();
+    assert_eq!(literals.len(), 3);
+
+    let lines = run_cli(&["--model-format", "lines", "-q", "-q", file_name]);
+    let expected: Vec<String> = literals
+        .iter()
+        .chain(&["0"])
+        .map(|literal| format!("v {literal}"))
+        .collect();
+    assert_eq!(lines, format!("\nSAT\n{}\n", expected.join("\n")));
+
+    let bits = run_cli(&["--model-format", "bits", "-q", "-q", file_name]);
+    let expected: String = literals
+        .iter()
+        .map(|literal| if literal.starts_with('-') { '0' } else { '1' })
+        .collect();
+    assert_eq!(bits, format!("\nSAT\n{expected}\n"));
+
+    let mut out = Vec::new();
+    let mut solver = Solver::new(VerbosityLevel::OnlyResult);
+    assert!(solver.add_clause_ints(&[-1]));
+    assert!(solver.add_clause_ints(&[2]));
+    let exit_code = solver.solve();
+    write_result(&mut out, &solver, exit_code, ModelFormat::BitVector).expect("writes to memory");
+    assert_eq!(String::from_utf8_lossy(&out), "\nSAT\n01\n");
+
+    let exe = env!("CARGO_BIN_EXE_nanosat-rs");
+    let output = Command::new(exe)
+        .args(["--model-format", "binary", file_name])
+        .output()
+        .expect("failed to run main binary");
+    assert_eq!(output.status.code(), Some(1));
+    assert!(String::from_utf8_lossy(&output.stderr).starts_with("Unknown model format \"binary\""));
+}
+
+#[test]
+fn test_cli_maxsat() {
+    assert_eq!(
+        run_cli(&["--maxsat", "res/success/small.wcnf"]),
+        "\nOPTIMUM 3 1 -2\n"
+    );
+}
+
+#[test]
+fn test_cli_stats_only() {
+    let exe = env!("CARGO_BIN_EXE_nanosat-rs");
+    let output = Command::new(exe)
+        .args(["--stats-only", "res/success/medium_sat.cnf.xz"])
+        .output()
+        .expect("failed to run main binary");
+    assert_eq!(output.status.code(), Some(0));
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("Number of variables:           403"));
+    assert!(stdout.contains("Number of clauses:            2029"));
+    assert!(!stdout.contains("SAT"));
+}
+
+#[test]
+fn test_cli_simplify_only() {
+    let exe = env!("CARGO_BIN_EXE_nanosat-rs");
+    let output = Command::new(exe)
+        .args(["--simplify-only", "res/success/small_sat.cnf"])
+        .output()
+        .expect("failed to run main binary");
+    assert_eq!(output.status.code(), Some(0));
+    // Unit propagation satisfies all clauses
+    assert_eq!(String::from_utf8_lossy(&output.stdout), "p cnf 3 0\n");
+}
+
+/// Runs `nanosat --verify`; returns the exit code and stderr
+fn run_verify(file_name: &str, expected: &str) -> (Option<i32>, String) {
+    let exe = env!("CARGO_BIN_EXE_nanosat-rs");
+    let output = Command::new(exe)
+        .args(["--verify", file_name, expected])
+        .output()
+        .expect("failed to run main binary");
+    (
+        output.status.code(),
+        String::from_utf8_lossy(&output.stderr).into_owned(),
+    )
+}
+
+#[test]
+fn test_cli_verify_fixtures() {
+    for (file_name, expected) in [
+        ("res/success/big_sat_instance.cnf.xz", "SAT"),
+        ("res/success/empty_clause.cnf", "UNSAT"),
+        ("res/success/inline_comments.cnf", "SAT"),
+        ("res/success/medium_sat.cnf", "SAT"),
+        ("res/success/medium_sat.cnf.gz", "SAT"),
+        ("res/success/multi_line_clause.cnf", "SAT"),
+        ("res/success/no_clauses.cnf", "SAT"),
+        ("res/success/pigeonhole_4_3.cnf", "UNSAT"),
+        ("res/success/small_sat.cnf", "res/success/small_sat.model"),
+        ("res/success/whitespace_header.cnf", "SAT"),
+    ] {
+        assert_eq!(
+            run_verify(file_name, expected),
+            (Some(0), String::new()),
+            "{file_name}"
+        );
+    }
+}
+
+#[test]
+fn test_cli_verify_mismatch() {
+    assert_eq!(
+        run_verify("res/success/medium_sat.cnf", "UNSAT"),
+        (
+            Some(1),
+            String::from("Expected Unsat, but the solver returned Sat.\n")
+        )
+    );
+    assert_eq!(
+        run_verify(
+            "res/success/small_sat.cnf",
+            "res/fail/small_sat_violated.model"
+        ),
+        (
+            Some(1),
+            String::from(
+                "Model in \"res/fail/small_sat_violated.model\" does not satisfy all clauses.\n"
+            )
+        )
+    );
+
+    // Literals of variables beyond the instance are rejected instead of read
+    for (model_file, literal) in [
+        ("res/fail/small_sat_out_of_range.model", "999999999"),
+        ("res/fail/small_sat_min_literal.model", "-2147483648"),
+    ] {
+        assert_eq!(
+            run_verify("res/success/small_sat.cnf", model_file),
+            (
+                Some(1),
+                format!(
+                    "Literal {literal} in model file {model_file:?} exceeds the 3 variables of the instance.\n"
+                )
+            )
+        );
+    }
+}
+
+#[test]
+fn test_cli_competition_output() {
+    let exe = env!("CARGO_BIN_EXE_nanosat-rs");
+    let output = Command::new(exe)
+        .args(["--competition", "res/success/medium_sat.cnf"])
+        .output()
+        .expect("failed to run main binary");
+    assert_eq!(output.status.code(), Some(10));
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.starts_with("s SATISFIABLE\nv "));
+    assert!(stdout.ends_with(" 0\n"));
+    assert!(
+        stdout
+            .lines()
+            .skip(1)
+            .all(|line| line.starts_with("v ") && line.len() <= 78)
+    );
+
+    // The model can be read back by the verification mode
+    let model_file = format!("{}/medium_sat.model", env!("CARGO_TARGET_TMPDIR"));
+    fs::write(&model_file, stdout.as_bytes()).expect("failed to write model file");
+    assert_eq!(
+        run_verify("res/success/medium_sat.cnf", &model_file),
+        (Some(0), String::new())
+    );
+
+    let output = Command::new(exe)
+        .args(["--competition", "res/success/empty_clause.cnf"])
+        .output()
+        .expect("failed to run main binary");
+    assert_eq!(output.status.code(), Some(20));
+    assert_eq!(String::from_utf8_lossy(&output.stdout), "s UNSATISFIABLE\n");
+}
+
+#[test]
+fn test_cli_assume() {
+    let exe = env!("CARGO_BIN_EXE_nanosat-rs");
+    let run_assume = |assumptions_file: &str| {
+        Command::new(exe)
+            .args(["--assume", assumptions_file, "res/success/small_sat.cnf"])
+            .output()
+            .expect("failed to run main binary")
+    };
+
+    let output = run_assume("res/success/small_sat.assumptions");
+    assert_eq!(output.status.code(), Some(10));
+    assert!(String::from_utf8_lossy(&output.stdout).ends_with("\nSAT 1 2 3\n"));
+
+    // The instance forces 3 to be true
+    let output = run_assume("res/success/small_sat_unsat.assumptions");
+    assert_eq!(output.status.code(), Some(20));
+    assert!(String::from_utf8_lossy(&output.stdout).ends_with("\nUNSAT\n"));
+
+    // Flags combine in any order
+    let output = Command::new(exe)
+        .args([
+            "-q",
+            "--assume",
+            "res/success/small_sat.assumptions",
+            "-q",
+            "--seed",
+            "7",
+            "res/success/small_sat.cnf",
+        ])
+        .output()
+        .expect("failed to run main binary");
+    assert_eq!(output.status.code(), Some(10));
+    assert_eq!(String::from_utf8_lossy(&output.stdout), "\nSAT 1 2 3\n");
+}
+
+#[test]
+fn test_cli_invalid_arguments() {
+    let exe = env!("CARGO_BIN_EXE_nanosat-rs");
+    let output = Command::new(exe)
+        .args(["-x", "res/success/medium_sat.cnf"])
+        .output()
+        .expect("failed to run main binary");
+    assert_eq!(output.status.code(), Some(1));
+    assert!(String::from_utf8_lossy(&output.stderr).starts_with("Expects `nanosat"));
+}
+
+#[test]
+fn test_solve_cnf_instance_to_writer() {
+    let mut out = Vec::new();
+    let exit_code =
+        solve_cnf_instance_to("res/success/small_sat.cnf", VerbosityLevel::All, &mut out);
+    assert_eq!(exit_code, SolverExitCode::Sat);
+    let output = String::from_utf8(out).expect("output is UTF-8");
+    assert!(output.contains("Problem Statistics"));
+    assert!(output.contains("Search Statistics"));
+    assert!(output.contains("Summary"));
+    assert!(
+        output
+            .lines()
+            .last()
+            .is_some_and(|line| line.starts_with("SAT "))
+    );
+
+    let mut out = Vec::new();
+    let exit_code = solve_cnf_instance_to(
+        "res/success/empty_clause.cnf",
+        VerbosityLevel::OnlyResult,
+        &mut out,
+    );
+    assert_eq!(exit_code, SolverExitCode::Unsat);
+    assert_eq!(out, b"\nUNSAT\n");
+}
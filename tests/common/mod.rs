@@ -1,3 +1,6 @@
+// Each test crate includes this module but uses only some of the helpers
+#![allow(dead_code)]
+
 use nanosat_rs::{parsing::ClauseReceiver, solver::literal::Literal};
 
 /// Mock for solver type
@@ -9,13 +12,28 @@ pub struct SolverMock {
 }
 
 impl ClauseReceiver for SolverMock {
-    fn create_variables(&mut self, num_variables: usize) {
+    fn create_variables(&mut self, num_variables: usize) -> bool {
         self.num_variables = num_variables;
+        true
     }
 
     fn add_clause(&mut self, literals: &[Literal]) -> bool {
         self.num_clauses += 1;
         self.clauses.push(Vec::from(literals));
-        return true;
+        true
+    }
+}
+
+/// Small deterministic pseudo-random generator for instance generation
+pub struct Lcg(pub u64);
+
+impl Lcg {
+    /// Next pseudo-random number below `bound`
+    pub fn next(&mut self, bound: usize) -> usize {
+        self.0 = self
+            .0
+            .wrapping_mul(6_364_136_223_846_793_005)
+            .wrapping_add(1_442_695_040_888_963_407);
+        usize::try_from(self.0 >> 33).unwrap() % bound
     }
 }
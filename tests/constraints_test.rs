@@ -0,0 +1,74 @@
+use nanosat_rs::{
+    constraints::{add_at_most_k, add_at_most_one},
+    parsing::ClauseReceiver,
+    solver::{Solver, SolverExitCode, VerbosityLevel, literal::Literal},
+};
+
+/// Solver over `n` variables with at most `k` of them true; variables
+/// `1..=forced` are forced to true
+fn at_most_k_solver(n: usize, k: usize, forced: usize) -> (Solver, Vec<Literal>) {
+    let mut solver = Solver::new(VerbosityLevel::OnlyResult);
+    solver.create_variables(n);
+    let lits: Vec<Literal> = (0..n)
+        .map(|var| Literal::from_var_with_polarity(var, true))
+        .collect();
+    for lit in &lits[..forced] {
+        solver.add_clause_ints(&[lit.to_dimacs()]);
+    }
+    if add_at_most_k(&mut solver, &lits, k) {
+        // Prefer true to push against the bound
+        let phases = vec![true; solver.num_variables()];
+        solver.set_initial_phases(&phases);
+    }
+    (solver, lits)
+}
+
+fn num_true(solver: &Solver, lits: &[Literal]) -> usize {
+    lits.iter()
+        .filter(|lit| lit.is_true(solver.model()))
+        .count()
+}
+
+#[test]
+fn test_at_most_k() {
+    for k in 0..=7 {
+        for forced in 0..=6 {
+            let (mut solver, lits) = at_most_k_solver(6, k, forced);
+            if forced <= k {
+                assert_eq!(solver.solve(), SolverExitCode::Sat);
+                let count = num_true(&solver, &lits);
+                assert!(forced <= count && count <= k);
+            } else {
+                assert_eq!(solver.solve(), SolverExitCode::Unsat);
+            }
+        }
+    }
+}
+
+#[test]
+fn test_at_most_one() {
+    // Pairwise and ladder encodings
+    for n in [3, 8] {
+        for forced in 0..=2 {
+            let mut solver = Solver::new(VerbosityLevel::OnlyResult);
+            solver.create_variables(n);
+            let lits: Vec<Literal> = (0..n)
+                .map(|var| Literal::from_var_with_polarity(var, true))
+                .collect();
+            for lit in &lits[..forced] {
+                solver.add_clause_ints(&[lit.to_dimacs()]);
+            }
+            add_at_most_one(&mut solver, &lits);
+            let phases = vec![true; solver.num_variables()];
+            solver.set_initial_phases(&phases);
+
+            if forced <= 1 {
+                assert_eq!(solver.solve(), SolverExitCode::Sat);
+                let count = num_true(&solver, &lits);
+                assert!(forced <= count && count <= 1);
+            } else {
+                assert_eq!(solver.solve(), SolverExitCode::Unsat);
+            }
+        }
+    }
+}
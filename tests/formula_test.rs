@@ -0,0 +1,52 @@
+use nanosat_rs::{
+    formula::FormulaBuilder,
+    solver::{SolverExitCode, VerbosityLevel, variable::VariableValue},
+};
+
+#[test]
+fn test_build_sat_formula() {
+    let mut formula = FormulaBuilder::new();
+    formula
+        .add_clause(&[1, -2])
+        .add_clause(&[2, 3])
+        .add_clause(&[-3]);
+    assert_eq!(formula.num_variables(), 3);
+    assert_eq!(formula.num_clauses(), 3);
+
+    let mut solver = formula.build(VerbosityLevel::OnlyResult);
+    assert_eq!(solver.num_variables(), 3);
+    assert_eq!(solver.solve(), SolverExitCode::Sat);
+    assert_eq!(
+        solver.model(),
+        &vec![
+            VariableValue::True,
+            VariableValue::True,
+            VariableValue::False
+        ]
+    );
+}
+
+#[test]
+fn test_build_formula_with_empty_clause() {
+    let mut formula = FormulaBuilder::new();
+    formula
+        .add_clause(&[1, 2])
+        .add_clause(&[])
+        .add_clause(&[-1]);
+    let mut solver = formula.build(VerbosityLevel::OnlyResult);
+    assert_eq!(solver.solve(), SolverExitCode::Unsat);
+}
+
+#[test]
+fn test_build_formula_with_conflicting_units() {
+    let mut formula = FormulaBuilder::new();
+    formula
+        .add_clause(&[1, 2])
+        .add_clause(&[-2])
+        .add_clause(&[-1]);
+    let mut solver = formula.build(VerbosityLevel::OnlyResult);
+    assert_eq!(solver.solve(), SolverExitCode::Unsat);
+
+    // UNSAT is remembered
+    assert_eq!(solver.solve(), SolverExitCode::Unsat);
+}
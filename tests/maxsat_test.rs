@@ -0,0 +1,84 @@
+use nanosat_rs::{
+    maxsat::MaxSatInstance,
+    solver::{literal::Literal, variable::VariableValue},
+};
+
+use crate::common::Lcg;
+
+mod common;
+
+fn lits(dimacs: &[i32]) -> Vec<Literal> {
+    dimacs.iter().copied().map(Literal::from_dimacs).collect()
+}
+
+#[test]
+fn test_maxsat_small() {
+    // Hard: (1 or 2); soft: -1 (3), -2 (2), (1) (4)
+    let mut instance = MaxSatInstance::new();
+    instance
+        .add_hard_clause(&lits(&[1, 2]))
+        .add_soft_clause(3, &lits(&[-1]))
+        .add_soft_clause(2, &lits(&[-2]))
+        .add_soft_clause(4, &lits(&[1]));
+    let solution = instance.solve().unwrap();
+    assert_eq!(solution.cost, 3);
+    assert_eq!(
+        solution.model,
+        vec![VariableValue::True, VariableValue::False]
+    );
+    assert_eq!(instance.cost(&solution.model), 3);
+}
+
+#[test]
+fn test_maxsat_unsat_hard_clauses() {
+    let mut instance = MaxSatInstance::new();
+    instance
+        .add_hard_clause(&lits(&[1]))
+        .add_hard_clause(&lits(&[-1]))
+        .add_soft_clause(1, &lits(&[1]));
+    assert_eq!(instance.solve(), None);
+}
+
+#[test]
+fn test_maxsat_against_brute_force() {
+    const NUM_VARS: usize = 8;
+    let mut rng = Lcg(11);
+    let random_clause = |rng: &mut Lcg| -> Vec<Literal> {
+        (0..1 + rng.next(3))
+            .map(|_| Literal::from_var_with_polarity(rng.next(NUM_VARS), rng.next(2) == 0))
+            .collect()
+    };
+    for round in 0..100 {
+        let mut instance = MaxSatInstance::new();
+        for _ in 0..round % 12 {
+            instance.add_hard_clause(&random_clause(&mut rng));
+        }
+        for _ in 0..5 + round % 20 {
+            let weight = 1 + rng.next(10) as u64;
+            instance.add_soft_clause(weight, &random_clause(&mut rng));
+        }
+
+        // Brute-force optimum over all assignments satisfying the hard clauses
+        let num_vars = instance.num_variables();
+        let optimum = (0..1_usize << num_vars)
+            .map(|bits| -> Vec<VariableValue> {
+                (0..num_vars)
+                    .map(|var| VariableValue::from_bool(bits >> var & 1 == 1))
+                    .collect()
+            })
+            .filter(|model| {
+                instance
+                    .hard_clauses()
+                    .iter()
+                    .all(|clause| clause.iter().any(|literal| literal.is_true(model)))
+            })
+            .map(|model| instance.cost(&model))
+            .min();
+
+        let solution = instance.solve();
+        assert_eq!(solution.as_ref().map(|solution| solution.cost), optimum);
+        if let Some(solution) = solution {
+            assert_eq!(instance.cost(&solution.model), solution.cost);
+        }
+    }
+}
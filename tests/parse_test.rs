@@ -1,6 +1,13 @@
 use std::process::Command;
 
-use nanosat_rs::{parsing::parse_cnf, solver::literal::Literal};
+use nanosat_rs::{
+    maxsat::MaxSatInstance,
+    parsing::{
+        ClauseStats, ParseMode, TeeReceiver, parse_assumptions, parse_cnf, parse_cnf_with_mode,
+        parse_wcnf,
+    },
+    solver::literal::Literal,
+};
 
 use crate::common::SolverMock;
 
@@ -34,11 +41,135 @@ fn test_parse_cnf_xz() {
     check_medium_cnf("cnf.xz");
 }
 
+#[test]
+fn test_clause_stats() {
+    let mut stats = ClauseStats::default();
+    parse_cnf("res/success/small_sat.cnf", &mut stats);
+    assert_eq!(
+        stats,
+        ClauseStats {
+            num_variables: 3,
+            num_clauses: 6,
+            num_literals: 11,
+            max_variable: 3,
+        }
+    );
+}
+
+#[test]
+fn test_tee_receiver() {
+    let mut solver = SolverMock::default();
+    let mut stats = ClauseStats::default();
+    parse_cnf(
+        "res/success/medium_sat.cnf",
+        &mut TeeReceiver::new(&mut solver, &mut stats),
+    );
+    assert_eq!(solver.num_variables, 403);
+    assert_eq!(solver.clauses.len(), 2029);
+    assert_eq!(stats.num_variables, 403);
+    assert_eq!(stats.num_clauses, 2029);
+    assert_eq!(
+        stats.num_literals,
+        solver.clauses.iter().map(Vec::len).sum::<usize>()
+    );
+}
+
 #[test]
 fn test_parse_cnf_gz() {
     check_medium_cnf("cnf.gz");
 }
 
+#[test]
+fn test_parse_cnf_bz2() {
+    check_medium_cnf("cnf.bz2");
+}
+
+#[test]
+fn test_parse_cnf_compression_by_magic_bytes() {
+    // gzip content with plain extension, xz content with gzip extension
+    check_medium_cnf("gz.cnf");
+    check_medium_cnf("xz.gz");
+}
+
+#[test]
+fn test_parse_cnf_multi_line_clause() {
+    let mut solver = SolverMock::default();
+    parse_cnf("res/success/multi_line_clause.cnf", &mut solver);
+    assert_eq!(solver.num_variables, 3);
+    let lit = Literal::from_dimacs;
+    assert_eq!(
+        solver.clauses,
+        vec![
+            vec![lit(1), lit(-2)],
+            vec![lit(2), lit(3)],
+            vec![lit(-1), lit(-3)],
+            vec![lit(3)],
+        ]
+    );
+}
+
+#[test]
+fn test_parse_cnf_inline_comments() {
+    let mut solver = SolverMock::default();
+    parse_cnf("res/success/inline_comments.cnf", &mut solver);
+    let lit = Literal::from_dimacs;
+    assert_eq!(
+        solver.clauses,
+        vec![
+            vec![lit(1), lit(-2)],
+            vec![lit(2), lit(3)],
+            vec![lit(-1), lit(-3)],
+        ]
+    );
+}
+
+#[test]
+fn test_parse_cnf_whitespace_header() {
+    let mut solver = SolverMock::default();
+    parse_cnf("res/success/whitespace_header.cnf", &mut solver);
+    assert_eq!(solver.num_variables, 3);
+    assert_eq!(solver.num_clauses, 2);
+}
+
+#[test]
+fn test_parse_cnf_crlf() {
+    let mut solver = SolverMock::default();
+    parse_cnf("res/success/crlf.cnf", &mut solver);
+    assert_eq!(solver.num_variables, 3);
+    let lit = Literal::from_dimacs;
+    assert_eq!(
+        solver.clauses,
+        vec![
+            vec![lit(1), lit(-2)],
+            vec![lit(2), lit(3)],
+            vec![lit(-1), lit(-3)],
+        ]
+    );
+}
+
+#[test]
+fn test_parse_cnf_lenient_counts() {
+    // One clause less than declared
+    let mut solver = SolverMock::default();
+    parse_cnf_with_mode(
+        "res/fail/missing_clause.cnf",
+        &mut solver,
+        ParseMode::Lenient,
+    );
+    assert_eq!(solver.num_variables, 403);
+    assert_eq!(solver.num_clauses, 2028);
+
+    // Variable 5000 exceeds the declared 403 variables
+    let mut solver = SolverMock::default();
+    parse_cnf_with_mode(
+        "res/fail/too_many_vars.cnf",
+        &mut solver,
+        ParseMode::Lenient,
+    );
+    assert_eq!(solver.num_variables, 5000);
+    assert_eq!(solver.num_clauses, 2029);
+}
+
 #[test]
 fn test_parse_cnf_file_does_not_exist() {
     let exe = env!("CARGO_BIN_EXE_nanosat-rs");
@@ -77,12 +208,69 @@ fn test_parse_cnf_missing_clause() {
     );
 }
 
+#[test]
+fn test_parse_cnf_invalid_header() {
+    check_parsing_fails(
+        "res/fail/invalid_header.cnf",
+        1,
+        "CNF header invalid (res/fail/invalid_header.cnf:1)",
+    );
+}
+
+#[test]
+fn test_parse_cnf_duplicate_header() {
+    check_parsing_fails(
+        "res/fail/duplicate_header.cnf",
+        1,
+        "Duplicate CNF header (res/fail/duplicate_header.cnf:4)",
+    );
+}
+
+#[test]
+fn test_parse_cnf_invalid_header_counts() {
+    check_parsing_fails(
+        "res/fail/non_numeric_vars.cnf",
+        1,
+        "Could not parse number of variables in header (res/fail/non_numeric_vars.cnf:1)",
+    );
+    check_parsing_fails(
+        "res/fail/negative_vars.cnf",
+        1,
+        "Number of variables in header is negative (res/fail/negative_vars.cnf:1)",
+    );
+    check_parsing_fails(
+        "res/fail/zero_vars.cnf",
+        1,
+        "Header declares clauses but no variables (res/fail/zero_vars.cnf:2)",
+    );
+    check_parsing_fails(
+        "res/fail/huge_vars.cnf",
+        1,
+        "Number of variables in header exceeds 2147483647 (res/fail/huge_vars.cnf:1)",
+    );
+}
+
+#[test]
+fn test_parse_cnf_oversized_header() {
+    // Rejected by the solver's variable limit instead of aborting on allocation
+    check_parsing_fails(
+        "res/fail/oversized_vars.cnf",
+        1,
+        "Number of variables in header exceeds the solver's limit (res/fail/oversized_vars.cnf:2)",
+    );
+}
+
 #[test]
 fn test_parse_cnf_too_many_vars() {
     check_parsing_fails(
         "res/fail/too_many_vars.cnf",
-        101,
-        "\nthread 'main' panicked",
+        1,
+        "Variable exceeds the number of variables in the header (res/fail/too_many_vars.cnf:12)",
+    );
+    check_parsing_fails(
+        "res/fail/var_out_of_range.cnf",
+        1,
+        "Variable exceeds the number of variables in the header (res/fail/var_out_of_range.cnf:4)",
     );
 }
 
@@ -97,10 +285,99 @@ fn test_parse_cnf_double_minus() {
 
 #[test]
 fn test_parse_cnf_empty_clause() {
-    check_parsing_fails(
-        "res/fail/empty_clause.cnf",
-        1,
-        "Number of clauses in cnf incorrect (res/fail/empty_clause.cnf:0)",
+    let mut solver = SolverMock::default();
+    parse_cnf("res/success/empty_clause.cnf", &mut solver);
+    assert_eq!(solver.num_clauses, 2029);
+    assert_eq!(solver.clauses[3], Vec::new());
+
+    // Empty clause makes the instance UNSAT
+    let exe = env!("CARGO_BIN_EXE_nanosat-rs");
+    let output = Command::new(exe)
+        .arg("res/success/empty_clause.cnf")
+        .output()
+        .expect("failed to run main binary");
+    assert_eq!(output.status.code(), Some(20));
+    assert!(String::from_utf8_lossy(&output.stdout).ends_with("UNSAT\n"));
+}
+
+#[test]
+fn test_parse_cnf_conflicting_units() {
+    // Contradicting units make the instance UNSAT rather than failing the parse
+    let exe = env!("CARGO_BIN_EXE_nanosat-rs");
+    let output = Command::new(exe)
+        .arg("res/success/conflicting_units.cnf")
+        .output()
+        .expect("failed to run main binary");
+    assert_eq!(output.status.code(), Some(20));
+    assert!(output.stderr.is_empty());
+    assert!(String::from_utf8_lossy(&output.stdout).ends_with("UNSAT\n"));
+}
+
+#[test]
+fn test_parse_wcnf() {
+    let lit = Literal::from_dimacs;
+    let mut instance = MaxSatInstance::new();
+    parse_wcnf("res/success/small.wcnf", &mut instance);
+    assert_eq!(instance.num_variables(), 2);
+    assert_eq!(instance.hard_clauses(), &[vec![lit(1), lit(2)]]);
+    assert_eq!(
+        instance.soft_clauses(),
+        &[(3, vec![lit(-1)]), (2, vec![lit(-2)]), (4, vec![lit(1)])]
+    );
+
+    // Without a top weight, all clauses are soft
+    let mut instance = MaxSatInstance::new();
+    parse_wcnf("res/success/no_top.wcnf", &mut instance);
+    assert!(instance.hard_clauses().is_empty());
+    assert_eq!(instance.soft_clauses().len(), 3);
+    assert_eq!(instance.solve().map(|solution| solution.cost), Some(1));
+}
+
+#[test]
+fn test_parse_wcnf_weight_overflow() {
+    let exe = env!("CARGO_BIN_EXE_nanosat-rs");
+    let output = Command::new(exe)
+        .args(["--maxsat", "res/fail/weight_overflow.wcnf"])
+        .output()
+        .expect("failed to run main binary");
+    assert_eq!(output.status.code(), Some(1));
+    assert_eq!(
+        String::from_utf8_lossy(&output.stderr),
+        "Could not parse clause weight; it exceeds 18446744073709551615 \
+         (res/fail/weight_overflow.wcnf:4).\n"
+    );
+}
+
+#[test]
+fn test_parse_assumptions() {
+    let lit = Literal::from_dimacs;
+    // One literal per line after a comment
+    assert_eq!(
+        parse_assumptions("res/success/small_sat.assumptions", 3),
+        vec![lit(1), lit(2), lit(3)]
+    );
+    // Whitespace-separated list terminated by `0`
+    assert_eq!(
+        parse_assumptions("res/success/small_sat_unsat.assumptions", 3),
+        vec![lit(1), lit(-3)]
+    );
+}
+
+#[test]
+fn test_parse_assumptions_unknown_variable() {
+    let exe = env!("CARGO_BIN_EXE_nanosat-rs");
+    let output = Command::new(exe)
+        .args([
+            "--assume",
+            "res/fail/unknown_var.assumptions",
+            "res/success/small_sat.cnf",
+        ])
+        .output()
+        .expect("failed to run main binary");
+    assert_eq!(output.status.code(), Some(1));
+    assert_eq!(
+        String::from_utf8_lossy(&output.stderr),
+        "Assumption exceeds the number of variables (res/fail/unknown_var.assumptions:2).\n"
     );
 }
 
@@ -0,0 +1,120 @@
+use std::sync::Arc;
+
+use nanosat_rs::{
+    parsing::{ClauseReceiver, parse_cnf},
+    portfolio::{solve_portfolio, solve_portfolio_with_sharing},
+    solver::{ClauseExchange, Solver, SolverExitCode, VerbosityLevel, literal::Literal},
+};
+
+use crate::common::{Lcg, SolverMock};
+
+mod common;
+
+/// Whether `model` satisfies all `clauses`
+fn satisfies(clauses: &[Vec<Literal>], model: &[bool]) -> bool {
+    clauses.iter().all(|clause| {
+        clause
+            .iter()
+            .any(|literal| model[literal.var()] == literal.polarity())
+    })
+}
+
+/// Clauses stating that `num_pigeons` pigeons sit in distinct of `num_holes` holes
+fn pigeonhole(num_pigeons: usize, num_holes: usize) -> Vec<Vec<Literal>> {
+    let pigeon_in_hole = |pigeon: usize, hole: usize| num_holes * pigeon + hole;
+    let mut clauses: Vec<Vec<Literal>> = (0..num_pigeons)
+        .map(|pigeon| {
+            (0..num_holes)
+                .map(|hole| Literal::from_var_with_polarity(pigeon_in_hole(pigeon, hole), true))
+                .collect()
+        })
+        .collect();
+    for hole in 0..num_holes {
+        for first in 0..num_pigeons {
+            for second in first + 1..num_pigeons {
+                clauses.push(vec![
+                    Literal::from_var_with_polarity(pigeon_in_hole(first, hole), false),
+                    Literal::from_var_with_polarity(pigeon_in_hole(second, hole), false),
+                ]);
+            }
+        }
+    }
+    clauses
+}
+
+#[test]
+fn test_portfolio_sat() {
+    let mut mock_solver = SolverMock::default();
+    parse_cnf("res/success/medium_sat.cnf", &mut mock_solver);
+    for num_threads in [1, 4] {
+        let (status, model) = solve_portfolio(&mock_solver.clauses, num_threads);
+        assert_eq!(status, SolverExitCode::Sat);
+        let model = model.expect("SAT results come with a model");
+        assert!(satisfies(&mock_solver.clauses, &model));
+    }
+}
+
+#[test]
+fn test_portfolio_unsat() {
+    for num_threads in [1, 3] {
+        assert_eq!(
+            solve_portfolio(&pigeonhole(6, 5), num_threads),
+            (SolverExitCode::Unsat, None)
+        );
+    }
+    let contradiction = vec![
+        vec![Literal::from_dimacs(1)],
+        vec![Literal::from_dimacs(-1)],
+    ];
+    assert_eq!(
+        solve_portfolio(&contradiction, 2),
+        (SolverExitCode::Unsat, None)
+    );
+    assert_eq!(
+        solve_portfolio(&[], 2),
+        (SolverExitCode::Sat, Some(Vec::new()))
+    );
+}
+
+#[test]
+fn test_clause_sharing() {
+    // A solver importing the clauses learned by another one on the same
+    // instance reaches the same result
+    let clauses = pigeonhole(6, 5);
+    let exchange = Arc::new(ClauseExchange::new());
+    let mut results = Vec::new();
+    for id in 0..2 {
+        let mut solver = Solver::new(VerbosityLevel::OnlyResult);
+        solver.create_variables(30);
+        for clause in &clauses {
+            solver.add_clause(clause);
+        }
+        solver.set_clause_exchange(Arc::clone(&exchange), id, 4);
+        results.push((solver.solve(), solver.statistics().num_imported_clauses));
+    }
+    assert_eq!(results[0], (SolverExitCode::Unsat, 0));
+    assert_eq!(results[1].0, SolverExitCode::Unsat);
+    assert!(results[1].1 > 0);
+}
+
+#[test]
+fn test_portfolio_sharing_stress() {
+    // Random 3-SAT instances close to the satisfiability threshold
+    let mut rng = Lcg(20);
+    for round in 0..20 {
+        let num_vars = 60 + rng.next(40);
+        let clauses: Vec<Vec<Literal>> = (0..num_vars * 426 / 100)
+            .map(|_| {
+                (0..3)
+                    .map(|_| Literal::from_var_with_polarity(rng.next(num_vars), rng.next(2) == 0))
+                    .collect()
+            })
+            .collect();
+        let (status, model) = solve_portfolio(&clauses, 3);
+        let (shared_status, shared_model) = solve_portfolio_with_sharing(&clauses, 3, 3);
+        assert_eq!(status, shared_status, "round {round}");
+        for model in [model, shared_model].into_iter().flatten() {
+            assert!(satisfies(&clauses, &model), "round {round}");
+        }
+    }
+}
@@ -0,0 +1,306 @@
+use std::{cell::RefCell, io, io::Write, rc::Rc};
+
+use nanosat_rs::{
+    parsing::parse_cnf,
+    proof::{DratWriter, LratStep, LratWriter, ProofStep, ProofWriter, check_drat, check_lrat},
+    solver::{Solver, SolverExitCode, SolverOptions, VerbosityLevel, literal::Literal},
+};
+
+/// Clauses from DIMACS-style signed integers
+fn clauses(ints: &[&[i32]]) -> Vec<Vec<Literal>> {
+    ints.iter()
+        .map(|clause| clause.iter().copied().map(Literal::from_dimacs).collect())
+        .collect()
+}
+
+/// Proof step adding the clause of DIMACS-style signed integers
+fn add(ints: &[i32]) -> ProofStep {
+    ProofStep::Add(ints.iter().copied().map(Literal::from_dimacs).collect())
+}
+
+/// Proof step deleting the clause of DIMACS-style signed integers
+fn delete(ints: &[i32]) -> ProofStep {
+    ProofStep::Delete(ints.iter().copied().map(Literal::from_dimacs).collect())
+}
+
+#[test]
+fn test_check_valid_proofs() {
+    // All four clauses over two variables
+    let formula = clauses(&[&[1, 2], &[-1, 2], &[1, -2], &[-1, -2]]);
+    assert!(check_drat(&formula, &[add(&[2]), add(&[])]));
+    assert!(check_drat(
+        &formula,
+        &[add(&[2]), delete(&[-1, 2]), delete(&[2, 1]), add(&[])]
+    ));
+
+    // Tautologies may be added; the empty clause ends the proof
+    assert!(check_drat(
+        &formula,
+        &[add(&[1, -1]), add(&[2]), add(&[]), add(&[3])]
+    ));
+
+    // Propagation alone refutes the formula
+    let formula = clauses(&[&[1], &[-1, 2], &[-2]]);
+    assert!(check_drat(&formula, &[add(&[])]));
+}
+
+#[test]
+fn test_check_invalid_proofs() {
+    let formula = clauses(&[&[1, 2], &[-1, 2], &[1, -2], &[-1, -2]]);
+
+    // The empty clause is not implied by propagation alone
+    assert!(!check_drat(&formula, &[add(&[])]));
+    // The unit clause 3 is not implied
+    assert!(!check_drat(&formula, &[add(&[3]), add(&[2]), add(&[])]));
+    // The empty clause is never derived
+    assert!(!check_drat(&formula, &[add(&[2])]));
+    assert!(!check_drat(&formula, &[]));
+    // Deleted clauses no longer imply the added ones
+    assert!(!check_drat(
+        &formula,
+        &[delete(&[-1, 2]), add(&[2]), add(&[])]
+    ));
+    // A satisfiable formula has no proof
+    let formula = clauses(&[&[1, 2], &[-1, 2]]);
+    assert!(!check_drat(&formula, &[add(&[2]), add(&[])]));
+}
+
+/// Output that stays readable after the solver took ownership of its writer
+#[derive(Clone, Default)]
+struct SharedBuffer(Rc<RefCell<Vec<u8>>>);
+
+impl Write for SharedBuffer {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.0.borrow_mut().write(buf)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+/// Clauses stating that `num_pigeons` pigeons sit in distinct of `num_holes` holes
+fn pigeonhole(num_pigeons: i32, num_holes: i32) -> Vec<Vec<Literal>> {
+    let pigeon_in_hole = |pigeon: i32, hole: i32| num_holes * pigeon + hole + 1;
+    let mut ints: Vec<Vec<i32>> = (0..num_pigeons)
+        .map(|pigeon| {
+            (0..num_holes)
+                .map(|hole| pigeon_in_hole(pigeon, hole))
+                .collect()
+        })
+        .collect();
+    for hole in 0..num_holes {
+        for first in 0..num_pigeons {
+            for second in first + 1..num_pigeons {
+                ints.push(vec![
+                    -pigeon_in_hole(first, hole),
+                    -pigeon_in_hole(second, hole),
+                ]);
+            }
+        }
+    }
+    ints.iter()
+        .map(|clause| clause.iter().copied().map(Literal::from_dimacs).collect())
+        .collect()
+}
+
+/// Solves the UNSAT `clauses` while writing a proof to `writer`
+fn prove(clauses: &[Vec<Literal>], options: SolverOptions, writer: impl ProofWriter + 'static) {
+    let mut solver = Solver::with_options(VerbosityLevel::OnlyResult, options);
+    solver.set_proof_writer(writer, clauses.len());
+    for clause in clauses {
+        solver.add_clause_incremental(clause);
+    }
+    assert_eq!(solver.solve(), SolverExitCode::Unsat);
+    solver.finish_proof().expect("writing to memory succeeds");
+}
+
+/// DIMACS-style signed integers of a proof line up to the terminating `0`s
+fn parse_ints(line: &str) -> Vec<i64> {
+    line.split_whitespace()
+        .map(|token| token.parse().expect("proof contains integers"))
+        .collect()
+}
+
+/// Literals of DIMACS-style signed integers
+fn literals(ints: &[i64]) -> Vec<Literal> {
+    ints.iter()
+        .map(|&int| Literal::from_dimacs(i32::try_from(int).expect("literal fits")))
+        .collect()
+}
+
+#[test]
+fn test_lrat_proof() {
+    let formula = pigeonhole(5, 4);
+    let elimination = SolverOptions {
+        variable_elimination: true,
+        blocked_clause_elimination: true,
+        ..SolverOptions::default()
+    };
+    for options in [SolverOptions::default(), elimination] {
+        let buffer = SharedBuffer::default();
+        prove(&formula, options, LratWriter::new(buffer.clone()));
+
+        // Lines are `id literals 0 hints 0` or `id d ids 0`
+        let text = String::from_utf8(buffer.0.take()).expect("proof is text");
+        let proof: Vec<LratStep> = text
+            .lines()
+            .map(|line| {
+                if let Some((_, ids)) = line.split_once(" d ") {
+                    let ids = parse_ints(ids);
+                    return LratStep::Delete(
+                        ids[..ids.len() - 1].iter().map(|&id| id as u64).collect(),
+                    );
+                }
+                let ints = parse_ints(line);
+                let end = ints
+                    .iter()
+                    .skip(1)
+                    .position(|&int| int == 0)
+                    .expect("clause ends")
+                    + 1;
+                LratStep::Add {
+                    id: ints[0] as u64,
+                    literals: literals(&ints[1..end]),
+                    hints: ints[end + 1..ints.len() - 1]
+                        .iter()
+                        .map(|&id| id as u64)
+                        .collect(),
+                }
+            })
+            .collect();
+        assert!(check_lrat(&formula, &proof));
+
+        // Derived clauses are numbered after the original ones
+        assert!(proof.iter().all(|step| match step {
+            LratStep::Add { id, .. } => *id as usize > formula.len(),
+            LratStep::Delete(_) => true,
+        }));
+    }
+}
+
+#[test]
+fn test_drat_proof() {
+    let formula = pigeonhole(5, 4);
+    let buffer = SharedBuffer::default();
+    prove(
+        &formula,
+        SolverOptions::default(),
+        DratWriter::new(buffer.clone()),
+    );
+
+    let text = String::from_utf8(buffer.0.take()).expect("proof is text");
+    let proof: Vec<ProofStep> = text
+        .lines()
+        .map(|line| match line.strip_prefix("d ") {
+            Some(rest) => {
+                let ints = parse_ints(rest);
+                ProofStep::Delete(literals(&ints[..ints.len() - 1]))
+            }
+            None => {
+                let ints = parse_ints(line);
+                ProofStep::Add(literals(&ints[..ints.len() - 1]))
+            }
+        })
+        .collect();
+    assert!(check_drat(&formula, &proof));
+}
+
+#[test]
+fn test_check_lrat() {
+    let formula = clauses(&[&[1, 2], &[-1, 2], &[1, -2], &[-1, -2]]);
+    let add = |id, ints: &[i32], hints: &[u64]| LratStep::Add {
+        id,
+        literals: ints.iter().copied().map(Literal::from_dimacs).collect(),
+        hints: hints.to_vec(),
+    };
+    assert!(check_lrat(
+        &formula,
+        &[add(5, &[2], &[1, 2]), add(6, &[], &[5, 3, 4])]
+    ));
+
+    // Hints must be unit or falsified, not satisfied
+    assert!(!check_lrat(
+        &formula,
+        &[add(5, &[2], &[3, 1]), add(6, &[], &[5, 3, 4])]
+    ));
+    // Identifiers must be unused
+    assert!(!check_lrat(
+        &formula,
+        &[add(4, &[2], &[1, 2]), add(6, &[], &[4, 3, 4])]
+    ));
+    // Deleted clauses cannot be hinted
+    assert!(!check_lrat(
+        &formula,
+        &[
+            LratStep::Delete(vec![2]),
+            add(5, &[2], &[1, 2]),
+            add(6, &[], &[5, 3, 4])
+        ]
+    ));
+    // Duplicate literals do not prevent a clause from being unit
+    let formula = clauses(&[&[1, 1], &[-1, 2, 2], &[-2]]);
+    assert!(check_lrat(&formula, &[add(4, &[], &[1, 2, 3])]));
+    assert!(check_drat(&formula, &[ProofStep::Add(Vec::new())]));
+}
+
+/// Solves `clauses` recording the UNSAT core; returns the result and the core
+fn solve_for_core(
+    clauses: &[Vec<Literal>],
+    options: SolverOptions,
+) -> (SolverExitCode, Vec<usize>) {
+    let mut solver = Solver::with_options(
+        VerbosityLevel::OnlyResult,
+        SolverOptions {
+            unsat_core: true,
+            ..options
+        },
+    );
+    for clause in clauses {
+        solver.add_clause_incremental(clause);
+    }
+    (solver.solve(), solver.unsat_core())
+}
+
+#[test]
+fn test_unsat_core() {
+    // Satisfiable clauses over other variables precede the pigeonhole clauses
+    let padding = clauses(&[&[21, 22], &[-21, 23], &[-22, -23], &[24]]);
+    let mut formula = padding.clone();
+    formula.extend(pigeonhole(5, 4));
+
+    let elimination = SolverOptions {
+        variable_elimination: true,
+        blocked_clause_elimination: true,
+        ..SolverOptions::default()
+    };
+    for options in [SolverOptions::default(), elimination] {
+        let (result, core) = solve_for_core(&formula, options);
+        assert_eq!(result, SolverExitCode::Unsat);
+        assert!(!core.is_empty());
+        assert!(core.iter().all(|&idx| idx >= padding.len()));
+
+        // The core alone is UNSAT
+        let core_clauses: Vec<Vec<Literal>> =
+            core.iter().map(|&idx| formula[idx].clone()).collect();
+        let (result, _) = solve_for_core(&core_clauses, SolverOptions::default());
+        assert_eq!(result, SolverExitCode::Unsat);
+    }
+
+    // Satisfiable instances have no core
+    let (result, core) = solve_for_core(&padding, SolverOptions::default());
+    assert_eq!(result, SolverExitCode::Sat);
+    assert!(core.is_empty());
+
+    // The empty clause of the fixture is a core on its own
+    let mut solver = Solver::with_options(
+        VerbosityLevel::OnlyResult,
+        SolverOptions {
+            unsat_core: true,
+            ..SolverOptions::default()
+        },
+    );
+    parse_cnf("res/success/empty_clause.cnf", &mut solver);
+    assert_eq!(solver.solve(), SolverExitCode::Unsat);
+    assert_eq!(solver.unsat_core(), vec![3]);
+}
@@ -1,9 +1,28 @@
+use std::{
+    cell::RefCell,
+    collections::HashSet,
+    fs::File,
+    rc::Rc,
+    sync::{
+        Arc,
+        atomic::{AtomicBool, Ordering},
+    },
+};
+
 use nanosat_rs::{
-    parsing::parse_cnf,
-    solver::{Solver, SolverExitCode, VerbosityLevel},
+    parsing::{ClauseReceiver, ParseMode, parse_cnf, parse_cnf_with_mode},
+    solver::{
+        BranchingHeuristic, ConflictInfo, InitialPolarity, LearningScheme, MinimizationLevel,
+        ReductionStrategy, RestartStrategy, SearchSnapshot, SolveOutcome, Solver, SolverEvent,
+        SolverExitCode, SolverOptions, VerbosityLevel,
+        clause::{ClauseRef, Clauses, DimacsClause},
+        literal::{DimacsLiteralError, Literal},
+        variable::VariableValue,
+    },
 };
+use proptest::prelude::*;
 
-use crate::common::SolverMock;
+use crate::common::{Lcg, SolverMock};
 
 mod common;
 
@@ -12,7 +31,7 @@ fn check_model(solver: &Solver, mock_solver: &SolverMock) {
     for clause in mock_solver.clauses.iter() {
         let mut contains_true_literal = false;
         for &lit in clause.iter() {
-            if lit.is_true(&solver.model()) {
+            if lit.is_true(solver.model()) {
                 contains_true_literal = true;
                 break;
             }
@@ -47,6 +66,407 @@ fn test_solve_medium_sat_instance() {
     check_model(&solver, &mock_solver);
 }
 
+#[test]
+fn test_reduction_strategies() {
+    for reduction_strategy in [
+        ReductionStrategy::Activity,
+        ReductionStrategy::Lbd,
+        ReductionStrategy::Random,
+    ] {
+        let options = SolverOptions {
+            reduction_strategy,
+            ..SolverOptions::default()
+        };
+        let mut solver = Solver::with_options(VerbosityLevel::OnlyResult, options);
+        parse_cnf("res/success/big_sat_instance.cnf.xz", &mut solver);
+        let mut mock_solver = SolverMock::default();
+        parse_cnf("res/success/big_sat_instance.cnf.xz", &mut mock_solver);
+        assert_eq!(solver.solve(), SolverExitCode::Sat);
+        check_model(&solver, &mock_solver);
+    }
+}
+
+#[test]
+fn test_scheduled_reductions() {
+    let mut mock_solver = SolverMock::default();
+    parse_cnf("res/success/big_sat_instance.cnf.xz", &mut mock_solver);
+    let mut num_reductions = Vec::new();
+    for reduction_first_interval in [None, Some(100)] {
+        let options = SolverOptions {
+            reduction_strategy: ReductionStrategy::Lbd,
+            reduction_first_interval,
+            reduction_interval_growth: 1.5,
+            ..SolverOptions::default()
+        };
+        let mut solver = Solver::with_options(VerbosityLevel::OnlyResult, options);
+        parse_cnf("res/success/big_sat_instance.cnf.xz", &mut solver);
+        assert_eq!(solver.solve(), SolverExitCode::Sat);
+        check_model(&solver, &mock_solver);
+        num_reductions.push(solver.statistics().num_reductions);
+    }
+
+    // Reductions every 100, 150, 225, ... conflicts come on top of those
+    // triggered by the number of learned clauses
+    assert!(num_reductions[1] > num_reductions[0]);
+}
+
+#[test]
+fn test_reductions_keep_reasons() {
+    // Reductions every 20 conflicts run while many learned clauses, also
+    // inactive ones, are reasons on the trail
+    const NUM_VARS: usize = 100;
+    let mut rng = Lcg(31);
+    let mut num_learned_reasons = 0;
+    for round in 0..30 {
+        let options = SolverOptions {
+            reduction_strategy: [
+                ReductionStrategy::Activity,
+                ReductionStrategy::Lbd,
+                ReductionStrategy::Random,
+            ][round % 3],
+            reduction_first_interval: Some(20),
+            reduction_interval_growth: 1.0,
+            ..SolverOptions::default()
+        };
+        let mut solver = Solver::with_options(VerbosityLevel::OnlyResult, options);
+        assert!(solver.create_variables(NUM_VARS));
+        for _ in 0..400 {
+            let clause: Vec<Literal> = (0..3)
+                .map(|_| Literal::from_var_with_polarity(rng.next(NUM_VARS), rng.next(2) == 0))
+                .collect();
+            solver.add_clause(&clause);
+        }
+        if solver.solve() != SolverExitCode::Sat {
+            continue;
+        }
+
+        // Every reason is still a kept clause and implies its literal from
+        // literals assigned no later
+        let kept: HashSet<&[Literal]> = solver
+            .original_clauses()
+            .chain(solver.learned_clauses())
+            .collect();
+        let learned: HashSet<&[Literal]> = solver.learned_clauses().collect();
+        let mut previous_level = 0;
+        for &literal in solver.trail() {
+            // Only the first literal of a decision level is a decision
+            let level = solver.decision_level_of(literal.var());
+            let is_decision = level > previous_level;
+            previous_level = level;
+            let Some(reason) = solver.reason_of(literal.var()) else {
+                assert!(level == 0 || is_decision, "reason of {literal:?} was lost");
+                continue;
+            };
+            let clause = solver.clause(reason);
+            assert!(kept.contains(clause), "reason {clause:?} was deleted");
+            assert_eq!(clause[0], literal);
+            assert!(clause[1..].iter().all(|other| {
+                other.is_false(solver.model()) && solver.decision_level_of(other.var()) <= level
+            }));
+            if learned.contains(clause) {
+                num_learned_reasons += 1;
+            }
+        }
+    }
+    assert!(num_learned_reasons > 0);
+}
+
+#[test]
+fn test_random_phase() {
+    for file_name in [
+        "res/success/medium_sat.cnf",
+        "res/success/big_sat_instance.cnf.xz",
+    ] {
+        let options = SolverOptions {
+            random_phase_prob: 0.1,
+            ..SolverOptions::default()
+        };
+        let mut solver = Solver::with_options(VerbosityLevel::OnlyResult, options);
+        parse_cnf(file_name, &mut solver);
+        let mut mock_solver = SolverMock::default();
+        parse_cnf(file_name, &mut mock_solver);
+        assert_eq!(solver.solve(), SolverExitCode::Sat);
+        check_model(&solver, &mock_solver);
+    }
+}
+
+#[test]
+fn test_rephasing() {
+    let options = SolverOptions {
+        rephasing: true,
+        ..SolverOptions::default()
+    };
+    let mut solver = Solver::with_options(VerbosityLevel::OnlyResult, options);
+    parse_cnf("res/success/big_sat_instance.cnf.xz", &mut solver);
+    let mut mock_solver = SolverMock::default();
+    parse_cnf("res/success/big_sat_instance.cnf.xz", &mut mock_solver);
+    assert_eq!(solver.solve(), SolverExitCode::Sat);
+    check_model(&solver, &mock_solver);
+}
+
+#[test]
+fn test_stabilization() {
+    let options = SolverOptions {
+        stabilization_interval: Some(50),
+        ..SolverOptions::default()
+    };
+    let mut solver = Solver::with_options(VerbosityLevel::OnlyResult, options);
+    parse_cnf("res/success/big_sat_instance.cnf.xz", &mut solver);
+    let mut mock_solver = SolverMock::default();
+    parse_cnf("res/success/big_sat_instance.cnf.xz", &mut mock_solver);
+    assert_eq!(solver.solve(), SolverExitCode::Sat);
+    check_model(&solver, &mock_solver);
+    assert!(solver.statistics().num_stabilizations > 0);
+}
+
+#[test]
+fn test_geometric_restarts() {
+    let options = SolverOptions {
+        restart_strategy: RestartStrategy::Geometric,
+        random_seed: 7,
+        ..SolverOptions::default()
+    };
+    let mut solver = Solver::with_options(VerbosityLevel::OnlyResult, options);
+    parse_cnf("res/success/big_sat_instance.cnf.xz", &mut solver);
+    let mut mock_solver = SolverMock::default();
+    parse_cnf("res/success/big_sat_instance.cnf.xz", &mut mock_solver);
+    assert_eq!(solver.solve(), SolverExitCode::Sat);
+    check_model(&solver, &mock_solver);
+}
+
+#[test]
+fn test_interrupt() {
+    let interrupt = Arc::new(AtomicBool::new(true));
+    let mut solver = Solver::new(VerbosityLevel::OnlyResult);
+    parse_cnf("res/success/medium_sat.cnf", &mut solver);
+    solver.set_interrupt(Arc::clone(&interrupt));
+    assert_eq!(solver.solve(), SolverExitCode::Unknown);
+    assert_eq!(solver.statistics().num_decisions, 0);
+
+    // Solving resumes once the flag is cleared
+    interrupt.store(false, Ordering::Relaxed);
+    assert_eq!(solver.solve(), SolverExitCode::Sat);
+}
+
+#[test]
+fn test_run_outcome() {
+    let mut solver = Solver::new(VerbosityLevel::OnlyResult);
+    parse_cnf("res/success/medium_sat.cnf", &mut solver);
+    let mut mock_solver = SolverMock::default();
+    parse_cnf("res/success/medium_sat.cnf", &mut mock_solver);
+    let SolveOutcome::Sat(model) = solver.run() else {
+        panic!("instance is SAT");
+    };
+    assert!(mock_solver.clauses.iter().all(|clause| {
+        clause
+            .iter()
+            .any(|literal| model[literal.var()] == literal.polarity())
+    }));
+
+    // Interrupted before searching
+    let mut solver = Solver::new(VerbosityLevel::OnlyResult);
+    parse_cnf("res/success/medium_sat.cnf", &mut solver);
+    solver.set_interrupt(Arc::new(AtomicBool::new(true)));
+    assert_eq!(solver.run(), SolveOutcome::Unknown);
+
+    // The core is only recorded if enabled
+    let mut solver = Solver::new(VerbosityLevel::OnlyResult);
+    parse_cnf("res/success/empty_clause.cnf", &mut solver);
+    assert_eq!(solver.run(), SolveOutcome::Unsat { core: None });
+    let options = SolverOptions {
+        unsat_core: true,
+        ..SolverOptions::default()
+    };
+    let mut solver = Solver::with_options(VerbosityLevel::OnlyResult, options);
+    parse_cnf("res/success/empty_clause.cnf", &mut solver);
+    assert_eq!(
+        solver.run(),
+        SolveOutcome::Unsat {
+            core: Some(vec![3])
+        }
+    );
+}
+
+#[test]
+fn test_learning_schemes() {
+    for learning_scheme in [
+        LearningScheme::FirstUip,
+        LearningScheme::LastUip,
+        LearningScheme::AllDecisions,
+    ] {
+        let options = SolverOptions {
+            learning_scheme,
+            ..SolverOptions::default()
+        };
+        let mut solver = Solver::with_options(VerbosityLevel::OnlyResult, options);
+        parse_cnf("res/success/medium_sat.cnf", &mut solver);
+        let mut mock_solver = SolverMock::default();
+        parse_cnf("res/success/medium_sat.cnf", &mut mock_solver);
+        assert_eq!(solver.solve(), SolverExitCode::Sat);
+        check_model(&solver, &mock_solver);
+
+        // Pigeonhole principle: 5 pigeons do not fit into 4 holes
+        let mut solver = Solver::with_options(VerbosityLevel::OnlyResult, options);
+        solver.create_variables(20);
+        let pigeon_in_hole = |pigeon: usize, hole: usize| 4 * pigeon + hole;
+        for pigeon in 0..5 {
+            let clause: Vec<Literal> = (0..4)
+                .map(|hole| Literal::from_var_with_polarity(pigeon_in_hole(pigeon, hole), true))
+                .collect();
+            solver.add_clause(&clause);
+        }
+        for hole in 0..4 {
+            for first in 0..5 {
+                for second in first + 1..5 {
+                    solver.add_clause(&[
+                        Literal::from_var_with_polarity(pigeon_in_hole(first, hole), false),
+                        Literal::from_var_with_polarity(pigeon_in_hole(second, hole), false),
+                    ]);
+                }
+            }
+        }
+        assert_eq!(solver.solve(), SolverExitCode::Unsat);
+        assert!(solver.statistics().num_total_conflicts > 0);
+    }
+}
+
+#[test]
+fn test_minimization_levels() {
+    // Assuming 1 implies 2, 3, and 4; assuming 5 then implies 6 and 7, which
+    // violates the last clause; of the learned clause (-6 or -1 or -2 or -4),
+    // the reason (2 or -1) of 2 is in the clause, whereas the reason (4 or -3)
+    // of 4 is only implied through (3 or -1)
+    for (minimization, num_minimized_literals) in [
+        (MinimizationLevel::None, 0),
+        (MinimizationLevel::Local, 1),
+        (MinimizationLevel::Recursive, 2),
+    ] {
+        let options = SolverOptions {
+            subsumption: false,
+            minimization,
+            ..SolverOptions::default()
+        };
+        let mut solver = Solver::with_options(VerbosityLevel::OnlyResult, options);
+        for clause in [
+            &[-1, 2][..],
+            &[-1, 3],
+            &[-3, 4],
+            &[-5, 6],
+            &[-6, 7],
+            &[-7, -6, -1, -2, -4],
+        ] {
+            assert!(solver.add_clause_ints(clause));
+        }
+        assert_eq!(
+            solver.solve_with_assumptions(&[Literal::from_dimacs(1), Literal::from_dimacs(5)]),
+            SolverExitCode::Unsat
+        );
+        assert_eq!(solver.statistics().num_total_conflicts, 1);
+        assert_eq!(
+            solver.statistics().num_minimized_literals,
+            num_minimized_literals
+        );
+    }
+
+    // Minimization never changes the result
+    for minimization in [MinimizationLevel::None, MinimizationLevel::Local] {
+        let options = SolverOptions {
+            minimization,
+            ..SolverOptions::default()
+        };
+        let mut solver = Solver::with_options(VerbosityLevel::OnlyResult, options);
+        parse_cnf("res/success/medium_sat.cnf", &mut solver);
+        let mut mock_solver = SolverMock::default();
+        parse_cnf("res/success/medium_sat.cnf", &mut mock_solver);
+        assert_eq!(solver.solve(), SolverExitCode::Sat);
+        check_model(&solver, &mock_solver);
+    }
+}
+
+#[test]
+fn test_conflict_callback() {
+    let conflicts = Rc::new(RefCell::new(Vec::new()));
+    let mut solver = Solver::new(VerbosityLevel::OnlyResult);
+    let observed = Rc::clone(&conflicts);
+    solver.set_conflict_callback(move |info: &ConflictInfo| observed.borrow_mut().push(*info));
+    parse_cnf("res/success/medium_sat.cnf", &mut solver);
+    assert_eq!(solver.last_learned_lbd(), None);
+    assert_eq!(solver.last_learned_size(), None);
+    assert_eq!(solver.solve(), SolverExitCode::Sat);
+
+    let conflicts = conflicts.borrow();
+    assert_eq!(conflicts.len(), solver.statistics().num_total_conflicts);
+    let last = conflicts.last().expect("instance needs conflicts");
+    assert_eq!(
+        solver.last_learned_lbd(),
+        Some(u32::try_from(last.lbd).expect("LBD fits in u32"))
+    );
+    assert_eq!(solver.last_learned_size(), Some(last.learned_clause_len));
+    for info in conflicts.iter() {
+        assert!(info.learned_clause_len > 0);
+        assert!(info.backtrack_level < info.decision_level);
+        assert!(info.lbd >= 1 && info.lbd <= info.learned_clause_len);
+        assert_eq!(
+            info.learned_clause_len == 1,
+            info.backtrack_level == 0 && info.lbd == 1
+        );
+    }
+}
+
+#[test]
+fn test_event_callback() {
+    let events = Rc::new(RefCell::new(Vec::new()));
+    let options = SolverOptions {
+        reduction_first_interval: Some(100),
+        ..SolverOptions::default()
+    };
+    let mut solver = Solver::with_options(VerbosityLevel::OnlyResult, options);
+    let observed = Rc::clone(&events);
+    solver.set_event_callback(move |event| observed.borrow_mut().push(event));
+    parse_cnf("res/success/big_sat_instance.cnf.xz", &mut solver);
+    assert_eq!(solver.solve(), SolverExitCode::Sat);
+
+    // Events arrive in search order and match the statistics
+    let (mut num_restarts, mut num_conflicts, mut num_reductions, mut num_ticks) = (0, 0, 0, 0);
+    for &event in events.borrow().iter() {
+        match event {
+            SolverEvent::Restart { number, limit } => {
+                assert_eq!(number, num_restarts);
+                assert!(limit > 0);
+                num_restarts += 1;
+            }
+            SolverEvent::Conflict { total, lbd, .. } => {
+                num_conflicts += 1;
+                assert_eq!(total, num_conflicts);
+                assert!(lbd >= 1);
+            }
+            SolverEvent::Reduce { deleted, .. } => {
+                assert!(deleted > 0);
+                num_reductions += 1;
+            }
+            SolverEvent::ProgressTick { estimate } => {
+                assert!((0.0..=1.0).contains(&estimate));
+                num_ticks += 1;
+            }
+        }
+    }
+    assert!(matches!(
+        events.borrow().first(),
+        Some(SolverEvent::Restart { number: 0, .. })
+    ));
+    assert_eq!(num_restarts, solver.restart_windows().len());
+    assert_eq!(num_conflicts, solver.statistics().num_total_conflicts);
+    assert_eq!(num_reductions, solver.statistics().num_reductions);
+    assert!(num_reductions > 0 && num_ticks > 0);
+
+    // Without a callback, nothing is observed
+    let num_events = events.borrow().len();
+    solver.clear_event_callback();
+    assert!(solver.add_clause_incremental(&[Literal::from_dimacs(1), Literal::from_dimacs(2)]));
+    assert_eq!(solver.solve(), SolverExitCode::Sat);
+    assert_eq!(events.borrow().len(), num_events);
+}
+
 #[test]
 fn test_solve_big_sat_instance() {
     let mut solver = Solver::new(VerbosityLevel::OnlyResult);
@@ -59,3 +479,1739 @@ fn test_solve_big_sat_instance() {
     assert_eq!(res, SolverExitCode::Sat);
     check_model(&solver, &mock_solver);
 }
+
+#[test]
+fn test_solve_formula_without_clauses() {
+    let mut solver = Solver::new(VerbosityLevel::OnlyResult);
+    parse_cnf("res/success/no_clauses.cnf", &mut solver);
+    assert_eq!(solver.solve(), SolverExitCode::Sat);
+    assert_eq!(solver.model(), &vec![VariableValue::False; 3]);
+    assert_eq!(solver.count_models(), 8);
+
+    // No variables at all
+    let mut solver = Solver::new(VerbosityLevel::OnlyResult);
+    assert_eq!(solver.solve(), SolverExitCode::Sat);
+    assert_eq!(solver.count_models(), 1);
+}
+
+#[test]
+fn test_solve_with_lenient_parsing() {
+    let mut solver = Solver::new(VerbosityLevel::OnlyResult);
+    parse_cnf_with_mode(
+        "res/fail/too_many_vars.cnf",
+        &mut solver,
+        ParseMode::Lenient,
+    );
+    let mut mock_solver = SolverMock::default();
+    parse_cnf_with_mode(
+        "res/fail/too_many_vars.cnf",
+        &mut mock_solver,
+        ParseMode::Lenient,
+    );
+    assert_eq!(solver.num_variables(), 5000);
+    assert_eq!(solver.solve(), SolverExitCode::Sat);
+    check_model(&solver, &mock_solver);
+}
+
+#[test]
+fn test_variable_value_conversions() {
+    const NEGATED: VariableValue = VariableValue::True.negate();
+    assert_eq!(NEGATED, VariableValue::False);
+    assert_eq!(!VariableValue::False, VariableValue::True);
+    assert_eq!(!VariableValue::Unset, VariableValue::Unset);
+    assert_eq!(VariableValue::True.as_bool(), Some(true));
+    assert_eq!(VariableValue::False.as_bool(), Some(false));
+    assert_eq!(VariableValue::Unset.as_bool(), None);
+    assert!(VariableValue::Unset != false && VariableValue::Unset != true);
+
+    // Models assign every variable
+    let mut solver = Solver::new(VerbosityLevel::OnlyResult);
+    parse_cnf("res/success/small_sat.cnf", &mut solver);
+    assert_eq!(solver.solve(), SolverExitCode::Sat);
+    let model: Option<Vec<bool>> = solver.model().iter().map(|value| value.as_bool()).collect();
+    assert_eq!(model.map(|model| model.len()), Some(3));
+}
+
+#[test]
+fn test_compact_literals() {
+    assert_eq!(size_of::<Literal>(), 4);
+    assert_eq!(size_of::<ClauseRef>(), 4);
+
+    // Round trip of the largest representable variable
+    let var = (u32::MAX >> 1) as usize - 1;
+    let literal = Literal::from_var_with_polarity(var, false);
+    assert_eq!(literal.var(), var);
+    assert!(!literal.polarity());
+    assert!((!literal).polarity());
+    assert!(literal.valid());
+    assert!(!Literal::default().valid());
+
+    // Conversions from and to DIMACS
+    for value in [1, -1, 42, -42, i32::MAX, -i32::MAX] {
+        let literal = Literal::try_from(value).unwrap();
+        assert_eq!(literal, Literal::from_dimacs(value));
+        assert_eq!(i32::from(literal), value);
+    }
+    assert_eq!(
+        Literal::try_from_dimacs(0),
+        Err(DimacsLiteralError::Terminator)
+    );
+    assert_eq!(
+        Literal::try_from_dimacs(i32::MIN),
+        Err(DimacsLiteralError::OutOfRange)
+    );
+    assert_eq!(Literal::try_from(0), Err(DimacsLiteralError::Terminator));
+
+    // Readable output for tracing
+    assert_eq!(Literal::from_dimacs(-3).to_string(), "-3");
+    assert_eq!(Literal::from_dimacs(7).to_string(), "7");
+    assert_eq!(Literal::default().to_string(), "invalid");
+    assert_eq!(ClauseRef::from_idx(5, false).to_string(), "O#5");
+    assert_eq!(format!("{:?}", ClauseRef::from_idx(12, true)), "L#12");
+    assert_eq!(ClauseRef::default().to_string(), "invalid");
+}
+
+#[test]
+#[should_panic(expected = "variable exceeds the u32 literal representation")]
+fn test_literal_beyond_representation() {
+    let _ = Literal::from_var_with_polarity((u32::MAX >> 1) as usize, true);
+}
+
+#[test]
+fn test_clause_garbage_collection() {
+    let lit = |var| Literal::from_var_with_polarity(var, true);
+    let mut clauses = Clauses::<true>::default();
+    let first = clauses.add_clause(&[lit(0), lit(1)]);
+    let second = clauses.add_clause(&[lit(1), lit(2), lit(3)]);
+    let third = clauses.add_clause(&[lit(2), lit(4)]);
+    clauses.remove_clause(second);
+    assert_eq!(clauses.len(), 2);
+    assert!(clauses.wasted() > 0);
+
+    // Kept clauses move, removed ones become invalid
+    let relocation_map = clauses.collect_garbage();
+    assert_eq!(clauses.wasted(), 0);
+    assert_eq!(clauses.refs().count(), 2);
+    assert_eq!(&clauses[relocation_map.relocate(first)], &[lit(0), lit(1)]);
+    assert_eq!(&clauses[relocation_map.relocate(third)], &[lit(2), lit(4)]);
+    assert!(!relocation_map.relocate(second).valid());
+    assert_eq!(
+        relocation_map.relocate(ClauseRef::from_idx(0, false)),
+        ClauseRef::from_idx(0, false)
+    );
+}
+
+#[test]
+fn test_clause_remove_twice_or_from_empty_arena() {
+    let lit = |var| Literal::from_var_with_polarity(var, true);
+    let mut clauses = Clauses::<false>::default();
+    clauses.remove_clause(ClauseRef::from_idx(0, false));
+    assert!(clauses.is_empty());
+    assert_eq!(clauses.wasted(), 0);
+
+    // Removing a clause again changes nothing
+    let first = clauses.add_clause(&[lit(0), lit(1)]);
+    let second = clauses.add_clause(&[lit(1), lit(2)]);
+    clauses.remove_clause(first);
+    let wasted = clauses.wasted();
+    clauses.remove_clause(first);
+    assert_eq!(clauses.len(), 1);
+    assert_eq!(clauses.wasted(), wasted);
+    assert_eq!(&clauses[second], &[lit(1), lit(2)]);
+    clauses.remove_clause(second);
+    clauses.remove_clause(second);
+    assert!(clauses.is_empty());
+    assert_eq!(clauses.refs().count(), 0);
+}
+
+#[test]
+fn test_clause_interleaved_add_remove() {
+    let lit = |var| Literal::from_var_with_polarity(var, true);
+    let mut clauses = Clauses::<false>::default();
+    let mut live: Vec<(ClauseRef, Vec<Literal>)> = Vec::new();
+    let mut removed: Vec<ClauseRef> = Vec::new();
+    for round in 0..50 {
+        let literals: Vec<Literal> = (round..round + 2 + round % 3).map(lit).collect();
+        let clause_ref = clauses.add_clause(&literals);
+
+        // Removed slots are never handed out again
+        assert!(!removed.contains(&clause_ref));
+        live.push((clause_ref, literals));
+
+        // Remove the tail repeatedly and every third clause from the front
+        if round % 2 == 1 {
+            let (tail_ref, _) = live.pop().expect("a clause was added");
+            clauses.remove_clause(tail_ref);
+            removed.push(tail_ref);
+        }
+        if round % 3 == 2 {
+            let (front_ref, _) = live.remove(0);
+            clauses.remove_clause(front_ref);
+            removed.push(front_ref);
+        }
+
+        // All live clauses remain accessible
+        assert_eq!(clauses.len(), live.len());
+        let refs: Vec<ClauseRef> = clauses.refs().collect();
+        assert_eq!(
+            refs,
+            live.iter()
+                .map(|(clause_ref, _)| *clause_ref)
+                .collect::<Vec<_>>()
+        );
+        for (clause_ref, literals) in &live {
+            assert_eq!(&clauses[*clause_ref], literals.as_slice());
+        }
+        assert!(
+            removed
+                .iter()
+                .all(|&clause_ref| clauses.is_removed(clause_ref))
+        );
+    }
+
+    // Garbage collection keeps exactly the live clauses
+    let relocation_map = clauses.collect_garbage();
+    assert_eq!(clauses.refs().count(), live.len());
+    for (clause_ref, literals) in &live {
+        assert_eq!(
+            &clauses[relocation_map.relocate(*clause_ref)],
+            literals.as_slice()
+        );
+    }
+    assert!(
+        removed
+            .iter()
+            .all(|&clause_ref| !relocation_map.relocate(clause_ref).valid())
+    );
+}
+
+#[test]
+fn test_solve_summary() {
+    let mut solver = Solver::new(VerbosityLevel::OnlyResult);
+    parse_cnf("res/success/medium_sat.cnf", &mut solver);
+
+    // No rates are derived before the first solve
+    let summary = solver.summary();
+    assert_eq!(summary.total_time, 0.0);
+    assert_eq!(summary.conflicts_per_second, 0.0);
+    assert_eq!(summary.propagations_per_second, 0.0);
+
+    assert_eq!(solver.solve(), SolverExitCode::Sat);
+
+    let summary = solver.summary();
+    assert_eq!(summary.statistics, *solver.statistics());
+    assert!(summary.total_time > 0.0);
+    assert!(summary.propagations_per_second > 0.0);
+}
+
+#[test]
+fn test_clause_length_histogram() {
+    let mut solver = Solver::new(VerbosityLevel::OnlyResult);
+    assert!(solver.add_clause_ints(&[1, 2]));
+    assert!(solver.add_clause_ints(&[-1, 3]));
+    assert!(solver.add_clause_ints(&[1, -2, 4]));
+    assert!(solver.add_clause_ints(&[2, 3, -4, 5]));
+    assert_eq!(solver.clause_length_histogram(), vec![0, 0, 2, 1, 1]);
+
+    // Units are assigned instead of stored; satisfied clauses are skipped
+    assert!(solver.add_clause_ints(&[5]));
+    assert!(solver.add_clause_ints(&[-3, 5]));
+    assert_eq!(solver.clause_length_histogram(), vec![0, 0, 2, 1, 1]);
+
+    let mut solver = Solver::new(VerbosityLevel::OnlyResult);
+    parse_cnf("res/success/medium_sat.cnf", &mut solver);
+    let histogram = solver.clause_length_histogram();
+    assert_eq!(histogram.iter().sum::<usize>(), solver.num_clauses());
+}
+
+#[test]
+fn test_solve_timed() {
+    let mut solver = Solver::new(VerbosityLevel::OnlyResult);
+    parse_cnf("res/success/big_sat_instance.cnf.xz", &mut solver);
+    let (status, timings) = solver.solve_timed();
+    assert_eq!(status, SolverExitCode::Sat);
+
+    // Phases are disjoint parts of the whole call
+    assert!(timings.propagation > 0.0);
+    assert!(timings.conflict_analysis > 0.0);
+    assert!(
+        timings.preprocessing + timings.propagation + timings.conflict_analysis <= timings.total
+    );
+}
+
+#[test]
+fn test_restart_windows() {
+    let mut solver = Solver::new(VerbosityLevel::OnlyResult);
+    parse_cnf("res/success/big_sat_instance.cnf.xz", &mut solver);
+    assert_eq!(solver.solve(), SolverExitCode::Sat);
+
+    let windows = solver.restart_windows();
+    let stats = solver.statistics();
+    assert_eq!(windows.len(), stats.num_restarts);
+    let conflicts: usize = windows.iter().map(|w| w.num_conflicts).sum();
+    let propagations: usize = windows.iter().map(|w| w.num_propagations).sum();
+    assert!(conflicts <= stats.num_total_conflicts);
+    assert!(propagations <= stats.num_propagations);
+}
+
+#[test]
+fn test_duplicate_clause_detection() {
+    for detection in [false, true] {
+        let options = SolverOptions {
+            duplicate_clause_detection: detection,
+            ..SolverOptions::default()
+        };
+        let mut solver = Solver::with_options(VerbosityLevel::OnlyResult, options);
+        solver.create_variables(3);
+        for clause in [[1, 2, 3], [3, 1, 2], [2, -1, 3], [2, 3, 1]] {
+            let literals: Vec<Literal> = clause.into_iter().map(Literal::from_dimacs).collect();
+            assert!(solver.add_clause(&literals));
+        }
+        assert!(solver.add_clause(&[Literal::from_dimacs(1), Literal::from_dimacs(3)]));
+        assert!(solver.add_clause(&[
+            Literal::from_dimacs(3),
+            Literal::from_dimacs(1),
+            Literal::from_dimacs(3)
+        ]));
+
+        let num_duplicates = if detection { 3 } else { 0 };
+        assert_eq!(solver.statistics().num_duplicate_clauses, num_duplicates);
+        assert_eq!(solver.num_clauses(), 6 - num_duplicates);
+        assert_eq!(solver.solve(), SolverExitCode::Sat);
+    }
+}
+
+#[test]
+fn test_initial_phases() {
+    // Without constraints, the model follows the hinted phases
+    let mut solver = Solver::new(VerbosityLevel::OnlyResult);
+    solver.create_variables(4);
+    solver.set_initial_phases(&[true, false, true]);
+    solver.set_initial_phase(3, true);
+    assert_eq!(solver.solve(), SolverExitCode::Sat);
+    assert_eq!(
+        solver.model(),
+        &vec![
+            VariableValue::True,
+            VariableValue::False,
+            VariableValue::True,
+            VariableValue::True
+        ]
+    );
+
+    // Hints never override clauses
+    let mut solver = Solver::new(VerbosityLevel::OnlyResult);
+    assert!(solver.add_clause_ints(&[-1]));
+    solver.set_initial_phase(0, true);
+    assert_eq!(solver.solve(), SolverExitCode::Sat);
+    assert_eq!(solver.model(), &vec![VariableValue::False]);
+}
+
+#[test]
+fn test_initial_polarity() {
+    for initial_polarity in [
+        InitialPolarity::False,
+        InitialPolarity::True,
+        InitialPolarity::Random,
+        InitialPolarity::Jeroslow,
+    ] {
+        let options = SolverOptions {
+            initial_polarity,
+            ..SolverOptions::default()
+        };
+        let mut solver = Solver::with_options(VerbosityLevel::OnlyResult, options);
+        parse_cnf("res/success/medium_sat.cnf", &mut solver);
+        let mut mock_solver = SolverMock::default();
+        parse_cnf("res/success/medium_sat.cnf", &mut mock_solver);
+        assert_eq!(solver.solve(), SolverExitCode::Sat);
+        check_model(&solver, &mock_solver);
+    }
+
+    // Unconstrained variables keep their initial polarity
+    let options = SolverOptions {
+        initial_polarity: InitialPolarity::True,
+        ..SolverOptions::default()
+    };
+    let mut solver = Solver::with_options(VerbosityLevel::OnlyResult, options);
+    solver.create_variables(3);
+    assert_eq!(solver.solve(), SolverExitCode::Sat);
+    assert_eq!(solver.model(), &vec![VariableValue::True; 3]);
+
+    // Variables 1 and 3 occur positively in more and shorter clauses,
+    // variable 2 negatively
+    let options = SolverOptions {
+        initial_polarity: InitialPolarity::Jeroslow,
+        branching: BranchingHeuristic::InOrder,
+        subsumption: false,
+        ..SolverOptions::default()
+    };
+    let mut solver = Solver::with_options(VerbosityLevel::OnlyResult, options);
+    assert!(solver.add_clause_ints(&[1, 2]));
+    assert!(solver.add_clause_ints(&[1, -2, 3]));
+    assert!(solver.add_clause_ints(&[-1, -2, -3]));
+    assert!(solver.add_clause_ints(&[-2, 3]));
+    assert_eq!(solver.solve(), SolverExitCode::Sat);
+    assert_eq!(
+        solver.model(),
+        &vec![
+            VariableValue::True,
+            VariableValue::False,
+            VariableValue::True
+        ]
+    );
+}
+
+#[test]
+fn test_solve_with_assumptions() {
+    // (1 or 2) and (-1 or 3) and (-2 or 3)
+    let mut solver = Solver::new(VerbosityLevel::OnlyResult);
+    assert!(solver.add_clause_ints(&[1, 2]));
+    assert!(solver.add_clause_ints(&[-1, 3]));
+    assert!(solver.add_clause_ints(&[-2, 3]));
+    let lit = Literal::from_dimacs;
+
+    assert_eq!(
+        solver.solve_with_assumptions(&[lit(-1), lit(-3)]),
+        SolverExitCode::Unsat
+    );
+    let failed = solver.failed_assumptions();
+    assert!(failed.contains(&lit(-3)));
+    assert!(
+        failed
+            .iter()
+            .all(|&literal| [lit(-1), lit(-3)].contains(&literal))
+    );
+
+    // The assumptions do not persist
+    assert_eq!(
+        solver.solve_with_assumptions(&[lit(-1), lit(2)]),
+        SolverExitCode::Sat
+    );
+    assert!(solver.failed_assumptions().is_empty());
+    assert!(lit(3).is_true(solver.model()));
+    assert_eq!(solver.solve(), SolverExitCode::Sat);
+
+    // Contradicting assumptions
+    assert_eq!(
+        solver.solve_with_assumptions(&[lit(1), lit(2), lit(-1)]),
+        SolverExitCode::Unsat
+    );
+    let mut failed = solver.failed_assumptions().to_vec();
+    failed.sort();
+    assert_eq!(failed, vec![lit(-1), lit(1)]);
+
+    // A globally UNSAT instance has no failed assumptions
+    assert!(!solver.add_clause_ints(&[-3]));
+    assert_eq!(
+        solver.solve_with_assumptions(&[lit(1)]),
+        SolverExitCode::Unsat
+    );
+    assert!(solver.failed_assumptions().is_empty());
+
+    // Assumptions may contradict the pure literals of an earlier call
+    let options = SolverOptions {
+        pure_literal_elimination: true,
+        ..SolverOptions::default()
+    };
+    let mut solver = Solver::with_options(VerbosityLevel::OnlyResult, options);
+    assert!(solver.add_clause_ints(&[1, 2]));
+    assert!(solver.add_clause_ints(&[1, 3]));
+    assert_eq!(solver.solve(), SolverExitCode::Sat);
+    assert!(lit(1).is_true(solver.model()));
+    assert!(solver.statistics().num_pure_literals > 0);
+    assert_eq!(
+        solver.solve_with_assumptions(&[lit(-1)]),
+        SolverExitCode::Sat
+    );
+    assert!(lit(2).is_true(solver.model()) && lit(3).is_true(solver.model()));
+    assert_eq!(
+        solver.solve_with_assumptions(&[lit(-1), lit(-3)]),
+        SolverExitCode::Unsat
+    );
+    let mut failed = solver.failed_assumptions().to_vec();
+    failed.sort();
+    assert_eq!(failed, vec![lit(-1), lit(-3)]);
+    assert_eq!(solver.solve(), SolverExitCode::Sat);
+}
+
+#[test]
+fn test_push_and_pop_assumptions() {
+    // (1 or 2) and (-1 or 3) and (-2 or 3)
+    let mut solver = Solver::new(VerbosityLevel::OnlyResult);
+    assert!(solver.add_clause_ints(&[1, 2]));
+    assert!(solver.add_clause_ints(&[-1, 3]));
+    assert!(solver.add_clause_ints(&[-2, 3]));
+    let lit = Literal::from_dimacs;
+
+    // Pushed assumptions hold for every call until popped
+    solver.push_assumption(lit(-3));
+    assert_eq!(solver.solve(), SolverExitCode::Unsat);
+    assert_eq!(solver.failed_assumptions(), &[lit(-3)]);
+    assert_eq!(solver.solve(), SolverExitCode::Unsat);
+    assert_eq!(solver.pop_assumption(), Some(lit(-3)));
+    solver.push_assumption(lit(-1));
+    assert_eq!(solver.solve(), SolverExitCode::Sat);
+    assert!(lit(2).is_true(solver.model()));
+
+    // Call assumptions come after the pushed ones
+    assert_eq!(
+        solver.solve_with_assumptions(&[lit(-2)]),
+        SolverExitCode::Unsat
+    );
+    let mut failed = solver.failed_assumptions().to_vec();
+    failed.sort();
+    assert_eq!(failed, vec![lit(-1), lit(-2)]);
+    assert_eq!(solver.pushed_assumptions(), &[lit(-1)]);
+    assert_eq!(solver.pop_assumption(), Some(lit(-1)));
+    assert_eq!(solver.pop_assumption(), None);
+    assert_eq!(
+        solver.solve_with_assumptions(&[lit(-2)]),
+        SolverExitCode::Sat
+    );
+
+    // Learned clauses carry over when assumptions are popped
+    let mut solver = Solver::new(VerbosityLevel::OnlyResult);
+    parse_cnf("res/success/medium_sat.cnf", &mut solver);
+    assert_eq!(solver.solve(), SolverExitCode::Sat);
+    let model_literal = |var| Literal::from_var_with_polarity(var, solver.model()[var].is_true());
+    let (first, second, third) = (model_literal(0), model_literal(1), model_literal(2));
+    for literal in [first, second, !third] {
+        solver.push_assumption(literal);
+    }
+    if solver.solve() == SolverExitCode::Sat {
+        assert!(
+            solver
+                .pushed_assumptions()
+                .iter()
+                .all(|literal| literal.is_true(solver.model()))
+        );
+    }
+    let num_learned = solver.learned_clauses().count();
+    assert_eq!(solver.pop_assumption(), Some(!third));
+    assert_eq!(solver.learned_clauses().count(), num_learned);
+    assert!(
+        solver
+            .trail()
+            .iter()
+            .all(|&literal| solver.decision_level_of(literal.var()) == 0)
+    );
+    assert_eq!(solver.solve(), SolverExitCode::Sat);
+    assert!(first.is_true(solver.model()) && second.is_true(solver.model()));
+
+    // Pushed assumptions may contradict the pure literals of an earlier call
+    let options = SolverOptions {
+        pure_literal_elimination: true,
+        ..SolverOptions::default()
+    };
+    let mut solver = Solver::with_options(VerbosityLevel::OnlyResult, options);
+    assert!(solver.add_clause_ints(&[1, 2]));
+    assert!(solver.add_clause_ints(&[1, 3]));
+    assert_eq!(solver.solve(), SolverExitCode::Sat);
+    assert!(solver.statistics().num_pure_literals > 0);
+    solver.push_assumption(lit(-1));
+    assert_eq!(solver.solve(), SolverExitCode::Sat);
+    assert!(lit(2).is_true(solver.model()) && lit(3).is_true(solver.model()));
+    solver.push_assumption(lit(-2));
+    assert_eq!(solver.solve(), SolverExitCode::Unsat);
+    let mut failed = solver.failed_assumptions().to_vec();
+    failed.sort();
+    assert_eq!(failed, vec![lit(-1), lit(-2)]);
+}
+
+#[test]
+fn test_trail_inspection() {
+    let mut solver = Solver::new(VerbosityLevel::OnlyResult);
+    parse_cnf("res/success/medium_sat.cnf", &mut solver);
+    assert_eq!(solver.solve(), SolverExitCode::Sat);
+
+    // Every variable is on the trail once, ordered by decision level
+    let trail = solver.trail();
+    assert_eq!(trail.len(), solver.num_variables());
+    let mut num_implied = 0;
+    for (i, literal) in trail.iter().enumerate() {
+        assert!(literal.is_true(solver.model()));
+        if let Some(next) = trail.get(i + 1) {
+            assert!(
+                solver.decision_level_of(literal.var()) <= solver.decision_level_of(next.var())
+            );
+        }
+
+        // Reasons imply their first literal from literals earlier on the trail
+        if let Some(reason) = solver.reason_of(literal.var()) {
+            num_implied += 1;
+            let clause = solver.clause(reason);
+            assert_eq!(clause[0], *literal);
+            for other in &clause[1..] {
+                assert!(other.is_false(solver.model()));
+                assert!(trail[..i].contains(&!*other));
+            }
+        }
+    }
+    assert!(num_implied > 0);
+}
+
+#[test]
+fn test_num_free_variables() {
+    let mut solver = Solver::new(VerbosityLevel::OnlyResult);
+    solver.create_variables(3);
+    assert_eq!(solver.num_free_variables(), 3);
+    assert!(solver.add_clause(&[Literal::from_dimacs(2)]));
+    assert_eq!(solver.num_free_variables(), 2);
+}
+
+#[test]
+fn test_memory_usage() {
+    let mut solver = Solver::new(VerbosityLevel::OnlyResult);
+    let empty_usage = solver.memory_usage_bytes();
+    parse_cnf("res/success/medium_sat.cnf", &mut solver);
+    let parsed_usage = solver.memory_usage_bytes();
+    assert!(parsed_usage > empty_usage);
+    assert!(parsed_usage >= solver.num_variables() * size_of::<bool>());
+}
+
+#[test]
+fn test_search_shape_statistics() {
+    let mut solver = Solver::new(VerbosityLevel::OnlyResult);
+    parse_cnf("res/success/big_sat_instance.cnf.xz", &mut solver);
+    assert_eq!(solver.solve(), SolverExitCode::Sat);
+
+    let stats = solver.statistics();
+    assert!(stats.max_decision_level > 0);
+    assert!(stats.max_decision_level <= stats.num_decisions);
+    assert!(stats.max_trail_length <= solver.num_variables());
+    assert!(stats.max_trail_length > stats.max_decision_level);
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn test_statistics_json() {
+    let mut solver = Solver::new(VerbosityLevel::OnlyResult);
+    parse_cnf("res/success/small_sat.cnf", &mut solver);
+    assert_eq!(solver.solve(), SolverExitCode::Sat);
+
+    let json = solver.statistics_json();
+    assert!(json.starts_with("{\"statistics\":{\"num_variables\":3,"));
+    assert!(json.contains("\"conflicts_per_second\":"));
+    assert!(json.contains("\"total_time\":"));
+}
+
+#[test]
+fn test_solve_all_enumerates_every_model() {
+    // (1 or 2 or 3) and (-1 or -2) has 5 models
+    let mut solver = Solver::new(VerbosityLevel::OnlyResult);
+    solver.create_variables(3);
+    let clauses = [
+        vec![
+            Literal::from_var_with_polarity(0, true),
+            Literal::from_var_with_polarity(1, true),
+            Literal::from_var_with_polarity(2, true),
+        ],
+        vec![
+            Literal::from_var_with_polarity(0, false),
+            Literal::from_var_with_polarity(1, false),
+        ],
+    ];
+    for clause in &clauses {
+        assert!(solver.add_clause(clause));
+    }
+
+    let mut models: Vec<Vec<VariableValue>> = Vec::new();
+    let num_models = solver.solve_all(|model| {
+        models.push(Vec::from(model));
+        true
+    });
+    assert_eq!(num_models, 5);
+    for (i, model) in models.iter().enumerate() {
+        assert!(!models[..i].contains(model));
+        for clause in &clauses {
+            assert!(clause.iter().any(|lit| lit.is_true(model)));
+        }
+    }
+}
+
+#[test]
+fn test_solve_all_stops_early() {
+    let mut solver = Solver::new(VerbosityLevel::OnlyResult);
+    parse_cnf("res/success/medium_sat.cnf", &mut solver);
+
+    let num_models = solver.solve_all(|_| false);
+    assert_eq!(num_models, 1);
+}
+
+#[test]
+fn test_count_models() {
+    // (1 or 2 or 3) and (-1 or -2) has 5 models; variable 4 is unconstrained
+    let mut solver = Solver::new(VerbosityLevel::OnlyResult);
+    solver.create_variables(4);
+    assert!(solver.add_clause(&[
+        Literal::from_var_with_polarity(0, true),
+        Literal::from_var_with_polarity(1, true),
+        Literal::from_var_with_polarity(2, true),
+    ]));
+    assert!(solver.add_clause(&[
+        Literal::from_var_with_polarity(0, false),
+        Literal::from_var_with_polarity(1, false),
+    ]));
+    assert_eq!(solver.count_models(), 10);
+
+    // 1 xor 2 xor 3 and (1 or 4) has 2 models with 1 true for each value of
+    // 4, and 2 models with 1 false and 4 true
+    let mut solver = Solver::new(VerbosityLevel::OnlyResult);
+    solver.create_variables(4);
+    let lit = Literal::from_dimacs;
+    assert!(solver.add_xor(&[lit(1), lit(2), lit(3)], true));
+    assert!(solver.add_clause_ints(&[1, 4]));
+    assert_eq!(solver.count_models(), 6);
+}
+
+#[test]
+fn test_count_models_single_model() {
+    let mut solver = Solver::new(VerbosityLevel::OnlyResult);
+    parse_cnf("res/success/small_sat.cnf", &mut solver);
+    assert_eq!(solver.count_models(), 1);
+}
+
+#[test]
+fn test_pure_literal_elimination() {
+    // 1 only occurs positively; 2 and 3 occur in both polarities
+    let clauses = [
+        [
+            Literal::from_var_with_polarity(0, true),
+            Literal::from_var_with_polarity(1, true),
+            Literal::from_var_with_polarity(2, true),
+        ],
+        [
+            Literal::from_var_with_polarity(0, true),
+            Literal::from_var_with_polarity(1, false),
+            Literal::from_var_with_polarity(2, false),
+        ],
+    ];
+    for pure_literal_elimination in [true, false] {
+        let options = SolverOptions {
+            pure_literal_elimination,
+            ..SolverOptions::default()
+        };
+        let mut solver = Solver::with_options(VerbosityLevel::OnlyResult, options);
+        solver.create_variables(3);
+        for clause in &clauses {
+            assert!(solver.add_clause(clause));
+        }
+        assert_eq!(solver.solve(), SolverExitCode::Sat);
+        assert_eq!(
+            solver.statistics().num_pure_literals,
+            usize::from(pure_literal_elimination)
+        );
+        if pure_literal_elimination {
+            assert!(solver.model()[0].is_true());
+        }
+    }
+
+    // Pure literals are not implied, so later solves may contradict them
+    let options = SolverOptions {
+        pure_literal_elimination: true,
+        ..SolverOptions::default()
+    };
+    let mut solver = Solver::with_options(VerbosityLevel::OnlyResult, options);
+    assert!(solver.add_clause_ints(&[1, 2]));
+    assert!(solver.add_clause_ints(&[1, -2, 3]));
+    assert!(solver.add_clause_ints(&[-3, 2]));
+    assert_eq!(solver.solve(), SolverExitCode::Sat);
+    assert!(solver.statistics().num_pure_literals > 0);
+    assert!(solver.add_clause_ints(&[-1]));
+    assert_eq!(solver.solve(), SolverExitCode::Sat);
+    assert!(Literal::from_dimacs(-1).is_true(solver.model()));
+}
+
+#[test]
+fn test_add_clause_ints() {
+    // (1 or -2) and (2 or 3) and (-1 or -3) and (3)
+    let mut solver = Solver::new(VerbosityLevel::OnlyResult);
+    assert!(solver.add_clause_ints(&[1, -2]));
+    assert_eq!(solver.num_variables(), 2);
+    assert!(solver.add_clause_ints(&[2, 3]));
+    assert!(solver.add_clause_ints(&[-1, -3]));
+    assert!(solver.add_clause_ints(&[3]));
+    assert_eq!(solver.num_variables(), 3);
+    assert_eq!(solver.solve(), SolverExitCode::Sat);
+    assert_eq!(
+        solver.model(),
+        &vec![
+            VariableValue::False,
+            VariableValue::False,
+            VariableValue::True
+        ]
+    );
+
+    // Contradicting unit after solving
+    assert!(!solver.add_clause_ints(&[-3]));
+}
+
+#[test]
+fn test_conflicting_unit_clauses() {
+    // The second unit is false on the top level
+    let mut solver = Solver::new(VerbosityLevel::OnlyResult);
+    assert!(solver.add_clause_ints(&[1]));
+    assert!(!solver.add_clause_ints(&[-1]));
+    // Later clauses are rejected, even agreeing units
+    assert!(!solver.add_clause_ints(&[1]));
+    assert_eq!(solver.solve(), SolverExitCode::Unsat);
+
+    // Propagating the unit falsifies a clause
+    let mut solver = Solver::new(VerbosityLevel::OnlyResult);
+    assert!(solver.add_clause_ints(&[-1, 2]));
+    assert!(solver.add_clause_ints(&[-1, -2]));
+    assert!(!solver.add_clause_ints(&[1]));
+    assert_eq!(solver.solve(), SolverExitCode::Unsat);
+
+    // Three units, each pair of which is consistent
+    let mut solver = Solver::new(VerbosityLevel::OnlyResult);
+    assert!(solver.add_clause_ints(&[-1, -2, -3]));
+    assert!(solver.add_clause_ints(&[1]));
+    assert!(solver.add_clause_ints(&[2]));
+    assert!(!solver.add_clause_ints(&[3]));
+    assert_eq!(solver.solve(), SolverExitCode::Unsat);
+}
+
+#[test]
+fn test_binary_implication_cycle() {
+    // Ring of equivalences 0 <-> 1 <-> ... <-> 19 <-> 0
+    let num_variables = 20;
+    let lit = Literal::from_var_with_polarity;
+    let ring = || {
+        let mut solver = Solver::new(VerbosityLevel::OnlyResult);
+        solver.create_variables(num_variables);
+        for var in 0..num_variables {
+            let next = (var + 1) % num_variables;
+            assert!(solver.add_clause(&[lit(var, false), lit(next, true)]));
+            assert!(solver.add_clause(&[lit(var, true), lit(next, false)]));
+        }
+        solver
+    };
+
+    let mut solver = ring();
+    assert_eq!(solver.solve(), SolverExitCode::Sat);
+    let value = solver.model()[0];
+    assert!(solver.model().iter().all(|&other| other == value));
+
+    // Forcing two variables of the ring apart is UNSAT
+    let mut solver = ring();
+    assert!(solver.add_clause(&[lit(0, true), lit(10, true)]));
+    solver.add_clause(&[lit(0, false), lit(10, false)]);
+    assert_eq!(solver.solve(), SolverExitCode::Unsat);
+}
+
+#[test]
+fn test_failed_literal_probing() {
+    // Assigning 1 to false propagates 2 and -2
+    let mut solver = Solver::new(VerbosityLevel::OnlyResult);
+    solver.create_variables(3);
+    for clause in [
+        [
+            Literal::from_var_with_polarity(0, true),
+            Literal::from_var_with_polarity(1, true),
+        ],
+        [
+            Literal::from_var_with_polarity(0, true),
+            Literal::from_var_with_polarity(1, false),
+        ],
+        [
+            Literal::from_var_with_polarity(1, true),
+            Literal::from_var_with_polarity(2, true),
+        ],
+        [
+            Literal::from_var_with_polarity(1, false),
+            Literal::from_var_with_polarity(2, false),
+        ],
+    ] {
+        assert!(solver.add_clause(&clause));
+    }
+    assert!(solver.probe());
+    assert_eq!(solver.statistics().num_failed_literals, 1);
+    assert!(solver.model()[0].is_true());
+    assert_eq!(solver.solve(), SolverExitCode::Sat);
+
+    // Simplification probes only with the option
+    for failed_literal_probing in [false, true] {
+        let options = SolverOptions {
+            failed_literal_probing,
+            ..SolverOptions::default()
+        };
+        let mut solver = Solver::with_options(VerbosityLevel::OnlyResult, options);
+        for clause in [[1, 2], [1, -2], [2, 3], [-2, -3]] {
+            assert!(solver.add_clause_ints(&clause));
+        }
+        assert_eq!(solver.solve(), SolverExitCode::Sat);
+        assert_eq!(
+            solver.statistics().num_failed_literals,
+            usize::from(failed_literal_probing)
+        );
+    }
+}
+
+#[test]
+fn test_failed_literal_probing_unsat() {
+    // Both polarities of 1 fail, so probing derives UNSAT
+    let mut solver = Solver::new(VerbosityLevel::OnlyResult);
+    for clause in [[1, 2], [1, -2], [-1, 3], [-1, -3]] {
+        assert!(solver.add_clause_ints(&clause));
+    }
+    assert!(!solver.probe());
+    assert_eq!(solver.solve(), SolverExitCode::Unsat);
+}
+
+#[test]
+fn test_variable_elimination() {
+    let options = SolverOptions {
+        variable_elimination: true,
+        ..SolverOptions::default()
+    };
+    for file_name in [
+        "res/success/medium_sat.cnf",
+        "res/success/big_sat_instance.cnf.xz",
+    ] {
+        let mut solver = Solver::with_options(VerbosityLevel::OnlyResult, options);
+        parse_cnf(file_name, &mut solver);
+        let mut mock_solver = SolverMock::default();
+        parse_cnf(file_name, &mut mock_solver);
+
+        // Eliminated variables must be reconstructed in the model
+        assert_eq!(solver.solve(), SolverExitCode::Sat);
+        check_model(&solver, &mock_solver);
+        if file_name == "res/success/medium_sat.cnf" {
+            assert!(solver.statistics().num_eliminated_variables > 0);
+        }
+    }
+}
+
+#[test]
+fn test_frozen_variables() {
+    let options = SolverOptions {
+        variable_elimination: true,
+        ..SolverOptions::default()
+    };
+    let mut solver = Solver::with_options(VerbosityLevel::OnlyResult, options);
+    parse_cnf("res/success/medium_sat.cnf", &mut solver);
+    let mut mock_solver = SolverMock::default();
+    parse_cnf("res/success/medium_sat.cnf", &mut mock_solver);
+    for var in 0..solver.num_variables() {
+        solver.freeze(var);
+    }
+
+    assert_eq!(solver.solve(), SolverExitCode::Sat);
+    check_model(&solver, &mock_solver);
+    assert_eq!(solver.statistics().num_eliminated_variables, 0);
+    assert_eq!(solver.statistics().num_pure_literals, 0);
+}
+
+#[test]
+fn test_decision_variables() {
+    // Complete models even if propagation leaves non-decision variables unset
+    for branching in [
+        BranchingHeuristic::Random,
+        BranchingHeuristic::Vsids,
+        BranchingHeuristic::InOrder,
+        BranchingHeuristic::DeterministicVsids,
+    ] {
+        let options = SolverOptions {
+            branching,
+            ..SolverOptions::default()
+        };
+        let mut solver = Solver::with_options(VerbosityLevel::OnlyResult, options);
+        parse_cnf("res/success/medium_sat.cnf", &mut solver);
+        let mut mock_solver = SolverMock::default();
+        parse_cnf("res/success/medium_sat.cnf", &mut mock_solver);
+        let even_vars: Vec<usize> = (0..solver.num_variables()).step_by(2).collect();
+        solver.set_decision_variables(&even_vars);
+
+        assert_eq!(solver.solve(), SolverExitCode::Sat);
+        check_model(&solver, &mock_solver);
+    }
+
+    // Outputs `y_i = y_(i-1) & x_i` of an AND chain are implied by its inputs
+    let num_inputs = 20;
+    let options = SolverOptions {
+        branching: BranchingHeuristic::InOrder,
+        ..SolverOptions::default()
+    };
+    let mut solver = Solver::with_options(VerbosityLevel::OnlyResult, options);
+    let output = |i: usize| i;
+    let input = |i: usize| num_inputs + i;
+    let lit = Literal::from_var_with_polarity;
+    solver.add_clause_incremental(&[lit(output(0), false), lit(input(0), true)]);
+    solver.add_clause_incremental(&[lit(output(0), true), lit(input(0), false)]);
+    for i in 1..num_inputs {
+        solver.add_clause_incremental(&[lit(output(i), false), lit(output(i - 1), true)]);
+        solver.add_clause_incremental(&[lit(output(i), false), lit(input(i), true)]);
+        solver.add_clause_incremental(&[
+            lit(output(i), true),
+            lit(output(i - 1), false),
+            lit(input(i), false),
+        ]);
+    }
+    let inputs: Vec<usize> = (0..num_inputs).map(input).collect();
+    solver.set_decision_variables(&inputs);
+
+    assert_eq!(solver.solve(), SolverExitCode::Sat);
+    assert!(solver.model().iter().all(|value| !value.is_unset()));
+    // One decision per input and a final one finding no variable to branch on
+    assert_eq!(solver.statistics().num_decisions, num_inputs + 1);
+}
+
+#[test]
+fn test_incremental_clause_addition() {
+    // Refine the instance by blocking the values of the first variables not
+    // fixed at the top level in each found model; the results agree with
+    // solving each refinement from scratch
+    let mut solver = Solver::new(VerbosityLevel::OnlyResult);
+    parse_cnf("res/success/medium_sat.cnf", &mut solver);
+    let mut mock_solver = SolverMock::default();
+    parse_cnf("res/success/medium_sat.cnf", &mut mock_solver);
+    for var in 0..solver.num_variables() {
+        solver.freeze(var);
+    }
+    loop {
+        let mut fresh_solver = Solver::new(VerbosityLevel::OnlyResult);
+        fresh_solver.create_variables(mock_solver.num_variables);
+        for clause in &mock_solver.clauses {
+            fresh_solver.add_clause(clause);
+        }
+        let res = solver.solve();
+        assert_eq!(res, fresh_solver.solve());
+        if res == SolverExitCode::Unsat {
+            break;
+        }
+        check_model(&solver, &mock_solver);
+        let blocking_clause: Vec<Literal> = (0..solver.num_variables())
+            .filter(|&var| solver.decision_level_of(var) > 0)
+            .take(8)
+            .map(|var| Literal::from_var_with_polarity(var, !solver.model()[var].is_true()))
+            .collect();
+        solver.add_clause_incremental(&blocking_clause);
+        mock_solver.add_clause(&blocking_clause);
+    }
+
+    // New variables and clauses until UNSAT
+    let mut solver = Solver::new(VerbosityLevel::OnlyResult);
+    let lit = Literal::from_dimacs;
+    assert!(solver.add_clause_incremental(&[lit(1), lit(2)]));
+    solver.freeze(0);
+    solver.freeze(1);
+    assert_eq!(solver.solve(), SolverExitCode::Sat);
+    assert!(solver.add_clause_incremental(&[lit(-1), lit(3)]));
+    assert!(solver.add_clause_incremental(&[lit(-3)]));
+    assert_eq!(solver.solve(), SolverExitCode::Sat);
+    assert_eq!(solver.model().len(), 3);
+    assert!(lit(2).is_true(solver.model()));
+    assert!(!solver.add_clause_incremental(&[lit(-2)]));
+    assert_eq!(solver.solve(), SolverExitCode::Unsat);
+
+    // Without freezing, added clauses may contradict the pure literals of
+    // earlier calls; negate a value fixed on the top level in each round
+    let mut rng = Lcg(5);
+    let options = SolverOptions {
+        pure_literal_elimination: true,
+        ..SolverOptions::default()
+    };
+    let mut solver = Solver::with_options(VerbosityLevel::OnlyResult, options);
+    let mut mock_solver = SolverMock::default();
+    assert!(solver.create_variables(40));
+    assert!(mock_solver.create_variables(40));
+    for _ in 0..100 {
+        let clause: Vec<Literal> = (0..3)
+            .map(|_| Literal::from_var_with_polarity(rng.next(40), rng.next(2) == 0))
+            .collect();
+        solver.add_clause(&clause);
+        mock_solver.add_clause(&clause);
+    }
+    loop {
+        let mut fresh_solver = Solver::new(VerbosityLevel::OnlyResult);
+        fresh_solver.create_variables(mock_solver.num_variables);
+        for clause in &mock_solver.clauses {
+            fresh_solver.add_clause(clause);
+        }
+        let res = solver.solve();
+        assert_eq!(res, fresh_solver.solve());
+        if res == SolverExitCode::Unsat {
+            break;
+        }
+        check_model(&solver, &mock_solver);
+        let Some(var) = (0..solver.num_variables()).find(|&var| {
+            solver.decision_level_of(var) == 0
+                && !mock_solver
+                    .clauses
+                    .iter()
+                    .any(|clause| clause.len() == 1 && clause[0].var() == var)
+        }) else {
+            break;
+        };
+        let clause = [Literal::from_var_with_polarity(
+            var,
+            !solver.model()[var].is_true(),
+        )];
+        solver.add_clause_incremental(&clause);
+        mock_solver.add_clause(&clause);
+    }
+    assert!(solver.statistics().num_pure_literals > 0);
+}
+
+#[test]
+fn test_subsumption() {
+    // (1 or 2) subsumes (1 or 2 or 3) and strengthens (-1 or 2 or 4) to (2 or 4)
+    let options = SolverOptions {
+        subsumption: true,
+        ..SolverOptions::default()
+    };
+    let mut solver = Solver::with_options(VerbosityLevel::OnlyResult, options);
+    solver.create_variables(4);
+    for clause in [
+        vec![
+            Literal::from_var_with_polarity(0, true),
+            Literal::from_var_with_polarity(1, true),
+        ],
+        vec![
+            Literal::from_var_with_polarity(0, true),
+            Literal::from_var_with_polarity(1, true),
+            Literal::from_var_with_polarity(2, true),
+        ],
+        vec![
+            Literal::from_var_with_polarity(0, false),
+            Literal::from_var_with_polarity(1, true),
+            Literal::from_var_with_polarity(3, true),
+        ],
+    ] {
+        assert!(solver.add_clause(&clause));
+    }
+    assert!(solver.preprocess());
+    assert_eq!(solver.statistics().num_subsumed_clauses, 1);
+    assert_eq!(solver.statistics().num_strengthened_clauses, 1);
+    assert_eq!(solver.num_clauses(), 2);
+    assert_eq!(solver.solve(), SolverExitCode::Sat);
+}
+
+#[test]
+fn test_vivify_learned_clauses() {
+    // Deciding -1, -2, and -3 in order learns (3 or 2 or 1)
+    let options = SolverOptions {
+        pure_literal_elimination: false,
+        subsumption: false,
+        branching: BranchingHeuristic::InOrder,
+        ..SolverOptions::default()
+    };
+    let mut solver = Solver::with_options(VerbosityLevel::OnlyResult, options);
+    for clause in [[1, 2, 3, 4], [1, 2, 3, -4]] {
+        assert!(solver.add_clause_ints(&clause));
+    }
+    assert_eq!(solver.solve(), SolverExitCode::Sat);
+    let lit = Literal::from_dimacs;
+    let learned: Vec<Vec<Literal>> = solver.learned_clauses().map(<[_]>::to_vec).collect();
+    assert_eq!(learned, [[lit(3), lit(2), lit(1)]]);
+
+    // -3 now implies 2, so 1 is dropped
+    assert!(solver.add_clause_ints(&[3, 5]));
+    assert!(solver.add_clause_ints(&[-5, 2]));
+    assert!(solver.vivify());
+    assert_eq!(solver.statistics().num_vivified_literals, 1);
+    let learned: Vec<Vec<Literal>> = solver.learned_clauses().map(<[_]>::to_vec).collect();
+    assert_eq!(learned, [[lit(3), lit(2)]]);
+    assert_eq!(solver.solve(), SolverExitCode::Sat);
+}
+
+#[test]
+fn test_vivify_between_restarts() {
+    // Random 3-SAT instance near the threshold that restarts often enough to
+    // vivify learned clauses
+    let mut rng = Lcg(11);
+    let mut mock_solver = SolverMock::default();
+    mock_solver.create_variables(120);
+    let clauses: Vec<Vec<Literal>> = (0..510)
+        .map(|_| {
+            (0..3)
+                .map(|_| Literal::from_var_with_polarity(rng.next(120), rng.next(2) == 0))
+                .collect()
+        })
+        .collect();
+    for clause in &clauses {
+        mock_solver.add_clause(clause);
+    }
+
+    for vivification in [false, true] {
+        let options = SolverOptions {
+            vivification,
+            ..SolverOptions::default()
+        };
+        let mut solver = Solver::with_options(VerbosityLevel::OnlyResult, options);
+        solver.create_variables(120);
+        for clause in &clauses {
+            solver.add_clause(clause);
+        }
+        assert_eq!(solver.solve(), SolverExitCode::Sat);
+
+        // Shortened learned clauses must keep the instance satisfiable
+        assert_eq!(solver.statistics().num_vivified_literals > 0, vivification);
+        check_model(&solver, &mock_solver);
+    }
+}
+
+#[test]
+fn test_max_variables() {
+    let options = SolverOptions {
+        max_variables: 10,
+        ..SolverOptions::default()
+    };
+    let mut solver = Solver::with_options(VerbosityLevel::OnlyResult, options);
+    assert!(solver.create_variables(10));
+    assert!(!solver.create_variables(11));
+    assert!(!solver.create_variables(usize::MAX));
+    assert_eq!(solver.num_variables(), 10);
+    assert!(solver.add_clause_ints(&[1, -10]));
+    assert_eq!(solver.solve(), SolverExitCode::Sat);
+}
+
+#[test]
+fn test_write_simplified_cnf() {
+    let mut solver = Solver::new(VerbosityLevel::OnlyResult);
+    parse_cnf("res/success/medium_sat.cnf", &mut solver);
+    assert!(solver.preprocess());
+    let path = format!("{}/medium_sat_simplified.cnf", env!("CARGO_TARGET_TMPDIR"));
+    let mut file = File::create(&path).expect("temporary file is writable");
+    solver
+        .write_simplified_cnf(&mut file)
+        .expect("temporary file is writable");
+
+    // The simplified instance is smaller and still satisfiable
+    let mut simplified = SolverMock::default();
+    parse_cnf(&path, &mut simplified);
+    assert_eq!(simplified.num_variables, 403);
+    assert!(simplified.num_clauses < 2029);
+    let mut simplified_solver = Solver::new(VerbosityLevel::OnlyResult);
+    parse_cnf(&path, &mut simplified_solver);
+    assert_eq!(simplified_solver.solve(), SolverExitCode::Sat);
+    assert_eq!(solver.solve(), SolverExitCode::Sat);
+}
+
+#[test]
+fn test_blocked_clause_elimination() {
+    let options = SolverOptions {
+        blocked_clause_elimination: true,
+        ..SolverOptions::default()
+    };
+    let mut solver = Solver::with_options(VerbosityLevel::OnlyResult, options);
+    parse_cnf("res/success/big_sat_instance.cnf.xz", &mut solver);
+    let mut mock_solver = SolverMock::default();
+    parse_cnf("res/success/big_sat_instance.cnf.xz", &mut mock_solver);
+    assert_eq!(solver.solve(), SolverExitCode::Sat);
+    assert!(solver.statistics().num_blocked_clauses > 0);
+
+    // The model must also satisfy the removed clauses
+    check_model(&solver, &mock_solver);
+}
+
+/// Solver whose blocked clause elimination removes (1 or 2) and (-1 or -2),
+/// both blocked on a literal of 1
+fn solver_with_blocked_clauses() -> Solver {
+    let options = SolverOptions {
+        pure_literal_elimination: false,
+        blocked_clause_elimination: true,
+        ..SolverOptions::default()
+    };
+    let mut solver = Solver::with_options(VerbosityLevel::OnlyResult, options);
+    assert!(solver.add_clause_ints(&[1, 2]));
+    assert!(solver.add_clause_ints(&[-1, -2]));
+    assert_eq!(solver.solve(), SolverExitCode::Sat);
+    assert_eq!(solver.statistics().num_blocked_clauses, 2);
+    solver
+}
+
+#[test]
+fn test_add_clause_after_blocked_clause_elimination() {
+    // Only the blocking literals are flipped to repair the model, so 2 may
+    // occur in added clauses
+    let mut solver = solver_with_blocked_clauses();
+    assert!(solver.add_clause_ints(&[2]));
+    assert_eq!(solver.solve(), SolverExitCode::Sat);
+    let lit = Literal::from_dimacs;
+    assert!(lit(2).is_true(solver.model()));
+    assert!(lit(-1).is_true(solver.model()));
+}
+
+#[test]
+#[should_panic(expected = "variable 0 blocks a clause removed by blocked clause elimination")]
+fn test_add_clause_over_blocking_variable() {
+    let mut solver = solver_with_blocked_clauses();
+    solver.add_clause_ints(&[1, 3]);
+}
+
+#[test]
+fn test_vsids_activity_rescaling() {
+    let options = SolverOptions {
+        branching: BranchingHeuristic::Vsids,
+        ..SolverOptions::default()
+    };
+    let mut solver = Solver::with_options(VerbosityLevel::OnlyResult, options);
+
+    // Eight pigeons do not fit into seven holes
+    let num_holes = 7;
+    let pigeon_in_hole = |pigeon: usize, hole: usize| (num_holes * pigeon + hole + 1) as i32;
+    for pigeon in 0..=num_holes {
+        let holes: Vec<i32> = (0..num_holes)
+            .map(|hole| pigeon_in_hole(pigeon, hole))
+            .collect();
+        assert!(solver.add_clause_ints(&holes));
+    }
+    for hole in 0..num_holes {
+        for first in 0..=num_holes {
+            for second in first + 1..=num_holes {
+                assert!(solver.add_clause_ints(&[
+                    -pigeon_in_hole(first, hole),
+                    -pigeon_in_hole(second, hole)
+                ]));
+            }
+        }
+    }
+    assert_eq!(solver.solve(), SolverExitCode::Unsat);
+
+    // Without rescaling, the increment of 0.95^-n overflows after this many conflicts
+    assert!(solver.statistics().num_total_conflicts > 5_000);
+    assert!(
+        solver
+            .variable_activities()
+            .iter()
+            .all(|activity| activity.is_finite() && *activity <= 1e100)
+    );
+    assert!(
+        solver
+            .variable_activities()
+            .iter()
+            .any(|&activity| activity > 0.0)
+    );
+}
+
+#[test]
+fn test_clause_inspection() {
+    let mut solver = Solver::new(VerbosityLevel::OnlyResult);
+    assert!(solver.add_clause_ints(&[1, -2]));
+    assert!(solver.add_clause_ints(&[2, 3, -4]));
+    let clauses: Vec<String> = solver
+        .original_clauses()
+        .map(|clause| DimacsClause(clause).to_string())
+        .collect();
+    assert_eq!(clauses, ["1 -2 0", "2 3 -4 0"]);
+    assert_eq!(solver.learned_clauses().count(), 0);
+
+    // Learned clauses can be inspected after search
+    let mut solver = Solver::new(VerbosityLevel::OnlyResult);
+    parse_cnf("res/success/medium_sat.cnf", &mut solver);
+    assert_eq!(solver.solve(), SolverExitCode::Sat);
+    assert_eq!(
+        solver.learned_clauses().count(),
+        solver.statistics().num_learned_clauses
+    );
+    assert_eq!(solver.original_clauses().count(), solver.num_clauses());
+    assert!(
+        solver
+            .learned_clauses()
+            .all(|clause| clause.len() >= 2 && DimacsClause(clause).to_string().ends_with(" 0"))
+    );
+}
+
+#[test]
+fn test_propagation_budget() {
+    let mut solver = Solver::new(VerbosityLevel::OnlyResult);
+    parse_cnf("res/success/medium_sat.cnf", &mut solver);
+    assert_eq!(
+        solver.solve_with_propagation_budget(10_000),
+        SolverExitCode::Unknown
+    );
+
+    // The budget is checked before every decision, so it is hardly exceeded
+    let num_propagations = solver.statistics().num_propagations;
+    assert!((10_000..11_000).contains(&num_propagations));
+
+    // The budget only holds for a single call
+    assert_eq!(solver.solve(), SolverExitCode::Sat);
+    assert!(solver.statistics().num_propagations > 11_000);
+}
+
+#[test]
+fn test_unit_learning_with_restarts() {
+    // Random 3-SAT around the threshold learns many units, and small budgets
+    // and Luby restarts return to the top level often
+    const NUM_VARS: usize = 60;
+    let mut rng = Lcg(23);
+    let mut num_units = 0;
+    for round in 0..40 {
+        let clauses: Vec<Vec<Literal>> = (0..256)
+            .map(|_| {
+                (0..3)
+                    .map(|_| Literal::from_var_with_polarity(rng.next(NUM_VARS), rng.next(2) == 0))
+                    .collect()
+            })
+            .collect();
+        let options = SolverOptions {
+            branching: [BranchingHeuristic::Vsids, BranchingHeuristic::Random][round % 2],
+            random_seed: round as u64,
+            ..SolverOptions::default()
+        };
+        let mut solver = Solver::with_options(VerbosityLevel::OnlyResult, options);
+        assert!(solver.create_variables(NUM_VARS));
+        let mut is_unsat = false;
+        for clause in &clauses {
+            is_unsat |= !solver.add_clause(clause);
+        }
+
+        let mut result = SolverExitCode::Unknown;
+        while !is_unsat && result == SolverExitCode::Unknown {
+            result = solver.solve_with_propagation_budget(100);
+            if result != SolverExitCode::Unknown {
+                break;
+            }
+
+            // No clause is unit or falsified under the top-level assignment,
+            // so every top-level literal was propagated
+            let top_level: Vec<Literal> = solver
+                .trail()
+                .iter()
+                .copied()
+                .filter(|literal| solver.decision_level_of(literal.var()) == 0)
+                .collect();
+            let is_top_level_true = |literal: &Literal| top_level.contains(literal);
+            for clause in solver.original_clauses().chain(solver.learned_clauses()) {
+                let num_open = clause
+                    .iter()
+                    .filter(|&&literal| !is_top_level_true(&!literal))
+                    .count();
+                assert!(
+                    clause.iter().any(is_top_level_true) || num_open >= 2,
+                    "round {round}: clause {clause:?} not propagated"
+                );
+            }
+        }
+        if result == SolverExitCode::Sat {
+            let model: Vec<bool> = solver.model().iter().map(|value| value.is_true()).collect();
+            assert!(
+                clauses
+                    .iter()
+                    .all(|clause| clause.iter().any(|lit| model[lit.var()] == lit.polarity()))
+            );
+        }
+        num_units += solver.statistics().num_top_level_units;
+    }
+    assert!(num_units > 0);
+}
+
+#[test]
+fn test_in_order_branching() {
+    // In-order branching ignores the seed and the random phase probability
+    let solve_with_seed = |random_seed| {
+        let options = SolverOptions {
+            branching: BranchingHeuristic::InOrder,
+            random_seed,
+            random_phase_prob: 0.5,
+            ..SolverOptions::default()
+        };
+        let mut solver = Solver::with_options(VerbosityLevel::OnlyResult, options);
+        parse_cnf("res/success/medium_sat.cnf", &mut solver);
+        assert_eq!(solver.solve(), SolverExitCode::Sat);
+        let mut mock_solver = SolverMock::default();
+        parse_cnf("res/success/medium_sat.cnf", &mut mock_solver);
+        check_model(&solver, &mock_solver);
+        (solver.model().clone(), *solver.statistics())
+    };
+    assert_eq!(solve_with_seed(1), solve_with_seed(2));
+}
+
+#[test]
+fn test_deterministic_vsids_branching() {
+    // Deterministic VSIDS ignores the seed and the random phase probability,
+    // so that the event logs of runs with different seeds are identical
+    let event_log_with_seed = |random_seed| {
+        let options = SolverOptions {
+            branching: BranchingHeuristic::DeterministicVsids,
+            random_seed,
+            random_phase_prob: 0.5,
+            ..SolverOptions::default()
+        };
+        let mut solver = Solver::with_options(VerbosityLevel::OnlyResult, options);
+        let log = Rc::new(RefCell::new(String::new()));
+        let observed = Rc::clone(&log);
+        solver.set_event_callback(move |event| {
+            observed.borrow_mut().push_str(&format!("{event:?}\n"));
+        });
+        parse_cnf("res/success/big_sat_instance.cnf.xz", &mut solver);
+        assert_eq!(solver.solve(), SolverExitCode::Sat);
+        let mut mock_solver = SolverMock::default();
+        parse_cnf("res/success/big_sat_instance.cnf.xz", &mut mock_solver);
+        check_model(&solver, &mock_solver);
+        let log = log.borrow().clone();
+        (log, solver.trail().to_vec())
+    };
+    let (log, trail) = event_log_with_seed(1);
+    assert!(log.contains("Conflict"));
+    assert_eq!((log, trail), event_log_with_seed(2));
+}
+
+#[test]
+fn test_search_snapshot() {
+    let mut solver = Solver::new(VerbosityLevel::OnlyResult);
+    parse_cnf("res/success/medium_sat.cnf", &mut solver);
+    // Unit clauses are assigned while parsing
+    assert_eq!(
+        solver.snapshot(),
+        SearchSnapshot {
+            trail_length: solver.num_assigned(),
+            ..SearchSnapshot::default()
+        }
+    );
+
+    // All variables are assigned in a model
+    assert_eq!(solver.solve(), SolverExitCode::Sat);
+    let snapshot = solver.snapshot();
+    assert_eq!(snapshot.trail_length, solver.num_assigned());
+    assert_eq!(snapshot.trail_length, solver.trail().len());
+    assert_eq!(
+        snapshot.num_conflicts,
+        solver.statistics().num_total_conflicts
+    );
+    assert_eq!(
+        snapshot.num_learned_clauses,
+        solver.statistics().num_learned_clauses
+    );
+    assert!(snapshot.decision_level > 0);
+}
+
+#[test]
+fn test_top_level_units() {
+    // The unit clause implies all three variables
+    let mut solver = Solver::new(VerbosityLevel::OnlyResult);
+    parse_cnf("res/success/small_sat.cnf", &mut solver);
+    assert_eq!(solver.solve(), SolverExitCode::Sat);
+    assert_eq!(solver.statistics().num_top_level_units, 3);
+
+    // Learned units count as well
+    let mut solver = Solver::new(VerbosityLevel::OnlyResult);
+    parse_cnf("res/success/medium_sat.cnf", &mut solver);
+    let num_parsed_units = solver.statistics().num_top_level_units;
+    assert_eq!(num_parsed_units, solver.num_assigned());
+    assert_eq!(solver.solve(), SolverExitCode::Sat);
+    assert!(solver.statistics().num_top_level_units >= num_parsed_units);
+
+    // Pure literals are no facts, so repeated calls do not count them again
+    let options = SolverOptions {
+        pure_literal_elimination: true,
+        ..SolverOptions::default()
+    };
+    let mut solver = Solver::with_options(VerbosityLevel::OnlyResult, options);
+    assert!(solver.add_clause_ints(&[1, 2, 3]));
+    assert!(solver.add_clause_ints(&[1, -2, -3]));
+    assert!(solver.add_clause_ints(&[2, -3]));
+    for _ in 0..4 {
+        assert_eq!(solver.solve(), SolverExitCode::Sat);
+        assert!(solver.statistics().num_pure_literals > 0);
+        assert_eq!(solver.statistics().num_top_level_units, 0);
+    }
+}
+
+#[test]
+fn test_unused_variables() {
+    let mut solver = Solver::new(VerbosityLevel::OnlyResult);
+    assert!(solver.create_variables(6));
+    assert!(solver.add_clause_ints(&[1, -4]));
+    // Satisfied clauses still count as occurrences
+    assert!(solver.add_clause_ints(&[1]));
+    assert!(solver.add_clause_ints(&[1, 2]));
+    assert!(solver.add_xor(&[Literal::from_dimacs(5), Literal::from_dimacs(6)], true));
+    assert!(solver.var_occurs(0));
+    assert!(solver.var_occurs(1));
+    assert!(!solver.var_occurs(2));
+    assert!(solver.var_occurs(5));
+    assert_eq!(solver.unused_variables(), vec![2]);
+
+    // Solving does not change the occurrences
+    assert_eq!(solver.solve(), SolverExitCode::Sat);
+    assert_eq!(solver.unused_variables(), vec![2]);
+}
+
+#[test]
+fn test_restart_blocking() {
+    // Satisfiable random 3-SAT instance needing more than 10000 conflicts
+    let mut rng = Lcg(9);
+    let num_variables = 150;
+    let clauses: Vec<Vec<Literal>> = (0..600)
+        .map(|_| {
+            (0..3)
+                .map(|_| Literal::from_var_with_polarity(rng.next(num_variables), rng.next(2) == 0))
+                .collect()
+        })
+        .collect();
+
+    for restart_blocking in [false, true] {
+        let options = SolverOptions {
+            restart_blocking,
+            ..SolverOptions::default()
+        };
+        let mut solver = Solver::with_options(VerbosityLevel::OnlyResult, options);
+        assert!(solver.create_variables(num_variables));
+        for clause in &clauses {
+            assert!(solver.add_clause(clause));
+        }
+        assert_eq!(solver.solve(), SolverExitCode::Sat);
+        assert!(
+            clauses
+                .iter()
+                .all(|clause| clause.iter().any(|lit| lit.is_true(solver.model())))
+        );
+        assert_eq!(
+            solver.statistics().num_blocked_restarts > 0,
+            restart_blocking
+        );
+    }
+}
+
+/// Random clauses of 2 to 4 literals over `num_vars` variables; the number
+/// of clauses is around the satisfiability threshold, so both results are common
+fn random_clauses(num_vars: usize) -> impl Strategy<Value = Vec<Vec<Literal>>> {
+    let literal = (0..num_vars, any::<bool>())
+        .prop_map(|(var, polarity)| Literal::from_var_with_polarity(var, polarity));
+    prop::collection::vec(prop::collection::vec(literal, 2..5), 10..60)
+}
+
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(300))]
+
+    #[test]
+    fn test_random_instances_against_brute_force(
+        clauses in random_clauses(12),
+        config in 0..54_usize,
+        random_seed in any::<u64>(),
+    ) {
+        const NUM_VARS: usize = 12;
+        let is_satisfied_by = |model: &[bool]| {
+            clauses
+                .iter()
+                .all(|clause| clause.iter().any(|lit| model[lit.var()] == lit.polarity()))
+        };
+        let satisfiable = (0..1_usize << NUM_VARS).any(|bits| {
+            let model: Vec<bool> = (0..NUM_VARS).map(|var| bits >> var & 1 == 1).collect();
+            is_satisfied_by(&model)
+        });
+
+        // Vary the options that change propagation and conflict analysis
+        let options = SolverOptions {
+            branching: [
+                BranchingHeuristic::Random,
+                BranchingHeuristic::Vsids,
+                BranchingHeuristic::InOrder,
+            ][config % 3],
+            learning_scheme: [
+                LearningScheme::FirstUip,
+                LearningScheme::LastUip,
+                LearningScheme::AllDecisions,
+            ][config / 3 % 3],
+            minimization: [
+                MinimizationLevel::None,
+                MinimizationLevel::Local,
+                MinimizationLevel::Recursive,
+            ][config / 9 % 3],
+            variable_elimination: config / 27 == 0,
+            blocked_clause_elimination: config % 2 == 0,
+            random_seed,
+            ..SolverOptions::default()
+        };
+        let mut solver = Solver::with_options(VerbosityLevel::OnlyResult, options);
+        prop_assert!(solver.create_variables(NUM_VARS));
+        let mut result = SolverExitCode::Unknown;
+        for clause in &clauses {
+            if !solver.add_clause(clause) {
+                result = SolverExitCode::Unsat;
+            }
+        }
+        if result == SolverExitCode::Unknown {
+            result = solver.solve();
+        }
+
+        if satisfiable {
+            prop_assert_eq!(result, SolverExitCode::Sat);
+            let model: Vec<bool> = solver.model().iter().map(|value| value.is_true()).collect();
+            prop_assert!(is_satisfied_by(&model));
+        } else {
+            prop_assert_eq!(result, SolverExitCode::Unsat);
+        }
+    }
+}
+
+#[test]
+fn test_minimize_model() {
+    // Either 1 or both 2 and 3 are irrelevant
+    let mut solver = Solver::new(VerbosityLevel::OnlyResult);
+    assert!(solver.add_clause_ints(&[1, 2]));
+    assert!(solver.add_clause_ints(&[1, 3]));
+    assert_eq!(solver.solve(), SolverExitCode::Sat);
+    let partial_model = solver.minimize_model();
+    assert!(
+        partial_model == [Some(true), None, None]
+            || partial_model == [None, Some(true), Some(true)]
+    );
+
+    // Partial models satisfy all original clauses, also eliminated ones
+    for variable_elimination in [false, true] {
+        let options = SolverOptions {
+            variable_elimination,
+            blocked_clause_elimination: variable_elimination,
+            ..SolverOptions::default()
+        };
+        let mut solver = Solver::with_options(VerbosityLevel::OnlyResult, options);
+        parse_cnf("res/success/big_sat_instance.cnf.xz", &mut solver);
+        let mut mock_solver = SolverMock::default();
+        parse_cnf("res/success/big_sat_instance.cnf.xz", &mut mock_solver);
+        assert_eq!(solver.solve(), SolverExitCode::Sat);
+        let partial_model = solver.minimize_model();
+        assert!(partial_model.iter().any(Option::is_none));
+        for clause in &mock_solver.clauses {
+            assert!(
+                clause
+                    .iter()
+                    .any(|lit| partial_model[lit.var()] == Some(lit.polarity()))
+            );
+        }
+    }
+}
+
+#[test]
+fn test_propagate_assumptions() {
+    let lit = Literal::from_dimacs;
+    let mut solver = Solver::new(VerbosityLevel::OnlyResult);
+    assert!(solver.add_clause_ints(&[-1, 2]));
+    assert!(solver.add_clause_ints(&[-2, 3]));
+    assert!(solver.add_clause_ints(&[-3, -4, 5]));
+    assert!(solver.add_clause_ints(&[-5, -6]));
+
+    // Implications only, without the assumptions themselves
+    assert_eq!(solver.propagate_assumptions(&[]), Ok(Vec::new()));
+    assert_eq!(
+        solver.propagate_assumptions(&[lit(1)]),
+        Ok(vec![lit(2), lit(3)])
+    );
+    assert_eq!(
+        solver.propagate_assumptions(&[lit(1), lit(4)]),
+        Ok(vec![lit(2), lit(3), lit(5), lit(-6)])
+    );
+    assert_eq!(solver.num_assigned(), 0);
+
+    // Conflicts return the falsified clause
+    let conflict = solver
+        .propagate_assumptions(&[lit(6), lit(1), lit(4)])
+        .unwrap_err();
+    let mut conflict_clause = solver.clause(conflict).to_vec();
+    conflict_clause.sort_unstable();
+    assert!(conflict_clause == [lit(-5), lit(-6)] || conflict_clause == [lit(-3), lit(-4), lit(5)]);
+
+    // An assumption falsified by earlier ones returns the reason of its negation
+    let conflict = solver
+        .propagate_assumptions(&[lit(1), lit(-3)])
+        .unwrap_err();
+    assert_eq!(solver.clause(conflict), [lit(3), lit(-2)]);
+    assert_eq!(solver.num_assigned(), 0);
+
+    // The solver stays usable
+    assert_eq!(solver.solve(), SolverExitCode::Sat);
+}
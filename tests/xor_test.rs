@@ -0,0 +1,190 @@
+use nanosat_rs::{
+    parsing::ClauseReceiver,
+    solver::{Solver, SolverExitCode, VerbosityLevel, literal::Literal},
+};
+
+use crate::common::Lcg;
+
+mod common;
+
+/// Random instance of XOR rows and clauses over `num_vars` variables
+struct Instance {
+    xors: Vec<(Vec<Literal>, bool)>,
+    clauses: Vec<Vec<Literal>>,
+}
+
+impl Instance {
+    fn random(rng: &mut Lcg, num_vars: usize, num_xors: usize, num_clauses: usize) -> Self {
+        let mut random_literals = |len: usize| -> Vec<Literal> {
+            (0..len)
+                .map(|_| Literal::from_var_with_polarity(rng.next(num_vars), rng.next(2) == 0))
+                .collect()
+        };
+        let xors = (0..num_xors)
+            .map(|i| (random_literals(2 + i % 4), i % 3 == 0))
+            .collect();
+        let clauses = (0..num_clauses).map(|_| random_literals(3)).collect();
+        Self { xors, clauses }
+    }
+
+    fn is_satisfied_by(&self, model: &[bool]) -> bool {
+        let value = |literal: &Literal| model[literal.var()] == literal.polarity();
+        self.xors.iter().all(|(lits, rhs)| {
+            lits.iter().filter(|lit| value(lit)).count() % 2 == usize::from(*rhs)
+        }) && self.clauses.iter().all(|clause| clause.iter().any(value))
+    }
+
+    fn solver(&self, num_vars: usize) -> Solver {
+        let mut solver = Solver::new(VerbosityLevel::OnlyResult);
+        solver.create_variables(num_vars);
+        for clause in &self.clauses {
+            let lits: Vec<i32> = clause.iter().map(Literal::to_dimacs).collect();
+            solver.add_clause_ints(&lits);
+        }
+        for (lits, rhs) in &self.xors {
+            solver.add_xor(lits, *rhs);
+        }
+        solver
+    }
+}
+
+#[test]
+fn test_xor_contradiction() {
+    // (1 xor 2) and (2 xor 3) and (1 xor 3) sum up to 0 == 1
+    let mut solver = Solver::new(VerbosityLevel::OnlyResult);
+    let lit = Literal::from_dimacs;
+    assert!(solver.add_xor(&[lit(1), lit(2)], true));
+    assert!(solver.add_xor(&[lit(2), lit(3)], true));
+    assert!(solver.add_xor(&[lit(1), lit(3)], true));
+    assert_eq!(solver.solve(), SolverExitCode::Unsat);
+}
+
+#[test]
+fn test_xor_with_negated_literals() {
+    // (-1 xor 2) is true iff 1 and 2 are equal
+    let mut solver = Solver::new(VerbosityLevel::OnlyResult);
+    let lit = Literal::from_dimacs;
+    assert!(solver.add_xor(&[lit(-1), lit(2)], true));
+    assert!(solver.add_clause_ints(&[1]));
+    assert_eq!(solver.solve(), SolverExitCode::Sat);
+    assert!(lit(2).is_true(solver.model()));
+
+    // Duplicate variables cancel out; (2 xor 2 xor 3) is true iff 3 is true
+    assert!(solver.add_xor(&[lit(2), lit(2), lit(3)], true));
+    assert_eq!(solver.solve(), SolverExitCode::Sat);
+    assert!(lit(3).is_true(solver.model()));
+    assert!(!solver.add_xor(&[lit(3), lit(3)], true));
+    assert_eq!(solver.solve(), SolverExitCode::Unsat);
+}
+
+#[test]
+fn test_xor_against_brute_force() {
+    const NUM_VARS: usize = 10;
+    let mut rng = Lcg(7);
+    for round in 0..200 {
+        let instance = Instance::random(&mut rng, NUM_VARS, 2 + round % 7, 10 + round % 30);
+        let satisfiable = (0..1_usize << NUM_VARS).any(|bits| {
+            let model: Vec<bool> = (0..NUM_VARS).map(|var| bits >> var & 1 == 1).collect();
+            instance.is_satisfied_by(&model)
+        });
+
+        let mut solver = instance.solver(NUM_VARS);
+        if satisfiable {
+            assert_eq!(solver.solve(), SolverExitCode::Sat);
+            let model: Vec<bool> = solver.model().iter().map(|value| value.is_true()).collect();
+            assert!(instance.is_satisfied_by(&model));
+        } else {
+            assert_eq!(solver.solve(), SolverExitCode::Unsat);
+        }
+    }
+}
+
+#[test]
+fn test_xor_propagation_during_search() {
+    // A larger parity system needs XOR propagation beyond the top level
+    const NUM_VARS: usize = 60;
+    let mut rng = Lcg(3);
+    let hidden: Vec<bool> = (0..NUM_VARS).map(|_| rng.next(2) == 0).collect();
+    let mut instance = Instance::random(&mut rng, NUM_VARS, 40, 0);
+    for (lits, rhs) in &mut instance.xors {
+        *rhs = lits
+            .iter()
+            .filter(|lit| hidden[lit.var()] == lit.polarity())
+            .count()
+            % 2
+            == 1;
+    }
+    while instance.clauses.len() < 150 {
+        let clause = Instance::random(&mut rng, NUM_VARS, 0, 1).clauses.remove(0);
+        if clause.iter().any(|lit| hidden[lit.var()] == lit.polarity()) {
+            instance.clauses.push(clause);
+        }
+    }
+
+    let mut solver = instance.solver(NUM_VARS);
+    assert_eq!(solver.solve(), SolverExitCode::Sat);
+    let model: Vec<bool> = solver.model().iter().map(|value| value.is_true()).collect();
+    assert!(instance.is_satisfied_by(&model));
+}
+
+/// Solves a satisfiable instance of `num_xors` rows over `xor_len` literals
+/// and `num_clauses` clauses; the rows imply literals in many conflicts, and
+/// each of their reasons is a new clause
+fn check_xor_reasons_are_not_duplicated(
+    seed: u64,
+    num_vars: usize,
+    num_xors: usize,
+    xor_len: usize,
+    num_clauses: usize,
+) {
+    let mut rng = Lcg(seed);
+    let hidden: Vec<bool> = (0..num_vars).map(|_| rng.next(2) == 0).collect();
+    let mut instance = Instance::random(&mut rng, num_vars, 0, 0);
+    for _ in 0..num_xors {
+        let lits: Vec<Literal> = (0..xor_len)
+            .map(|_| Literal::from_var_with_polarity(rng.next(num_vars), rng.next(2) == 0))
+            .collect();
+        let rhs = lits
+            .iter()
+            .filter(|lit| hidden[lit.var()] == lit.polarity())
+            .count()
+            % 2
+            == 1;
+        instance.xors.push((lits, rhs));
+    }
+    while instance.clauses.len() < num_clauses {
+        let clause = Instance::random(&mut rng, num_vars, 0, 1).clauses.remove(0);
+        if clause.iter().any(|lit| hidden[lit.var()] == lit.polarity()) {
+            instance.clauses.push(clause);
+        }
+    }
+
+    let mut solver = instance.solver(num_vars);
+    assert_eq!(solver.solve(), SolverExitCode::Sat);
+    let model: Vec<bool> = solver.model().iter().map(|value| value.is_true()).collect();
+    assert!(instance.is_satisfied_by(&model));
+    let mut learned: Vec<Vec<Literal>> = solver
+        .learned_clauses()
+        .map(|clause| {
+            let mut clause = clause.to_vec();
+            clause.sort_unstable();
+            clause
+        })
+        .collect();
+    let num_learned = learned.len();
+    learned.sort_unstable();
+    learned.dedup();
+    assert!(num_learned > 0);
+    assert_eq!(learned.len(), num_learned);
+}
+
+#[test]
+fn test_xor_reasons_are_not_duplicated() {
+    check_xor_reasons_are_not_duplicated(7, 40, 25, 6, 90);
+}
+
+#[test]
+#[ignore = "takes minutes in debug builds"]
+fn test_xor_reasons_are_not_duplicated_large() {
+    check_xor_reasons_are_not_duplicated(11, 80, 50, 8, 200);
+}